@@ -0,0 +1,743 @@
+use crate::requests::ResponseParts;
+use bytes::{Bytes, BytesMut};
+use futures_util::StreamExt;
+use log::{debug, warn};
+use rquest::header::{
+	HeaderMap, HeaderName, HeaderValue, AGE, CACHE_CONTROL, CONTENT_LENGTH, DATE, ETAG, EXPIRES,
+	IF_MATCH, IF_MODIFIED_SINCE, IF_NONE_MATCH, IF_RANGE, IF_UNMODIFIED_SINCE, LAST_MODIFIED, RANGE, VARY,
+};
+use rquest::{Method, Request, StatusCode, Version};
+use std::collections::HashMap;
+use std::fs;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+/// Status codes that are cacheable by default (RFC 9110 §15.1).
+const CACHEABLE_STATUS_CODES: [u16; 11] = [200, 203, 204, 300, 301, 308, 404, 405, 410, 414, 501];
+
+/// Prefix of entries stored on disk, which is changed whenever their format changes.
+const DISK_MAGIC: &[u8; 8] = b"KICACHE2";
+
+/// Prefix of the files on disk listing the `Vary` header names of a URL's stored responses.
+const VARY_MAGIC: &[u8; 8] = b"KIVARY1\n";
+
+/// The maximum amount of variants stored for a single URL, ie. for different `Accept-Language` values.
+const MAX_VARIANTS: usize = 8;
+
+/// Used to give each temporary file a unique name, so that concurrent writes of the same entry do not corrupt it.
+static NEXT_TEMP_FILE: AtomicU64 = AtomicU64::new(0);
+
+/// Config for caching responses natively.
+#[derive(Debug, Clone)]
+pub struct CacheConfig {
+	/// The maximum total size of response bodies kept in memory.
+	pub max_memory_size: u64,
+	/// The directory to persist entries in, if any.
+	pub directory: Option<PathBuf>,
+	/// The maximum total size of the files in [directory].
+	pub max_disk_size: u64,
+}
+
+/// How a response was produced by the cache.
+/// This must be kept in sync with the `CACHE_*` constants in `NativeEngine`.
+#[repr(i32)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CacheStatus {
+	/// The cache is disabled or the request could not be cached.
+	Uncached = 0,
+	/// The response was received from the network, and stored if it was cacheable.
+	Miss = 1,
+	/// The response was served from the cache without a network request.
+	Hit = 2,
+	/// The cached response was served after the server confirmed it is still valid.
+	Revalidated = 3,
+}
+
+/// A private HTTP cache (RFC 9111) storing responses in memory, and optionally on disk.
+/// Responses with a `Vary` header are stored as separate variants of their URL, keyed by the request headers it names.
+pub struct ResponseCache {
+	config: CacheConfig,
+	memory: Mutex<MemoryStore>,
+}
+
+/// The parts of a request that are relevant to the cache, captured before it is sent.
+pub struct CacheRequest {
+	method: Method,
+	url: String,
+	headers: HeaderMap,
+	/// Whether a stored response must be revalidated even if it is fresh.
+	no_cache: bool,
+	/// Whether the response may be stored.
+	no_store: bool,
+}
+
+/// The result of looking up a request in the cache.
+pub enum CacheLookup {
+	Fresh(Arc<CachedResponse>),
+	/// A stored response that has to be revalidated before it can be used.
+	Stale(Arc<CachedResponse>),
+	Miss,
+}
+
+/// A stored response alongside what is needed to determine whether it can be reused.
+pub struct CachedResponse {
+	url: String,
+	status: StatusCode,
+	version: Version,
+	headers: HeaderMap,
+	/// The values of the request headers named by the response's `Vary` header.
+	vary: Vec<(HeaderName, Option<HeaderValue>)>,
+	/// When the response was received.
+	response_time: SystemTime,
+	body: Bytes,
+}
+
+impl ResponseCache {
+	pub fn new(config: CacheConfig) -> Self {
+		if let Some(directory) = &config.directory {
+			if let Err(err) = fs::create_dir_all(directory) {
+				warn!("Failed to create cache directory {directory:?}: {err}");
+			}
+		}
+
+		Self { config, memory: Mutex::new(MemoryStore::default()) }
+	}
+
+	/// Captures the parts of a request that are relevant to the cache,
+	/// or [None] if the request bypasses the cache entirely.
+	pub fn request(&self, request: &Request) -> Option<CacheRequest> {
+		let headers = request.headers();
+
+		// Conditional and range requests are left up to the caller, as their responses are not complete representations
+		let conditional = [IF_NONE_MATCH, IF_MODIFIED_SINCE, IF_MATCH, IF_UNMODIFIED_SINCE, IF_RANGE, RANGE]
+			.iter()
+			.any(|name| headers.contains_key(name));
+		if conditional || *request.method() == Method::HEAD {
+			return None;
+		}
+
+		let cache_control = CacheControl::parse(headers);
+		Some(CacheRequest {
+			method: request.method().clone(),
+			url: request.url().to_string(),
+			headers: headers.clone(),
+			no_cache: cache_control.no_cache || cache_control.max_age == Some(Duration::ZERO),
+			no_store: cache_control.no_store,
+		})
+	}
+
+	/// Looks up the stored response for a request.
+	pub async fn lookup(&self, request: &CacheRequest) -> CacheLookup {
+		if request.method != Method::GET || request.no_store {
+			return CacheLookup::Miss;
+		}
+
+		let Some(entry) = self.get(&request.url, &request.headers).await else { return CacheLookup::Miss };
+
+		if !request.no_cache && entry.is_fresh() {
+			CacheLookup::Fresh(entry)
+		} else if entry.has_validators() {
+			CacheLookup::Stale(entry)
+		} else {
+			CacheLookup::Miss
+		}
+	}
+
+	/// Handles the response to a request that was looked up in the cache, storing it if possible.
+	/// [stale]: The stored response that was being revalidated, if any.
+	pub fn handle_response(
+		self: &Arc<Self>,
+		request: &CacheRequest,
+		stale: Option<Arc<CachedResponse>>,
+		response: ResponseParts,
+	) -> (ResponseParts, CacheStatus) {
+		// A response to an unsafe method invalidates the stored response of its URL (RFC 9111 §4.4)
+		if !request.method.is_safe() {
+			if !response.status.is_client_error() && !response.status.is_server_error() {
+				self.remove(&request.url);
+			}
+			return (response, CacheStatus::Uncached);
+		}
+
+		if let Some(stale) = stale {
+			if response.status == StatusCode::NOT_MODIFIED {
+				let entry = Arc::new(stale.revalidated(&response.headers));
+				self.insert(entry.clone());
+				return (entry.to_parts(), CacheStatus::Revalidated);
+			}
+		}
+
+		if request.method != Method::GET || !self.is_storable(request, &response) {
+			return (response, CacheStatus::Miss);
+		}
+
+		// The body is stored once it has been fully read by the JVM
+		let cache = self.clone();
+		let entry = CachedResponse {
+			url: request.url.clone(),
+			status: response.status,
+			version: response.version,
+			headers: response.headers.clone(),
+			vary: vary_values(&vary_names(&response.headers), &request.headers),
+			response_time: SystemTime::now(),
+			body: Bytes::new(),
+		};
+		let body = tee_body(response.body, self.max_entry_size(), move |body| {
+			cache.insert(Arc::new(CachedResponse { body, ..entry }));
+		});
+
+		(ResponseParts { body, ..response }, CacheStatus::Miss)
+	}
+
+	fn is_storable(&self, request: &CacheRequest, response: &ResponseParts) -> bool {
+		let cache_control = CacheControl::parse(&response.headers);
+		let vary_any = response.headers.get_all(VARY).iter()
+			.filter_map(|value| value.to_str().ok())
+			.any(|value| value.split(',').any(|name| name.trim() == "*"));
+
+		!request.no_store
+			&& !cache_control.no_store
+			&& !vary_any
+			&& CACHEABLE_STATUS_CODES.contains(&response.status.as_u16())
+			&& (freshness_lifetime(&response.headers, SystemTime::now()).is_some()
+			|| response.headers.contains_key(ETAG)
+			|| response.headers.contains_key(LAST_MODIFIED))
+	}
+
+	/// Bodies larger than this are not stored.
+	fn max_entry_size(&self) -> usize {
+		let max_disk_size = self.config.directory.as_ref().map_or(0, |_| self.config.max_disk_size);
+		self.config.max_memory_size.max(max_disk_size) as usize
+	}
+
+	async fn get(&self, url: &str, request_headers: &HeaderMap) -> Option<Arc<CachedResponse>> {
+		let cached = self.memory.lock().expect("cache lock poisoned").get(url, request_headers);
+		if cached.is_some() {
+			return cached;
+		}
+
+		// The variant to read depends on the request headers named by the `Vary` header of the stored responses
+		let names = match tokio::fs::read(self.vary_path(url)?).await {
+			Ok(data) => decode_vary_names(&data)?,
+			Err(_) => return None,
+		};
+		let vary = vary_values(&names, request_headers);
+		let entry = match tokio::fs::read(self.entry_path(url, &vary)?).await {
+			Ok(data) => CachedResponse::decode(&data).filter(|entry| entry.url == url && entry.matches_vary(request_headers))?,
+			Err(_) => return None,
+		};
+		let entry = Arc::new(entry);
+
+		self.memory.lock().expect("cache lock poisoned")
+			.insert(entry.clone(), self.config.max_memory_size);
+		Some(entry)
+	}
+
+	fn insert(&self, entry: Arc<CachedResponse>) {
+		debug!("Storing response for {} in cache", entry.url);
+
+		self.memory.lock().expect("cache lock poisoned")
+			.insert(entry.clone(), self.config.max_memory_size);
+
+		if let (Some(vary_path), Some(path)) = (self.vary_path(&entry.url), self.entry_path(&entry.url, &entry.vary)) {
+			let directory = self.config.directory.clone().unwrap();
+			let max_disk_size = self.config.max_disk_size;
+
+			// Avoid blocking the thread reading the body with file IO
+			tokio::task::spawn_blocking(move || {
+				let names: Vec<_> = entry.vary.iter().map(|(name, _)| name.clone()).collect();
+				let result = write_atomically(&vary_path, &encode_vary_names(&names))
+					.and_then(|_| write_atomically(&path, &entry.encode()));
+				if let Err(err) = result {
+					warn!("Failed to write cache entry {path:?}: {err}");
+				}
+				evict_disk(&directory, max_disk_size);
+			});
+		}
+	}
+
+	fn remove(&self, url: &str) {
+		self.memory.lock().expect("cache lock poisoned").remove(url);
+
+		// Every variant is removed, since a later response could otherwise make stale variants reachable again
+		if let Some(directory) = self.config.directory.clone() {
+			let prefix = format!("{:016x}", fnv1a(url.as_bytes()));
+			// Avoid blocking the thread handling the response with file IO
+			tokio::task::spawn_blocking(move || {
+				let Ok(entries) = fs::read_dir(&directory) else { return };
+				for entry in entries.filter_map(|entry| entry.ok()) {
+					if entry.file_name().to_string_lossy().starts_with(&prefix) {
+						let _ = fs::remove_file(entry.path());
+					}
+				}
+			});
+		}
+	}
+
+	/// The file listing the `Vary` header names of a URL's stored responses.
+	fn vary_path(&self, url: &str) -> Option<PathBuf> {
+		let directory = self.config.directory.as_ref()?;
+		Some(directory.join(format!("{:016x}", fnv1a(url.as_bytes()))))
+	}
+
+	/// The file storing a single variant of a URL.
+	fn entry_path(&self, url: &str, vary: &[(HeaderName, Option<HeaderValue>)]) -> Option<PathBuf> {
+		let directory = self.config.directory.as_ref()?;
+		Some(directory.join(format!("{:016x}-{:016x}", fnv1a(url.as_bytes()), variant_hash(vary))))
+	}
+}
+
+impl CachedResponse {
+	fn matches_vary(&self, request_headers: &HeaderMap) -> bool {
+		self.vary.iter().all(|(name, value)| request_headers.get(name) == value.as_ref())
+	}
+
+	fn has_validators(&self) -> bool {
+		self.headers.contains_key(ETAG) || self.headers.contains_key(LAST_MODIFIED)
+	}
+
+	fn is_fresh(&self) -> bool {
+		if CacheControl::parse(&self.headers).no_cache {
+			return false;
+		}
+
+		match freshness_lifetime(&self.headers, self.response_time) {
+			Some(lifetime) => self.current_age() < lifetime,
+			None => false,
+		}
+	}
+
+	/// The age of this response (RFC 9111 §4.2.3), including the time it spent in other caches.
+	fn current_age(&self) -> Duration {
+		let age_value = self.headers.get(AGE)
+			.and_then(|value| value.to_str().ok())
+			.and_then(|value| value.parse::<u64>().ok())
+			.map_or(Duration::ZERO, Duration::from_secs);
+		let apparent_age = header_date(&self.headers, &DATE)
+			.and_then(|date| self.response_time.duration_since(date).ok())
+			.unwrap_or(Duration::ZERO);
+		let resident_time = SystemTime::now()
+			.duration_since(self.response_time)
+			.unwrap_or(Duration::ZERO);
+
+		apparent_age.max(age_value) + resident_time
+	}
+
+	/// Adds the validators of this response to a request in order to revalidate it.
+	pub fn add_validators(&self, request: &mut Request) {
+		if let Some(etag) = self.headers.get(ETAG) {
+			request.headers_mut().insert(IF_NONE_MATCH, etag.clone());
+		}
+		if let Some(last_modified) = self.headers.get(LAST_MODIFIED) {
+			request.headers_mut().insert(IF_MODIFIED_SINCE, last_modified.clone());
+		}
+	}
+
+	/// Creates a copy of this response updated with the headers of a `304 Not Modified` response (RFC 9111 §3.2).
+	fn revalidated(&self, headers: &HeaderMap) -> Self {
+		let mut updated = self.headers.clone();
+		for name in headers.keys().filter(|name| **name != CONTENT_LENGTH) {
+			updated.remove(name);
+			for value in headers.get_all(name) {
+				updated.append(name.clone(), value.clone());
+			}
+		}
+
+		Self {
+			url: self.url.clone(),
+			status: self.status,
+			version: self.version,
+			headers: updated,
+			vary: self.vary.clone(),
+			response_time: SystemTime::now(),
+			body: self.body.clone(),
+		}
+	}
+
+	/// Creates a response from this stored response, with its `Age` header updated.
+	pub fn to_parts(&self) -> ResponseParts {
+		let mut headers = self.headers.clone();
+		headers.insert(AGE, HeaderValue::from(self.current_age().as_secs()));
+
+		let body = self.body.clone();
+		ResponseParts {
+			version: self.version,
+			status: self.status,
+			headers,
+			body: futures_util::stream::once(async move { Ok(body) }).boxed(),
+			trailers: None,
+		}
+	}
+
+	fn encode(&self) -> Vec<u8> {
+		let mut data = Vec::with_capacity(self.body.len() + 1024);
+		data.extend_from_slice(DISK_MAGIC);
+		put_bytes(&mut data, self.url.as_bytes());
+		data.extend_from_slice(&self.status.as_u16().to_be_bytes());
+		data.push(version_code(self.version));
+		let response_time = self.response_time.duration_since(UNIX_EPOCH).unwrap_or_default();
+		data.extend_from_slice(&response_time.as_secs().to_be_bytes());
+
+		data.extend_from_slice(&(self.vary.len() as u32).to_be_bytes());
+		for (name, value) in &self.vary {
+			put_bytes(&mut data, name.as_str().as_bytes());
+			match value {
+				Some(value) => {
+					data.push(1);
+					put_bytes(&mut data, value.as_bytes());
+				}
+				None => data.push(0),
+			}
+		}
+
+		data.extend_from_slice(&(self.headers.len() as u32).to_be_bytes());
+		for (name, value) in &self.headers {
+			put_bytes(&mut data, name.as_str().as_bytes());
+			put_bytes(&mut data, value.as_bytes());
+		}
+
+		put_bytes(&mut data, &self.body);
+		data
+	}
+
+	fn decode(data: &[u8]) -> Option<Self> {
+		let mut reader = Reader(data);
+		if reader.take(DISK_MAGIC.len())? != DISK_MAGIC {
+			return None;
+		}
+
+		let url = String::from_utf8(reader.bytes()?.to_vec()).ok()?;
+		let status = StatusCode::from_u16(u16::from_be_bytes(reader.take(2)?.try_into().ok()?)).ok()?;
+		let version = version_from_code(reader.take(1)?[0])?;
+		let response_time = UNIX_EPOCH + Duration::from_secs(u64::from_be_bytes(reader.take(8)?.try_into().ok()?));
+
+		let vary_count = reader.u32()?;
+		let mut vary = Vec::with_capacity(vary_count.min(64) as usize);
+		for _ in 0..vary_count {
+			let name = HeaderName::from_bytes(reader.bytes()?).ok()?;
+			let value = match reader.take(1)?[0] {
+				0 => None,
+				_ => Some(HeaderValue::from_bytes(reader.bytes()?).ok()?),
+			};
+			vary.push((name, value));
+		}
+
+		let header_count = reader.u32()?;
+		let mut headers = HeaderMap::with_capacity(header_count.min(256) as usize);
+		for _ in 0..header_count {
+			let name = HeaderName::from_bytes(reader.bytes()?).ok()?;
+			let value = HeaderValue::from_bytes(reader.bytes()?).ok()?;
+			headers.append(name, value);
+		}
+
+		let body = Bytes::copy_from_slice(reader.bytes()?);
+		Some(Self { url, status, version, headers, vary, response_time, body })
+	}
+}
+
+/// Stored responses kept in memory, evicting the least recently used ones once over the size limit.
+#[derive(Default)]
+struct MemoryStore {
+	/// The stored variants of each URL, alongside when each was last used.
+	entries: HashMap<String, Vec<(Arc<CachedResponse>, u64)>>,
+	size: u64,
+	/// Incremented on every access, in order to track when each entry was last used.
+	clock: u64,
+}
+
+impl MemoryStore {
+	fn get(&mut self, url: &str, request_headers: &HeaderMap) -> Option<Arc<CachedResponse>> {
+		self.clock += 1;
+		let (entry, last_used) = self.entries.get_mut(url)?
+			.iter_mut()
+			.find(|(entry, _)| entry.matches_vary(request_headers))?;
+		*last_used = self.clock;
+		Some(entry.clone())
+	}
+
+	fn insert(&mut self, entry: Arc<CachedResponse>, max_size: u64) {
+		if let Some(index) = self.find_variant(&entry.url, &entry.vary) {
+			self.remove_variant(&entry.url, index);
+		}
+
+		let entry_size = entry.body.len() as u64;
+		if entry_size > max_size { return; }
+
+		if self.entries.get(&entry.url).is_some_and(|variants| variants.len() >= MAX_VARIANTS) {
+			let url = entry.url.clone();
+			if let Some((_, index)) = self.least_recently_used(|variant_url| *variant_url == url) {
+				self.remove_variant(&url, index);
+			}
+		}
+
+		while self.size + entry_size > max_size {
+			let Some((url, index)) = self.least_recently_used(|_| true) else { break };
+			self.remove_variant(&url, index);
+		}
+
+		self.clock += 1;
+		self.size += entry_size;
+		self.entries.entry(entry.url.clone()).or_default().push((entry, self.clock));
+	}
+
+	/// Removes every variant of a URL.
+	fn remove(&mut self, url: &str) {
+		if let Some(variants) = self.entries.remove(url) {
+			self.size -= variants.iter().map(|(entry, _)| entry.body.len() as u64).sum::<u64>();
+		}
+	}
+
+	fn find_variant(&self, url: &str, vary: &[(HeaderName, Option<HeaderValue>)]) -> Option<usize> {
+		self.entries.get(url)?.iter().position(|(entry, _)| entry.vary == vary)
+	}
+
+	/// Finds the least recently used variant of the URLs matching [filter].
+	fn least_recently_used(&self, filter: impl Fn(&String) -> bool) -> Option<(String, usize)> {
+		self.entries.iter()
+			.filter(|(url, _)| filter(url))
+			.flat_map(|(url, variants)| variants.iter().enumerate().map(move |(index, (_, last_used))| (url, index, *last_used)))
+			.min_by_key(|(_, _, last_used)| *last_used)
+			.map(|(url, index, _)| (url.clone(), index))
+	}
+
+	fn remove_variant(&mut self, url: &str, index: usize) {
+		let Some(variants) = self.entries.get_mut(url) else { return };
+		let (entry, _) = variants.remove(index);
+		self.size -= entry.body.len() as u64;
+		if variants.is_empty() {
+			self.entries.remove(url);
+		}
+	}
+}
+
+/// The cache directives of a `Cache-Control` header (RFC 9111 §5.2) that are used by this cache.
+#[derive(Default)]
+struct CacheControl {
+	no_store: bool,
+	no_cache: bool,
+	max_age: Option<Duration>,
+}
+
+impl CacheControl {
+	fn parse(headers: &HeaderMap) -> Self {
+		let mut cache_control = Self::default();
+
+		let directives = headers.get_all(CACHE_CONTROL).iter()
+			.filter_map(|value| value.to_str().ok())
+			.flat_map(|value| value.split(','));
+		for directive in directives {
+			let (name, value) = match directive.split_once('=') {
+				Some((name, value)) => (name.trim(), Some(value.trim().trim_matches('"'))),
+				None => (directive.trim(), None),
+			};
+
+			match &*name.to_ascii_lowercase() {
+				"no-store" => cache_control.no_store = true,
+				"no-cache" => cache_control.no_cache = true,
+				"max-age" => cache_control.max_age = value
+					.and_then(|value| value.parse::<u64>().ok())
+					.map(Duration::from_secs),
+				_ => {}
+			}
+		}
+
+		cache_control
+	}
+}
+
+/// How long a response is fresh for after it was generated (RFC 9111 §4.2.1), or [None] if it cannot be determined.
+fn freshness_lifetime(headers: &HeaderMap, response_time: SystemTime) -> Option<Duration> {
+	if let Some(max_age) = CacheControl::parse(headers).max_age {
+		return Some(max_age);
+	}
+
+	let date = header_date(headers, &DATE).unwrap_or(response_time);
+	if headers.contains_key(EXPIRES) {
+		// Invalid dates represent a time in the past
+		let expires = header_date(headers, &EXPIRES).unwrap_or(UNIX_EPOCH);
+		return Some(expires.duration_since(date).unwrap_or(Duration::ZERO));
+	}
+
+	// Heuristic freshness of 10% of the time since the last modification (RFC 9111 §4.2.2)
+	let last_modified = header_date(headers, &LAST_MODIFIED)?;
+	Some(date.duration_since(last_modified).unwrap_or(Duration::ZERO) / 10)
+}
+
+fn header_date(headers: &HeaderMap, name: &HeaderName) -> Option<SystemTime> {
+	let value = headers.get(name)?.to_str().ok()?;
+	httpdate::parse_http_date(value).ok()
+}
+
+/// Gets the names of the request headers that are named by the `Vary` header of a response.
+fn vary_names(response_headers: &HeaderMap) -> Vec<HeaderName> {
+	response_headers.get_all(VARY).iter()
+		.filter_map(|value| value.to_str().ok())
+		.flat_map(|value| value.split(','))
+		.filter_map(|name| HeaderName::from_bytes(name.trim().as_bytes()).ok())
+		.collect()
+}
+
+/// Gets the values of request headers named by a `Vary` header, which identify the variant of a stored response.
+fn vary_values(names: &[HeaderName], request_headers: &HeaderMap) -> Vec<(HeaderName, Option<HeaderValue>)> {
+	names.iter()
+		.map(|name| (name.clone(), request_headers.get(name).cloned()))
+		.collect()
+}
+
+/// A stable hash of the `Vary` values of a variant for naming its file.
+fn variant_hash(vary: &[(HeaderName, Option<HeaderValue>)]) -> u64 {
+	let mut data = Vec::new();
+	for (name, value) in vary {
+		put_bytes(&mut data, name.as_str().as_bytes());
+		match value {
+			Some(value) => {
+				data.push(1);
+				put_bytes(&mut data, value.as_bytes());
+			}
+			None => data.push(0),
+		}
+	}
+	fnv1a(&data)
+}
+
+fn encode_vary_names(names: &[HeaderName]) -> Vec<u8> {
+	let mut data = VARY_MAGIC.to_vec();
+	for name in names {
+		data.extend_from_slice(name.as_str().as_bytes());
+		data.push(b'\n');
+	}
+	data
+}
+
+fn decode_vary_names(data: &[u8]) -> Option<Vec<HeaderName>> {
+	let names = data.strip_prefix(VARY_MAGIC)?;
+	names.split(|byte| *byte == b'\n')
+		.filter(|name| !name.is_empty())
+		.map(|name| HeaderName::from_bytes(name).ok())
+		.collect()
+}
+
+/// Wraps a body stream in order to collect its chunks as they are read,
+/// calling [on_complete] with the full body if it ended successfully without exceeding [limit].
+fn tee_body(
+	body: futures_util::stream::BoxStream<'static, Result<Bytes, rquest::Error>>,
+	limit: usize,
+	on_complete: impl FnOnce(Bytes) + Send + 'static,
+) -> futures_util::stream::BoxStream<'static, Result<Bytes, rquest::Error>> {
+	let state = (body, Some(BytesMut::new()), Some(on_complete));
+
+	futures_util::stream::unfold(state, move |(mut body, mut collected, mut on_complete)| async move {
+		match body.next().await {
+			Some(Ok(chunk)) => {
+				if collected.as_ref().is_some_and(|buf| buf.len() + chunk.len() > limit) {
+					collected = None;
+				}
+				if let Some(buf) = &mut collected {
+					buf.extend_from_slice(&chunk);
+				}
+				Some((Ok(chunk), (body, collected, on_complete)))
+			}
+			Some(Err(err)) => Some((Err(err), (body, None, None))),
+			None => {
+				if let (Some(buf), Some(on_complete)) = (collected.take(), on_complete.take()) {
+					on_complete(buf.freeze());
+				}
+				None
+			}
+		}
+	}).boxed()
+}
+
+/// Writes a file by renaming a temporary file over it, so that partially written entries are never read.
+fn write_atomically(path: &Path, data: &[u8]) -> std::io::Result<()> {
+	let temp_path = path.with_extension(format!("{}.{}.tmp", std::process::id(), NEXT_TEMP_FILE.fetch_add(1, Ordering::Relaxed)));
+	let mut file = fs::File::create(&temp_path)?;
+	file.write_all(data)?;
+	file.sync_all()?;
+	fs::rename(&temp_path, path)
+}
+
+/// Deletes the least recently written entries in a cache directory until it is under the size limit.
+fn evict_disk(directory: &PathBuf, max_size: u64) {
+	let Ok(entries) = fs::read_dir(directory) else { return };
+
+	let mut files: Vec<(PathBuf, u64, SystemTime)> = entries
+		.filter_map(|entry| entry.ok())
+		// Files that are still being written are left alone
+		.filter(|entry| entry.path().extension().is_none_or(|extension| extension != "tmp"))
+		.filter_map(|entry| {
+			let metadata = entry.metadata().ok()?;
+			Some((entry.path(), metadata.len(), metadata.modified().ok()?))
+		})
+		.collect();
+
+	let mut size: u64 = files.iter().map(|(_, len, _)| len).sum();
+	if size <= max_size { return; }
+
+	files.sort_by_key(|(_, _, modified)| *modified);
+	for (path, len, _) in files {
+		if size <= max_size { break; }
+		if fs::remove_file(&path).is_ok() {
+			size -= len;
+		}
+	}
+}
+
+/// A stable hash of cache keys for naming files, which unlike [std::hash::DefaultHasher] does not change between builds.
+fn fnv1a(data: &[u8]) -> u64 {
+	data.iter().fold(0xcbf29ce484222325, |hash, byte| (hash ^ *byte as u64).wrapping_mul(0x100000001b3))
+}
+
+fn version_code(version: Version) -> u8 {
+	match version {
+		Version::HTTP_09 => 9,
+		Version::HTTP_10 => 10,
+		Version::HTTP_11 => 11,
+		Version::HTTP_2 => 20,
+		Version::HTTP_3 => 30,
+		_ => 0,
+	}
+}
+
+fn version_from_code(code: u8) -> Option<Version> {
+	match code {
+		9 => Some(Version::HTTP_09),
+		10 => Some(Version::HTTP_10),
+		11 => Some(Version::HTTP_11),
+		20 => Some(Version::HTTP_2),
+		30 => Some(Version::HTTP_3),
+		_ => None,
+	}
+}
+
+fn put_bytes(data: &mut Vec<u8>, bytes: &[u8]) {
+	data.extend_from_slice(&(bytes.len() as u64).to_be_bytes());
+	data.extend_from_slice(bytes);
+}
+
+/// Reads values written by [CachedResponse::encode], returning [None] for truncated data.
+struct Reader<'a>(&'a [u8]);
+
+impl<'a> Reader<'a> {
+	fn take(&mut self, length: usize) -> Option<&'a [u8]> {
+		if length > self.0.len() { return None; }
+		let (taken, rest) = self.0.split_at(length);
+		self.0 = rest;
+		Some(taken)
+	}
+
+	fn u32(&mut self) -> Option<u32> {
+		Some(u32::from_be_bytes(self.take(4)?.try_into().ok()?))
+	}
+
+	fn bytes(&mut self) -> Option<&'a [u8]> {
+		let length = u64::from_be_bytes(self.take(8)?.try_into().ok()?);
+		self.take(usize::try_from(length).ok()?)
+	}
+}
+
+#[cfg(test)]
+mod tests;
@@ -0,0 +1,280 @@
+use super::*;
+use rquest::header::{ACCEPT_ENCODING, ACCEPT_LANGUAGE};
+
+fn headers(pairs: &[(&str, &str)]) -> HeaderMap {
+	let mut headers = HeaderMap::new();
+	for (name, value) in pairs {
+		headers.append(HeaderName::from_bytes(name.as_bytes()).unwrap(), HeaderValue::from_str(value).unwrap());
+	}
+	headers
+}
+
+fn http_date(time: SystemTime) -> String {
+	httpdate::fmt_http_date(time)
+}
+
+fn response(url: &str, vary: &[(HeaderName, Option<&str>)], body: &str) -> CachedResponse {
+	CachedResponse {
+		url: url.to_owned(),
+		status: StatusCode::OK,
+		version: Version::HTTP_11,
+		headers: headers(&[("cache-control", "max-age=60")]),
+		vary: vary.iter()
+			.map(|(name, value)| (name.clone(), value.map(|value| HeaderValue::from_str(value).unwrap())))
+			.collect(),
+		response_time: SystemTime::now(),
+		body: Bytes::copy_from_slice(body.as_bytes()),
+	}
+}
+
+fn entry(url: &str, vary: &[(HeaderName, Option<&str>)], body: &str) -> Arc<CachedResponse> {
+	Arc::new(response(url, vary, body))
+}
+
+#[test]
+fn parses_cache_control_directives() {
+	let cache_control = CacheControl::parse(&headers(&[
+		("cache-control", "public, Max-Age=\"120\""),
+		("cache-control", "NO-CACHE"),
+	]));
+
+	assert!(cache_control.no_cache);
+	assert!(!cache_control.no_store);
+	assert_eq!(cache_control.max_age, Some(Duration::from_secs(120)));
+}
+
+#[test]
+fn ignores_invalid_max_age() {
+	let cache_control = CacheControl::parse(&headers(&[("cache-control", "max-age=soon, no-store")]));
+
+	assert!(cache_control.no_store);
+	assert_eq!(cache_control.max_age, None);
+}
+
+#[test]
+fn freshness_prefers_max_age_over_expires() {
+	let now = SystemTime::now();
+	let headers = headers(&[
+		("cache-control", "max-age=30"),
+		("date", http_date(now).as_str()),
+		("expires", http_date(now + Duration::from_secs(3600)).as_str()),
+	]);
+
+	assert_eq!(freshness_lifetime(&headers, now), Some(Duration::from_secs(30)));
+}
+
+#[test]
+fn freshness_from_expires_is_relative_to_date() {
+	let date = UNIX_EPOCH + Duration::from_secs(1_700_000_000);
+	let headers = headers(&[
+		("date", http_date(date).as_str()),
+		("expires", http_date(date + Duration::from_secs(600)).as_str()),
+	]);
+
+	// The response time is ignored when the response has a date
+	assert_eq!(freshness_lifetime(&headers, SystemTime::now()), Some(Duration::from_secs(600)));
+}
+
+#[test]
+fn invalid_expires_is_already_expired() {
+	let headers = headers(&[("expires", "0")]);
+
+	assert_eq!(freshness_lifetime(&headers, SystemTime::now()), Some(Duration::ZERO));
+}
+
+#[test]
+fn heuristic_freshness_is_a_tenth_of_the_last_modified_age() {
+	let date = UNIX_EPOCH + Duration::from_secs(1_700_000_000);
+	let headers = headers(&[
+		("date", http_date(date).as_str()),
+		("last-modified", http_date(date - Duration::from_secs(1000)).as_str()),
+	]);
+
+	assert_eq!(freshness_lifetime(&headers, date), Some(Duration::from_secs(100)));
+}
+
+#[test]
+fn no_freshness_without_any_information() {
+	assert_eq!(freshness_lifetime(&HeaderMap::new(), SystemTime::now()), None);
+}
+
+#[test]
+fn current_age_includes_age_header_and_resident_time() {
+	let response_time = SystemTime::now() - Duration::from_secs(10);
+	let stored = CachedResponse {
+		headers: headers(&[("age", "100"), ("date", http_date(response_time).as_str())]),
+		response_time,
+		..response("https://example.com/", &[], "")
+	};
+
+	let age = stored.current_age();
+	assert!(age >= Duration::from_secs(110) && age < Duration::from_secs(112), "{age:?}");
+}
+
+#[test]
+fn current_age_uses_apparent_age_from_date() {
+	let response_time = SystemTime::now();
+	let stored = CachedResponse {
+		headers: headers(&[("date", http_date(response_time - Duration::from_secs(50)).as_str())]),
+		response_time,
+		..response("https://example.com/", &[], "")
+	};
+
+	let age = stored.current_age();
+	assert!(age >= Duration::from_secs(50) && age < Duration::from_secs(52), "{age:?}");
+}
+
+#[test]
+fn no_cache_responses_are_never_fresh() {
+	let stored = CachedResponse {
+		headers: headers(&[("cache-control", "max-age=60, no-cache")]),
+		..response("https://example.com/", &[], "")
+	};
+
+	assert!(!stored.is_fresh());
+	assert!(entry("https://example.com/", &[], "").is_fresh());
+}
+
+#[test]
+fn matches_vary_values() {
+	let response_headers = headers(&[("vary", "Accept-Encoding, accept-language")]);
+	let names = vary_names(&response_headers);
+	assert_eq!(names, vec![ACCEPT_ENCODING, ACCEPT_LANGUAGE]);
+
+	let request_headers = headers(&[("accept-encoding", "gzip")]);
+	let stored = CachedResponse {
+		vary: vary_values(&names, &request_headers),
+		..response("https://example.com/", &[], "")
+	};
+
+	assert!(stored.matches_vary(&request_headers));
+	assert!(!stored.matches_vary(&headers(&[("accept-encoding", "br")])));
+	assert!(!stored.matches_vary(&headers(&[("accept-encoding", "gzip"), ("accept-language", "en")])));
+	assert!(!stored.matches_vary(&HeaderMap::new()));
+}
+
+#[test]
+fn variant_hash_depends_on_values() {
+	let gzip = [(ACCEPT_ENCODING, Some(HeaderValue::from_static("gzip")))];
+	let br = [(ACCEPT_ENCODING, Some(HeaderValue::from_static("br")))];
+	let missing = [(ACCEPT_ENCODING, None)];
+
+	assert_eq!(variant_hash(&gzip), variant_hash(&gzip.clone()));
+	assert_ne!(variant_hash(&gzip), variant_hash(&br));
+	assert_ne!(variant_hash(&missing), variant_hash(&[]));
+}
+
+#[test]
+fn memory_store_evicts_least_recently_used() {
+	let mut store = MemoryStore::default();
+	store.insert(entry("https://a/", &[], "aaaa"), 8);
+	store.insert(entry("https://b/", &[], "bbbb"), 8);
+
+	// Using the first entry makes the second one the least recently used
+	assert!(store.get("https://a/", &HeaderMap::new()).is_some());
+	store.insert(entry("https://c/", &[], "cccc"), 8);
+
+	assert!(store.get("https://a/", &HeaderMap::new()).is_some());
+	assert!(store.get("https://b/", &HeaderMap::new()).is_none());
+	assert!(store.get("https://c/", &HeaderMap::new()).is_some());
+	assert_eq!(store.size, 8);
+}
+
+#[test]
+fn memory_store_skips_entries_over_the_limit() {
+	let mut store = MemoryStore::default();
+	store.insert(entry("https://a/", &[], "aaaa"), 8);
+	store.insert(entry("https://b/", &[], "too large"), 8);
+
+	assert!(store.get("https://a/", &HeaderMap::new()).is_some());
+	assert!(store.get("https://b/", &HeaderMap::new()).is_none());
+	assert_eq!(store.size, 4);
+}
+
+#[test]
+fn memory_store_replaces_same_variant() {
+	let mut store = MemoryStore::default();
+	store.insert(entry("https://a/", &[], "old"), 100);
+	store.insert(entry("https://a/", &[], "new!"), 100);
+
+	assert_eq!(&*store.get("https://a/", &HeaderMap::new()).unwrap().body, b"new!");
+	assert_eq!(store.entries["https://a/"].len(), 1);
+	assert_eq!(store.size, 4);
+}
+
+#[test]
+fn memory_store_keeps_variants_separately() {
+	let mut store = MemoryStore::default();
+	store.insert(entry("https://a/", &[(ACCEPT_LANGUAGE, Some("en"))], "hello"), 100);
+	store.insert(entry("https://a/", &[(ACCEPT_LANGUAGE, Some("de"))], "hallo"), 100);
+
+	let english = store.get("https://a/", &headers(&[("accept-language", "en")])).unwrap();
+	let german = store.get("https://a/", &headers(&[("accept-language", "de")])).unwrap();
+	assert_eq!(&*english.body, b"hello");
+	assert_eq!(&*german.body, b"hallo");
+	assert!(store.get("https://a/", &headers(&[("accept-language", "fr")])).is_none());
+
+	store.remove("https://a/");
+	assert!(store.entries.is_empty());
+	assert_eq!(store.size, 0);
+}
+
+#[test]
+fn memory_store_limits_variants_per_url() {
+	let mut store = MemoryStore::default();
+	for index in 0..=MAX_VARIANTS {
+		let language = index.to_string();
+		store.insert(entry("https://a/", &[(ACCEPT_LANGUAGE, Some(&*language))], "x"), 100);
+	}
+
+	assert_eq!(store.entries["https://a/"].len(), MAX_VARIANTS);
+	assert!(store.get("https://a/", &headers(&[("accept-language", "0")])).is_none());
+	assert!(store.get("https://a/", &headers(&[("accept-language", "1")])).is_some());
+}
+
+#[test]
+fn disk_entries_round_trip() {
+	let original = CachedResponse {
+		url: "https://example.com/path?query".to_owned(),
+		status: StatusCode::NOT_FOUND,
+		version: Version::HTTP_2,
+		headers: headers(&[("etag", "\"abc\""), ("set-cookie", "a=1"), ("set-cookie", "b=2")]),
+		vary: vec![
+			(ACCEPT_ENCODING, Some(HeaderValue::from_static("gzip"))),
+			(ACCEPT_LANGUAGE, None),
+		],
+		response_time: UNIX_EPOCH + Duration::from_secs(1_700_000_000),
+		body: Bytes::from_static(b"\x00binary body\xFF"),
+	};
+
+	let decoded = CachedResponse::decode(&original.encode()).unwrap();
+
+	assert_eq!(decoded.url, original.url);
+	assert_eq!(decoded.status, original.status);
+	assert_eq!(decoded.version, original.version);
+	assert_eq!(decoded.headers, original.headers);
+	assert_eq!(decoded.vary, original.vary);
+	assert_eq!(decoded.response_time, original.response_time);
+	assert_eq!(decoded.body, original.body);
+}
+
+#[test]
+fn rejects_invalid_disk_entries() {
+	let encoded = entry("https://example.com/", &[], "body").encode();
+
+	assert!(CachedResponse::decode(&encoded[..encoded.len() - 1]).is_none());
+	assert!(CachedResponse::decode(&encoded[..DISK_MAGIC.len()]).is_none());
+
+	let mut wrong_magic = encoded.clone();
+	wrong_magic[DISK_MAGIC.len() - 1] = b'1';
+	assert!(CachedResponse::decode(&wrong_magic).is_none());
+}
+
+#[test]
+fn vary_names_round_trip() {
+	let names = vec![ACCEPT_ENCODING, ACCEPT_LANGUAGE];
+
+	assert_eq!(decode_vary_names(&encode_vary_names(&names)), Some(names));
+	assert_eq!(decode_vary_names(&encode_vary_names(&[])), Some(vec![]));
+	assert_eq!(decode_vary_names(b"accept-encoding\n"), None);
+}
@@ -0,0 +1,205 @@
+use crate::cache::ResponseCache;
+use crate::config::{HttpVersionPolicy, ImpersonateConfig, RequestConfig};
+use crate::doh::DohLookup;
+use crate::fingerprint::{EchoServer, Fingerprint};
+use crate::har::HarRecorder;
+use crate::limits::Limiter;
+use crate::retry::RetryPolicy;
+use crate::root_certs;
+use rquest::header::HeaderName;
+use rquest::Client;
+use std::net::IpAddr;
+use std::path::PathBuf;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+type BoxError = Box<dyn std::error::Error + Send + Sync>;
+
+/// The maximum amount of variant clients kept at once, each of which holds its own connection pool.
+const MAX_VARIANT_CLIENTS: usize = 8;
+
+/// A rquest [Client] alongside the config it was built with and any state managed on top of it.
+pub struct NativeClient {
+	config: ImpersonateConfig,
+
+	/// The client used for requests that do not need any client-level overrides.
+	client: Client,
+
+	/// Variants of [client] with per-request overrides of options that rquest only supports setting per client.
+	/// These do not share a connection pool with [client].
+	/// Only the [MAX_VARIANT_CLIENTS] most recently used are kept, ordered from least to most recently used,
+	/// since per-request timeouts, addresses and header orders can take any number of distinct values.
+	variant_clients: Mutex<Vec<(ClientVariant, Client)>>,
+
+	/// Queues requests according to the rate and concurrency limits, if any are configured.
+	/// This is shared with queued requests, which may outlive this client.
+	limiter: Option<Arc<Limiter>>,
+
+	/// Caches responses natively, if enabled.
+	response_cache: Option<Arc<ResponseCache>>,
+
+	/// Records requests into HAR entries, if enabled.
+	har_recorder: Option<Arc<HarRecorder>>,
+}
+
+/// The client-level options that can be overridden per request.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+struct ClientVariant {
+	connect_timeout: Option<Duration>,
+	local_address: Option<IpAddr>,
+	interface: Option<String>,
+	http_version: HttpVersionPolicy,
+	header_order: Option<Arc<[HeaderName]>>,
+}
+
+impl ClientVariant {
+	fn of(config: &ImpersonateConfig) -> Self {
+		Self {
+			connect_timeout: config.connect_timeout,
+			local_address: config.local_address,
+			interface: config.interface.clone(),
+			http_version: config.http_version,
+			header_order: config.header_order.clone(),
+		}
+	}
+
+	/// Applies the overrides of a request on top of this variant.
+	fn with_overrides(&self, request_config: &RequestConfig) -> Self {
+		Self {
+			connect_timeout: request_config.connect_timeout.or(self.connect_timeout),
+			local_address: request_config.local_address.or(self.local_address),
+			interface: request_config.interface.clone().or_else(|| self.interface.clone()),
+			http_version: request_config.http_version.unwrap_or(self.http_version),
+			header_order: request_config.header_order.clone().or_else(|| self.header_order.clone()),
+		}
+	}
+}
+
+impl NativeClient {
+	pub fn new(mut config: ImpersonateConfig) -> Result<Self, BoxError> {
+		// DoH queries are sent with a separate client that uses the same config but resolves hosts normally.
+		// The lookup is created once here so that its cache is shared between all variants of this client.
+		if let Some(doh) = config.doh.take() {
+			let doh_client = build_client(&config)?;
+			config.dns_lookup = Some(Arc::new(DohLookup::new(doh, doh_client)));
+		}
+
+		let limiter = (!config.limits.is_empty())
+			.then(|| Arc::new(Limiter::new(config.limits.clone())));
+		let response_cache = config.cache.clone()
+			.map(|cache_config| Arc::new(ResponseCache::new(cache_config)));
+		let har_recorder = match config.har.clone() {
+			None => None,
+			Some(har_config) => Some(Arc::new(HarRecorder::new(har_config)
+				.map_err(|err| format!("Failed to create HAR file: {err}"))?)),
+		};
+
+		Ok(Self {
+			client: build_client(&config)?,
+			config,
+			variant_clients: Mutex::new(Vec::new()),
+			limiter,
+			response_cache,
+			har_recorder,
+		})
+	}
+
+	/// Gets the [Client] that should be used to execute a request with the specified options.
+	pub fn client_for(&self, request_config: &RequestConfig) -> Result<Client, BoxError> {
+		let base = ClientVariant::of(&self.config);
+		let variant = base.with_overrides(request_config);
+		if variant == base {
+			return Ok(self.client.clone());
+		}
+
+		let mut variant_clients = self.variant_clients.lock().unwrap();
+		if let Some(index) = variant_clients.iter().position(|(existing, _)| *existing == variant) {
+			let entry = variant_clients.remove(index);
+			let client = entry.1.clone();
+			variant_clients.push(entry);
+			return Ok(client);
+		}
+
+		let config = ImpersonateConfig {
+			connect_timeout: variant.connect_timeout,
+			local_address: variant.local_address,
+			interface: variant.interface.clone(),
+			http_version: variant.http_version,
+			header_order: variant.header_order.clone(),
+			..self.config.clone()
+		};
+		let client = build_client(&config)?;
+
+		// Dropping the least recently used client closes its idle connections once its in-flight requests complete
+		if variant_clients.len() >= MAX_VARIANT_CLIENTS {
+			variant_clients.remove(0);
+		}
+		variant_clients.push((variant, client.clone()));
+		Ok(client)
+	}
+
+	/// Gets the policy for retrying requests natively, if enabled.
+	pub fn retry_policy(&self) -> Option<RetryPolicy> {
+		self.config.retry.clone()
+	}
+
+	/// Gets the limiter that requests need to be queued in before being sent, if any limits are configured.
+	pub fn limiter(&self) -> Option<Arc<Limiter>> {
+		self.limiter.clone()
+	}
+
+	/// Gets the cache that responses are served from and stored in, if enabled.
+	pub fn response_cache(&self) -> Option<Arc<ResponseCache>> {
+		self.response_cache.clone()
+	}
+
+	/// Gets the recorder that requests are captured in, if enabled.
+	pub fn har_recorder(&self) -> Option<Arc<HarRecorder>> {
+		self.har_recorder.clone()
+	}
+
+	/// Gets the name of the preset this client impersonates, if any.
+	pub fn preset(&self) -> Option<&str> {
+		self.config.preset.as_deref()
+	}
+
+	/// Gets the Unix domain socket that a request should be sent over instead of TCP, if any.
+	pub fn unix_socket_for(&self, request_config: &RequestConfig) -> Option<PathBuf> {
+		request_config.unix_socket.clone().or_else(|| self.config.unix_socket.clone())
+	}
+
+	/// Determines the fingerprint of the connections made by this client, by making a request to a local [EchoServer].
+	/// This uses a separate client with the same config, except for options that do not affect the fingerprint
+	/// but would prevent connecting to the server.
+	pub async fn fingerprint(&self) -> Result<Fingerprint, BoxError> {
+		// A domain is used so that the server name extension is sent like it would be to any other host
+		const HOST: &str = "fingerprint.localhost";
+
+		let server = EchoServer::start().await?;
+		let mut dns_overrides = self.config.dns_overrides.clone();
+		dns_overrides.push((HOST.to_owned(), vec![server.address()]));
+
+		let config = ImpersonateConfig {
+			invalid_certs: Some(true),
+			local_address: None,
+			interface: None,
+			dns_overrides,
+			..self.config.clone()
+		};
+		let client = build_client(&config)?;
+
+		client.get(format!("https://{HOST}:{}/", server.address().port()))
+			.send().await?
+			.bytes().await?;
+
+		server.observed().pop().ok_or_else(|| "No connection was made to the echo server".into())
+	}
+}
+
+/// Builds a new rquest [Client] from a config.
+fn build_client(config: &ImpersonateConfig) -> Result<Client, BoxError> {
+	let builder = config.apply(Client::builder())
+		.ca_cert_store(root_certs::get_cached_verify_store()?);
+
+	Ok(builder.build()?)
+}
@@ -0,0 +1,202 @@
+use crate::cache::CacheConfig;
+use crate::dns::{DnsResolver, IpPreference, Lookup};
+use crate::doh::DohConfig;
+use crate::har::HarConfig;
+use crate::limits::LimitsConfig;
+use crate::retry::RetryPolicy;
+use rquest::header::HeaderName;
+use rquest::tls::Impersonate;
+use rquest::ClientBuilder;
+use std::net::{IpAddr, SocketAddr};
+use std::path::PathBuf;
+use std::str::FromStr;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+/// Client-wide options mirroring the JVM-side `ImpersonateConfig`.
+#[derive(Debug, Clone, Default)]
+pub struct ImpersonateConfig {
+	pub verbose_logging: bool,
+	pub preset: Option<String>,
+	pub request_timeout: Option<Duration>,
+	pub connect_timeout: Option<Duration>,
+	pub idle_timeout: Option<Duration>,
+	pub max_idle_per_host: Option<usize>,
+	pub tcp_keepalive: Option<Duration>,
+	pub tcp_nodelay: Option<bool>,
+	pub http2_keep_alive_interval: Option<Duration>,
+	pub http2_keep_alive_timeout: Option<Duration>,
+	pub http2_keep_alive_while_idle: Option<bool>,
+	pub invalid_certs: Option<bool>,
+	pub https_only: Option<bool>,
+	pub header_order: Option<Arc<[HeaderName]>>,
+	pub http1_title_case_headers: Option<bool>,
+	pub decompression: bool,
+	pub gzip: Option<bool>,
+	pub brotli: Option<bool>,
+	pub deflate: Option<bool>,
+	pub zstd: Option<bool>,
+	/// Static addresses for hosts that skip DNS resolution entirely.
+	pub dns_overrides: Vec<(String, Vec<SocketAddr>)>,
+	/// A custom source of addresses, otherwise the system resolver is used.
+	pub dns_lookup: Option<Arc<dyn Lookup>>,
+	/// Resolve hosts with DNS-over-HTTPS, which is turned into a [dns_lookup] once the client is created.
+	pub doh: Option<DohConfig>,
+	pub ip_preference: IpPreference,
+	pub http_version: HttpVersionPolicy,
+	/// Retrying requests natively, which is handled outside of rquest's [ClientBuilder].
+	pub retry: Option<RetryPolicy>,
+	/// Rate and concurrency limits, which are handled outside of rquest's [ClientBuilder].
+	pub limits: LimitsConfig,
+	/// Caching responses natively, which is handled outside of rquest's [ClientBuilder].
+	pub cache: Option<CacheConfig>,
+	/// Recording requests into HAR entries, which is handled outside of rquest's [ClientBuilder].
+	pub har: Option<HarConfig>,
+	/// The local IP address to make connections from.
+	pub local_address: Option<IpAddr>,
+	/// The name of the network interface to bind connections to.
+	pub interface: Option<String>,
+	/// Sending requests over a Unix domain socket, which is handled outside of rquest's [ClientBuilder].
+	pub unix_socket: Option<PathBuf>,
+}
+
+impl ImpersonateConfig {
+	/// Applies this config to a rquest [ClientBuilder].
+	pub fn apply(&self, mut client: ClientBuilder) -> ClientBuilder {
+		client = client
+			.connection_verbose(self.verbose_logging);
+		if let Some(preset) = self.preset.as_deref() {
+			client = client.impersonate(Impersonate::from_str(preset)
+				.expect("BUG: invalid impersonate preset"));
+		}
+		if let Some(duration) = self.request_timeout {
+			client = client.timeout(duration);
+		}
+		if let Some(duration) = self.connect_timeout {
+			client = client.connect_timeout(duration);
+		}
+		if let Some(duration) = self.idle_timeout {
+			client = client.pool_idle_timeout(duration);
+		}
+		if let Some(max) = self.max_idle_per_host {
+			client = client.pool_max_idle_per_host(max);
+		}
+		if let Some(duration) = self.tcp_keepalive {
+			client = client.tcp_keepalive(duration);
+		}
+		if let Some(enabled) = self.tcp_nodelay {
+			client = client.tcp_nodelay(enabled);
+		}
+		if let Some(duration) = self.http2_keep_alive_interval {
+			client = client.http2_keep_alive_interval(duration);
+		}
+		if let Some(duration) = self.http2_keep_alive_timeout {
+			client = client.http2_keep_alive_timeout(duration);
+		}
+		if let Some(enabled) = self.http2_keep_alive_while_idle {
+			client = client.http2_keep_alive_while_idle(enabled);
+		}
+		if let Some(enabled) = self.invalid_certs {
+			client = client.danger_accept_invalid_certs(enabled);
+		}
+		if self.har.is_some() {
+			// Exposes the peer certificate of responses for recording
+			client = client.tls_info(true);
+		}
+		if let Some(enabled) = self.https_only {
+			client = client.https_only(enabled);
+		}
+		match self.http_version {
+			HttpVersionPolicy::Negotiate => {}
+			HttpVersionPolicy::Http1Only => client = client.http1_only(),
+			HttpVersionPolicy::Http2PriorKnowledge => client = client.http2_prior_knowledge(),
+		}
+		if let Some(order) = self.header_order.as_deref() {
+			client = client.headers_order(static_header_order(order));
+		}
+		if self.http1_title_case_headers == Some(true) {
+			client = client.http1_title_case_headers();
+		}
+		#[cfg(feature = "gzip")] {
+			client = client.gzip(self.decompression && self.gzip.unwrap_or(true));
+		}
+		#[cfg(feature = "brotli")] {
+			client = client.brotli(self.decompression && self.brotli.unwrap_or(true));
+		}
+		#[cfg(feature = "deflate")] {
+			client = client.deflate(self.decompression && self.deflate.unwrap_or(true));
+		}
+		#[cfg(feature = "zstd")] {
+			client = client.zstd(self.decompression && self.zstd.unwrap_or(true));
+		}
+		for (host, addrs) in &self.dns_overrides {
+			client = client.resolve_to_addrs(&*host, &*addrs);
+		}
+		if self.dns_lookup.is_some() || self.ip_preference != IpPreference::HappyEyeballs {
+			let resolver = DnsResolver::new(self.dns_lookup.clone(), self.ip_preference);
+			client = client.dns_resolver(Arc::new(resolver));
+		}
+		if let Some(address) = self.local_address {
+			client = client.local_address(address);
+		}
+		#[cfg(any(target_os = "android", target_os = "linux"))]
+		if let Some(interface) = self.interface.as_deref() {
+			client = client.interface(interface);
+		}
+
+		client
+	}
+}
+
+/// Gets a `'static` copy of a header order, which is what rquest requires.
+/// Each distinct order is only leaked once and then shared by every client using it,
+/// so recreating clients with the same order does not leak any more memory.
+fn static_header_order(order: &[HeaderName]) -> &'static [HeaderName] {
+	static ORDERS: Mutex<Vec<&'static [HeaderName]>> = Mutex::new(Vec::new());
+
+	let mut orders = ORDERS.lock().expect("header order lock poisoned");
+	if let Some(existing) = orders.iter().copied().find(|existing| *existing == order) {
+		return existing;
+	}
+
+	let leaked: &'static [HeaderName] = Box::leak(order.to_vec().into_boxed_slice());
+	orders.push(leaked);
+	leaked
+}
+
+/// Which HTTP versions are used for requests.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum HttpVersionPolicy {
+	/// Use HTTP/2 if negotiated through ALPN, otherwise HTTP/1.1.
+	#[default]
+	Negotiate,
+	Http1Only,
+	/// Use HTTP/2 without negotiating it, including over cleartext connections.
+	Http2PriorKnowledge,
+}
+
+impl FromStr for HttpVersionPolicy {
+	type Err = ();
+
+	fn from_str(s: &str) -> Result<Self, Self::Err> {
+		match s {
+			"Negotiate" => Ok(HttpVersionPolicy::Negotiate),
+			"Http1Only" => Ok(HttpVersionPolicy::Http1Only),
+			"Http2PriorKnowledge" => Ok(HttpVersionPolicy::Http2PriorKnowledge),
+			_ => Err(()),
+		}
+	}
+}
+
+/// Options that apply to a single request, overriding the client's config.
+#[derive(Debug, Default)]
+pub struct RequestConfig {
+	pub header_order: Option<Arc<[HeaderName]>>,
+	pub request_timeout: Option<Duration>,
+	pub connect_timeout: Option<Duration>,
+	pub socket_timeout: Option<Duration>,
+	pub local_address: Option<IpAddr>,
+	pub interface: Option<String>,
+	pub http_version: Option<HttpVersionPolicy>,
+	pub unix_socket: Option<PathBuf>,
+}
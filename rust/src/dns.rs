@@ -0,0 +1,93 @@
+use futures_util::future::BoxFuture;
+use rquest::dns::{Addrs, Name, Resolve, Resolving};
+use std::fmt::Debug;
+use std::net::{IpAddr, SocketAddr};
+use std::str::FromStr;
+use std::sync::Arc;
+
+type BoxError = Box<dyn std::error::Error + Send + Sync>;
+
+/// Which IP address families are used when connecting to a host.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum IpPreference {
+	/// Use addresses of both families, racing connections between them (RFC 8305).
+	#[default]
+	HappyEyeballs,
+	Ipv4Only,
+	Ipv6Only,
+}
+
+impl IpPreference {
+	fn allows(self, ip: &IpAddr) -> bool {
+		match self {
+			IpPreference::HappyEyeballs => true,
+			IpPreference::Ipv4Only => ip.is_ipv4(),
+			IpPreference::Ipv6Only => ip.is_ipv6(),
+		}
+	}
+}
+
+impl FromStr for IpPreference {
+	type Err = ();
+
+	fn from_str(s: &str) -> Result<Self, Self::Err> {
+		match s {
+			"HappyEyeballs" => Ok(IpPreference::HappyEyeballs),
+			"Ipv4Only" => Ok(IpPreference::Ipv4Only),
+			"Ipv6Only" => Ok(IpPreference::Ipv6Only),
+			_ => Err(()),
+		}
+	}
+}
+
+/// A source of IP addresses for a host, used in place of the system resolver.
+pub trait Lookup: Debug + Send + Sync {
+	fn lookup(&self, host: String) -> BoxFuture<'static, Result<Vec<IpAddr>, BoxError>>;
+}
+
+/// The resolver used by clients that have a custom [Lookup] or [IpPreference] configured.
+/// Static host overrides are handled separately by rquest before this is used.
+pub struct DnsResolver {
+	/// The custom lookup, otherwise the system resolver is used.
+	lookup: Option<Arc<dyn Lookup>>,
+	preference: IpPreference,
+}
+
+impl DnsResolver {
+	pub fn new(lookup: Option<Arc<dyn Lookup>>, preference: IpPreference) -> Self {
+		Self { lookup, preference }
+	}
+}
+
+impl Resolve for DnsResolver {
+	fn resolve(&self, name: Name) -> Resolving {
+		let host = name.as_str().to_owned();
+		let lookup = self.lookup.clone();
+		let preference = self.preference;
+
+		Box::pin(async move {
+			let ips = match lookup {
+				Some(lookup) => lookup.lookup(host.clone()).await?,
+				None => system_lookup(&*host).await?,
+			};
+
+			// Ports are ignored by rquest in favor of the port of the URL
+			let addrs: Vec<SocketAddr> = ips.into_iter()
+				.filter(|ip| preference.allows(ip))
+				.map(|ip| SocketAddr::new(ip, 0))
+				.collect();
+
+			if addrs.is_empty() {
+				return Err(format!("No addresses allowed by {preference:?} were found for {host}").into());
+			}
+
+			Ok(Box::new(addrs.into_iter()) as Addrs)
+		})
+	}
+}
+
+/// Resolves a host using the system resolver.
+pub async fn system_lookup(host: &str) -> Result<Vec<IpAddr>, BoxError> {
+	let addrs = tokio::net::lookup_host((host, 0)).await?;
+	Ok(addrs.map(|addr| addr.ip()).collect())
+}
@@ -0,0 +1,267 @@
+use crate::dns::{system_lookup, Lookup};
+use dashmap::DashMap;
+use futures_util::future::BoxFuture;
+use log::debug;
+use rquest::header::{ACCEPT, CONTENT_TYPE};
+use rquest::{Client, Url};
+use std::net::IpAddr;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+type BoxError = Box<dyn std::error::Error + Send + Sync>;
+
+const DNS_MESSAGE_MIME: &str = "application/dns-message";
+
+const TYPE_A: u16 = 1;
+const TYPE_AAAA: u16 = 28;
+const CLASS_IN: u16 = 1;
+const RCODE_NXDOMAIN: u16 = 3;
+
+/// The maximum amount of hosts that are cached before expired entries are evicted.
+const MAX_CACHE_ENTRIES: usize = 512;
+
+/// Config for resolving hosts with DNS-over-HTTPS.
+#[derive(Debug, Clone)]
+pub struct DohConfig {
+	/// The URL of the resolver, queried with POST requests.
+	pub url: Url,
+	/// Whether to use the system resolver when a query fails instead of failing the lookup.
+	pub fallback: bool,
+}
+
+/// A [Lookup] that resolves hosts through a DNS-over-HTTPS resolver (RFC 8484),
+/// caching answers for as long as their TTLs allow.
+#[derive(Clone)]
+pub struct DohLookup(Arc<DohLookupInner>);
+
+struct DohLookupInner {
+	config: DohConfig,
+	/// The client used to send queries, which must not resolve hosts through this lookup itself.
+	client: Client,
+	cache: DashMap<String, CacheEntry>,
+}
+
+struct CacheEntry {
+	ips: Vec<IpAddr>,
+	expires_at: Instant,
+}
+
+impl DohLookup {
+	pub fn new(config: DohConfig, client: Client) -> Self {
+		Self(Arc::new(DohLookupInner {
+			config,
+			client,
+			cache: DashMap::new(),
+		}))
+	}
+}
+
+impl std::fmt::Debug for DohLookup {
+	fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+		f.debug_struct("DohLookup")
+			.field("config", &self.0.config)
+			.finish_non_exhaustive()
+	}
+}
+
+impl Lookup for DohLookup {
+	fn lookup(&self, host: String) -> BoxFuture<'static, Result<Vec<IpAddr>, BoxError>> {
+		let inner = self.0.clone();
+
+		Box::pin(async move {
+			if let Some(entry) = inner.cache.get(&host) {
+				if entry.expires_at > Instant::now() {
+					return Ok(entry.ips.clone());
+				}
+			}
+
+			match inner.query(&*host).await {
+				Ok(ips) => Ok(ips),
+				Err(err) if inner.config.fallback => {
+					debug!("DoH lookup for {host} failed, falling back to the system resolver: {err}");
+					system_lookup(&*host).await
+				}
+				Err(err) => Err(format!("DoH lookup for {host} failed: {err}").into()),
+			}
+		})
+	}
+}
+
+impl DohLookupInner {
+	/// Queries both A and AAAA records for a host, and caches the combined answer.
+	/// If only one of the queries fails, the answers of the other are used without being cached.
+	async fn query(&self, host: &str) -> Result<Vec<IpAddr>, BoxError> {
+		let (a, aaaa) = futures_util::future::join(
+			self.query_type(host, TYPE_A),
+			self.query_type(host, TYPE_AAAA),
+		).await;
+
+		let (answers, complete) = match (a, aaaa) {
+			(Ok(mut a), Ok(aaaa)) => {
+				a.extend(aaaa);
+				(a, true)
+			}
+			(Ok(answers), Err(err)) | (Err(err), Ok(answers)) => {
+				if answers.is_empty() {
+					return Err(err);
+				}
+				debug!("DoH query for {host} only partially failed, using the remaining answers: {err}");
+				(answers, false)
+			}
+			(Err(a_err), Err(aaaa_err)) => return Err(format!("A: {a_err}, AAAA: {aaaa_err}").into()),
+		};
+
+		// The combined answer is only valid for as long as its shortest TTL
+		let Some(ttl) = answers.iter().map(|(_, ttl)| *ttl).min() else {
+			return Ok(Vec::new());
+		};
+		let ips: Vec<IpAddr> = answers.into_iter().map(|(ip, _)| ip).collect();
+		if !complete {
+			return Ok(ips);
+		}
+
+		self.evict_expired();
+		self.cache.insert(host.to_owned(), CacheEntry {
+			ips: ips.clone(),
+			expires_at: Instant::now() + Duration::from_secs(ttl as u64),
+		});
+
+		Ok(ips)
+	}
+
+	async fn query_type(&self, host: &str, record_type: u16) -> Result<Vec<(IpAddr, u32)>, BoxError> {
+		let response = self.client.post(self.config.url.clone())
+			.header(CONTENT_TYPE, DNS_MESSAGE_MIME)
+			.header(ACCEPT, DNS_MESSAGE_MIME)
+			.body(encode_query(host, record_type)?)
+			.send()
+			.await?
+			.error_for_status()?;
+
+		decode_answers(&*response.bytes().await?)
+	}
+
+	fn evict_expired(&self) {
+		if self.cache.len() < MAX_CACHE_ENTRIES { return; }
+
+		let now = Instant::now();
+		self.cache.retain(|_, entry| entry.expires_at > now);
+
+		if self.cache.len() >= MAX_CACHE_ENTRIES {
+			self.cache.clear();
+		}
+	}
+}
+
+/// Encodes a DNS query message in wire format for a single question.
+fn encode_query(host: &str, record_type: u16) -> Result<Vec<u8>, BoxError> {
+	let mut message = Vec::with_capacity(18 + host.len());
+
+	// Header: ID of 0 (RFC 8484 §4.1), recursion desired, 1 question
+	message.extend_from_slice(&[0, 0, 0x01, 0x00, 0, 1, 0, 0, 0, 0, 0, 0]);
+
+	// Question: name as length-prefixed labels, type, and class
+	for label in host.trim_end_matches('.').split('.') {
+		if label.is_empty() || label.len() > 63 {
+			return Err(format!("invalid host name: {host}").into());
+		}
+		message.push(label.len() as u8);
+		message.extend_from_slice(label.as_bytes());
+	}
+	message.push(0);
+	message.extend_from_slice(&record_type.to_be_bytes());
+	message.extend_from_slice(&CLASS_IN.to_be_bytes());
+
+	Ok(message)
+}
+
+/// Decodes the A and AAAA answers of a DNS response message in wire format, alongside their TTLs in seconds.
+/// Other records (ie. CNAME) are skipped, as the resolver has already followed them.
+fn decode_answers(message: &[u8]) -> Result<Vec<(IpAddr, u32)>, BoxError> {
+	let mut reader = MessageReader { message, position: 0 };
+
+	let _id = reader.u16()?;
+	let flags = reader.u16()?;
+	let question_count = reader.u16()?;
+	let answer_count = reader.u16()?;
+	reader.skip(4)?; // Authority & additional counts
+
+	match flags & 0x000F {
+		0 => {}
+		RCODE_NXDOMAIN => return Ok(Vec::new()),
+		rcode => return Err(format!("resolver responded with error code {rcode}").into()),
+	}
+
+	for _ in 0..question_count {
+		reader.skip_name()?;
+		reader.skip(4)?; // Type & class
+	}
+
+	let mut answers = Vec::with_capacity(answer_count as usize);
+	for _ in 0..answer_count {
+		reader.skip_name()?;
+		let record_type = reader.u16()?;
+		let _class = reader.u16()?;
+		let ttl = reader.u32()?;
+		let length = reader.u16()? as usize;
+		let data = reader.take(length)?;
+
+		match record_type {
+			TYPE_A if length == 4 => {
+				answers.push((IpAddr::from(<[u8; 4]>::try_from(data).unwrap()), ttl));
+			}
+			TYPE_AAAA if length == 16 => {
+				answers.push((IpAddr::from(<[u8; 16]>::try_from(data).unwrap()), ttl));
+			}
+			_ => {}
+		}
+	}
+
+	Ok(answers)
+}
+
+/// Reads big-endian values from a DNS message, failing on truncated messages.
+struct MessageReader<'a> {
+	message: &'a [u8],
+	position: usize,
+}
+
+impl<'a> MessageReader<'a> {
+	fn take(&mut self, length: usize) -> Result<&'a [u8], BoxError> {
+		let end = self.position + length;
+		if end > self.message.len() {
+			return Err("truncated DNS message".into());
+		}
+
+		let slice = &self.message[self.position..end];
+		self.position = end;
+		Ok(slice)
+	}
+
+	fn skip(&mut self, length: usize) -> Result<(), BoxError> {
+		self.take(length).map(|_| ())
+	}
+
+	fn u16(&mut self) -> Result<u16, BoxError> {
+		Ok(u16::from_be_bytes(self.take(2)?.try_into().unwrap()))
+	}
+
+	fn u32(&mut self) -> Result<u32, BoxError> {
+		Ok(u32::from_be_bytes(self.take(4)?.try_into().unwrap()))
+	}
+
+	/// Skips a name, which is either a sequence of labels or ends in a compression pointer.
+	fn skip_name(&mut self) -> Result<(), BoxError> {
+		loop {
+			let length = self.take(1)?[0];
+			match length {
+				0 => return Ok(()),
+				length if length & 0xC0 == 0xC0 => return self.skip(1),
+				length => self.skip(length as usize)?,
+			}
+		}
+	}
+}
+
+#[cfg(test)]
+mod tests;
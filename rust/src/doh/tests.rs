@@ -0,0 +1,127 @@
+use super::*;
+use std::net::{Ipv4Addr, Ipv6Addr};
+
+const TYPE_CNAME: u16 = 5;
+
+/// The offset of the question's name in a message, which answers point to with `0xC0 0x0C`.
+const QUESTION_NAME_POINTER: [u8; 2] = [0xC0, 0x0C];
+
+/// Builds a response message for `example.com` with the given response code and answers.
+fn response(rcode: u16, answers: &[(&[u8], u16, u32, &[u8])]) -> Vec<u8> {
+	let mut message = vec![0, 0];
+	message.extend_from_slice(&(0x8180 | rcode).to_be_bytes());
+	message.extend_from_slice(&[0, 1]);
+	message.extend_from_slice(&(answers.len() as u16).to_be_bytes());
+	message.extend_from_slice(&[0, 0, 0, 0]);
+
+	message.extend_from_slice(b"\x07example\x03com\x00");
+	message.extend_from_slice(&TYPE_A.to_be_bytes());
+	message.extend_from_slice(&CLASS_IN.to_be_bytes());
+
+	for (name, record_type, ttl, data) in answers {
+		message.extend_from_slice(name);
+		message.extend_from_slice(&record_type.to_be_bytes());
+		message.extend_from_slice(&CLASS_IN.to_be_bytes());
+		message.extend_from_slice(&ttl.to_be_bytes());
+		message.extend_from_slice(&(data.len() as u16).to_be_bytes());
+		message.extend_from_slice(data);
+	}
+	message
+}
+
+#[test]
+fn encodes_query() {
+	let query = encode_query("example.com", TYPE_AAAA).unwrap();
+
+	let mut expected = vec![0, 0, 0x01, 0x00, 0, 1, 0, 0, 0, 0, 0, 0];
+	expected.extend_from_slice(b"\x07example\x03com\x00");
+	expected.extend_from_slice(&[0, 28, 0, 1]);
+	assert_eq!(query, expected);
+}
+
+#[test]
+fn encodes_fully_qualified_name_without_trailing_dot() {
+	assert_eq!(encode_query("example.com.", TYPE_A).unwrap(), encode_query("example.com", TYPE_A).unwrap());
+}
+
+#[test]
+fn rejects_invalid_labels() {
+	assert!(encode_query("example..com", TYPE_A).is_err());
+	assert!(encode_query("", TYPE_A).is_err());
+	assert!(encode_query(&format!("{}.com", "a".repeat(64)), TYPE_A).is_err());
+	assert!(encode_query(&format!("{}.com", "a".repeat(63)), TYPE_A).is_ok());
+}
+
+#[test]
+fn decodes_answers_with_compressed_names() {
+	let message = response(0, &[
+		(&QUESTION_NAME_POINTER, TYPE_A, 300, &[93, 184, 215, 14]),
+		(&QUESTION_NAME_POINTER, TYPE_AAAA, 60, &Ipv6Addr::LOCALHOST.octets()),
+	]);
+
+	assert_eq!(decode_answers(&message).unwrap(), vec![
+		(IpAddr::V4(Ipv4Addr::new(93, 184, 215, 14)), 300),
+		(IpAddr::V6(Ipv6Addr::LOCALHOST), 60),
+	]);
+}
+
+#[test]
+fn decodes_answers_with_uncompressed_names() {
+	let message = response(0, &[(b"\x07example\x03com\x00", TYPE_A, 10, &[127, 0, 0, 1])]);
+
+	assert_eq!(decode_answers(&message).unwrap(), vec![(IpAddr::V4(Ipv4Addr::LOCALHOST), 10)]);
+}
+
+#[test]
+fn skips_cname_records() {
+	let message = response(0, &[
+		(&QUESTION_NAME_POINTER, TYPE_CNAME, 300, b"\x03cdn\xC0\x0C"),
+		(b"\x03cdn\xC0\x0C", TYPE_A, 20, &[127, 0, 0, 1]),
+	]);
+
+	assert_eq!(decode_answers(&message).unwrap(), vec![(IpAddr::V4(Ipv4Addr::LOCALHOST), 20)]);
+}
+
+#[test]
+fn skips_addresses_of_wrong_length() {
+	let message = response(0, &[(&QUESTION_NAME_POINTER, TYPE_A, 10, &[127, 0, 0])]);
+
+	assert_eq!(decode_answers(&message).unwrap(), vec![]);
+}
+
+#[test]
+fn decodes_nxdomain_as_no_answers() {
+	assert_eq!(decode_answers(&response(RCODE_NXDOMAIN, &[])).unwrap(), vec![]);
+}
+
+#[test]
+fn fails_on_error_codes() {
+	// SERVFAIL
+	assert!(decode_answers(&response(2, &[])).is_err());
+}
+
+#[test]
+fn fails_on_truncated_messages() {
+	let message = response(0, &[(&QUESTION_NAME_POINTER, TYPE_A, 10, &[127, 0, 0, 1])]);
+
+	for length in [0, 5, 12, 20, message.len() - 1] {
+		assert!(decode_answers(&message[..length]).is_err(), "decoded {length} bytes");
+	}
+}
+
+#[test]
+fn reader_does_not_advance_past_end() {
+	let mut reader = MessageReader { message: &[0, 1, 2], position: 0 };
+
+	assert_eq!(reader.u16().unwrap(), 1);
+	assert!(reader.u16().is_err());
+	assert_eq!(reader.take(1).unwrap(), &[2]);
+	assert!(reader.take(1).is_err());
+}
+
+#[test]
+fn reader_fails_on_truncated_pointer() {
+	let mut reader = MessageReader { message: &[0xC0], position: 0 };
+
+	assert!(reader.skip_name().is_err());
+}
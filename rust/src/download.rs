@@ -0,0 +1,153 @@
+use futures_util::StreamExt;
+use rquest::header::{HeaderMap, HeaderValue, ACCEPT_ENCODING, CONTENT_RANGE, ETAG, IF_RANGE, LAST_MODIFIED, RANGE};
+use rquest::{Client, Request, StatusCode, Version};
+use std::ffi::OsString;
+use std::fmt::{Display, Formatter};
+use std::path::{Path, PathBuf};
+use tokio::fs::{self, OpenOptions};
+use tokio::io::AsyncWriteExt;
+
+/// The response of a download, whose body has been written to a file.
+pub struct DownloadResult {
+	pub version: Version,
+	pub status: StatusCode,
+	pub headers: HeaderMap,
+	/// The size of the downloaded file, or 0 if the status was not successful and nothing was written.
+	pub size: u64,
+}
+
+pub enum DownloadError {
+	/// Sending the request or receiving the body failed.
+	Request(rquest::Error),
+	/// Writing the file failed, or the server responded unexpectedly.
+	File(String),
+}
+
+impl Display for DownloadError {
+	fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+		match self {
+			DownloadError::Request(err) => write!(f, "{err}"),
+			DownloadError::File(message) => f.write_str(message),
+		}
+	}
+}
+
+impl From<std::io::Error> for DownloadError {
+	fn from(err: std::io::Error) -> Self {
+		DownloadError::File(format!("Failed to write downloaded file: {err}"))
+	}
+}
+
+/// Downloads the body of a response directly into the file at [path].
+///
+/// The body is written to `<path>.part` first, which is only renamed to [path] once the body has been fully received.
+/// If [resume] is set and a partial file was left by an earlier download of the same url, only the rest of the body is
+/// requested with a `Range` header. An `If-Range` header with the validator of the earlier response is sent alongside it,
+/// so that the download restarts from scratch if the resource changed since. Partial files without a validator
+/// (stored in `<path>.part.validator`) are never resumed. If the partial file turns out to be complete already, it is
+/// renamed to [path] and reported with a `206 Partial Content` status.
+///
+/// The body is always requested without a content encoding, since ranges apply to the encoded body.
+pub async fn download(client: &Client, mut request: Request, path: &Path, resume: bool) -> Result<DownloadResult, DownloadError> {
+	let partial_path = with_suffix(path, ".part");
+	let validator_path = with_suffix(path, ".part.validator");
+
+	request.headers_mut().insert(ACCEPT_ENCODING, HeaderValue::from_static("identity"));
+
+	let mut offset = 0;
+	if resume {
+		let partial_size = fs::metadata(&partial_path).await.map_or(0, |metadata| metadata.len());
+		let validator = fs::read_to_string(&validator_path).await.ok()
+			.and_then(|validator| HeaderValue::from_str(validator.trim()).ok());
+
+		if let (1.., Some(validator)) = (partial_size, validator) {
+			offset = partial_size;
+			request.headers_mut().insert(RANGE, HeaderValue::from_str(&*format!("bytes={offset}-")).unwrap());
+			request.headers_mut().insert(IF_RANGE, validator);
+		}
+	}
+
+	let response = client.execute(request).await.map_err(DownloadError::Request)?;
+	let version = response.version();
+	let status = response.status();
+	let headers = response.headers().clone();
+
+	if !status.is_success() {
+		// The partial file was already complete, but the process stopped before renaming it.
+		// This is reported as a resumed download that had nothing left to receive.
+		if status == StatusCode::RANGE_NOT_SATISFIABLE && offset > 0 && content_range(&headers) == Some((None, Some(offset))) {
+			fs::rename(&partial_path, path).await?;
+			let _ = fs::remove_file(&validator_path).await;
+			return Ok(DownloadResult { version, status: StatusCode::PARTIAL_CONTENT, headers, size: offset });
+		}
+
+		return Ok(DownloadResult { version, status, headers, size: 0 });
+	}
+
+	// The server sends the full body instead when the If-Range validator does not match
+	let append = status == StatusCode::PARTIAL_CONTENT;
+	if append && (offset == 0 || content_range(&headers).and_then(|(start, _)| start) != Some(offset)) {
+		return Err(DownloadError::File("Server responded with a different range than requested".to_owned()));
+	}
+
+	let mut file = OpenOptions::new()
+		.create(true)
+		.write(true)
+		.append(append)
+		.truncate(!append)
+		.open(&partial_path)
+		.await?;
+
+	// Keep the validator of this response around so that the download can be resumed if it gets interrupted
+	match validator(&headers) {
+		Some(validator) => fs::write(&validator_path, validator).await?,
+		None => { let _ = fs::remove_file(&validator_path).await; }
+	}
+
+	let mut size = if append { offset } else { 0 };
+	let mut body = response.bytes_stream();
+	while let Some(chunk) = body.next().await {
+		let chunk = chunk.map_err(DownloadError::Request)?;
+		file.write_all(&chunk).await?;
+		size += chunk.len() as u64;
+	}
+
+	file.sync_all().await?;
+	drop(file);
+	fs::rename(&partial_path, path).await?;
+	let _ = fs::remove_file(&validator_path).await;
+
+	Ok(DownloadResult { version, status, headers, size })
+}
+
+/// Gets the validator that can be sent in an `If-Range` header to resume the body of a response.
+/// Weak entity tags cannot be used for this, in which case the last modified date is used instead.
+fn validator(headers: &HeaderMap) -> Option<&str> {
+	let etag = headers.get(ETAG)
+		.and_then(|value| value.to_str().ok())
+		.filter(|etag| !etag.starts_with("W/"));
+
+	etag.or_else(|| headers.get(LAST_MODIFIED)?.to_str().ok())
+}
+
+/// Parses a `Content-Range` header into the start of the range (absent for `*`) and the complete length (absent for `*`).
+fn content_range(headers: &HeaderMap) -> Option<(Option<u64>, Option<u64>)> {
+	let value = headers.get(CONTENT_RANGE)?.to_str().ok()?;
+	let (range, length) = value.strip_prefix("bytes ")?.split_once('/')?;
+
+	let start = match range {
+		"*" => None,
+		range => Some(range.split_once('-')?.0.parse().ok()?),
+	};
+	let length = match length {
+		"*" => None,
+		length => Some(length.parse().ok()?),
+	};
+	Some((start, length))
+}
+
+fn with_suffix(path: &Path, suffix: &str) -> PathBuf {
+	let mut path = OsString::from(path);
+	path.push(suffix);
+	PathBuf::from(path)
+}
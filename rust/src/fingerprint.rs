@@ -0,0 +1,667 @@
+use crate::har::json_string;
+use boring::asn1::Asn1Time;
+use boring::bn::BigNum;
+use boring::ec::{EcGroup, EcKey};
+use boring::hash::{hash, MessageDigest};
+use boring::nid::Nid;
+use boring::pkey::PKey;
+use boring::ssl::{select_next_proto, AlpnError, SslAcceptor, SslMethod};
+use boring::x509::{X509NameBuilder, X509};
+use log::debug;
+use std::fmt::Write as _;
+use std::io;
+use std::net::SocketAddr;
+use std::pin::Pin;
+use std::sync::{Arc, Mutex};
+use std::task::{Context, Poll};
+use tokio::io::{AsyncRead, AsyncReadExt, AsyncWrite, AsyncWriteExt, ReadBuf};
+use tokio::net::{TcpListener, TcpStream};
+use tokio::task::AbortHandle;
+
+type BoxError = Box<dyn std::error::Error + Send + Sync>;
+
+/// The HTTP/2 connection preface sent by clients before any frames.
+const HTTP2_PREFACE: &[u8; 24] = b"PRI * HTTP/2.0\r\n\r\nSM\r\n\r\n";
+
+/// The maximum size of a ClientHello or HTTP/1.1 request head that is read.
+const MAX_READ_SIZE: usize = 64 * 1024;
+
+/// The fingerprints of a client's TLS ClientHello and HTTP/2 connection setup, as observed by an [EchoServer].
+#[derive(Debug, Clone, Default)]
+pub struct Fingerprint {
+	/// The JA3 string, which depends on the order of extensions (randomized by newer browsers).
+	pub ja3: String,
+	/// The MD5 hash of [ja3].
+	pub ja3_hash: String,
+	/// The JA4 fingerprint, which sorts extensions and is therefore stable across connections.
+	pub ja4: String,
+	/// The raw JA4 fingerprint, listing the values that are hashed in [ja4].
+	pub ja4_raw: String,
+	/// Akamai's HTTP/2 fingerprint, if the connection negotiated HTTP/2.
+	pub akamai: Option<String>,
+	/// The MD5 hash of [akamai].
+	pub akamai_hash: Option<String>,
+}
+
+impl Fingerprint {
+	/// Serializes this fingerprint as a JSON object.
+	pub fn to_json(&self) -> String {
+		let optional = |value: &Option<String>| value.as_deref().map_or("null".to_owned(), json_string);
+		format!(
+			r#"{{"ja3":{},"ja3_hash":{},"ja4":{},"ja4_r":{},"akamai":{},"akamai_hash":{}}}"#,
+			json_string(&self.ja3),
+			json_string(&self.ja3_hash),
+			json_string(&self.ja4),
+			json_string(&self.ja4_raw),
+			optional(&self.akamai),
+			optional(&self.akamai_hash),
+		)
+	}
+}
+
+/// A local HTTPS server that accepts any client, records the fingerprint of its ClientHello and HTTP/2 connection setup,
+/// and responds to every request with that fingerprint as JSON.
+/// The server uses a self-signed certificate, so clients need to accept invalid certificates.
+pub struct EchoServer {
+	address: SocketAddr,
+	observed: Arc<Mutex<Vec<Fingerprint>>>,
+	task: AbortHandle,
+}
+
+impl EchoServer {
+	/// Starts listening on a random port of the loopback interface.
+	/// This needs to be called within the tokio runtime.
+	pub async fn start() -> Result<Self, BoxError> {
+		let acceptor = Arc::new(new_acceptor()?);
+		let listener = TcpListener::bind("127.0.0.1:0").await?;
+		let address = listener.local_addr()?;
+		let observed = Arc::new(Mutex::new(Vec::new()));
+
+		let observed_clone = observed.clone();
+		let task = tokio::spawn(async move {
+			while let Ok((stream, _)) = listener.accept().await {
+				let acceptor = acceptor.clone();
+				let observed = observed_clone.clone();
+
+				tokio::spawn(async move {
+					if let Err(err) = handle_connection(stream, &acceptor, &observed).await {
+						debug!("Fingerprint echo server connection failed: {err}");
+					}
+				});
+			}
+		});
+
+		Ok(Self { address, observed, task: task.abort_handle() })
+	}
+
+	pub fn address(&self) -> SocketAddr {
+		self.address
+	}
+
+	/// Gets the fingerprints of all the connections made to this server so far, in the order they were made.
+	pub fn observed(&self) -> Vec<Fingerprint> {
+		self.observed.lock().expect("observed lock poisoned").clone()
+	}
+}
+
+impl Drop for EchoServer {
+	fn drop(&mut self) {
+		self.task.abort();
+	}
+}
+
+/// Creates a TLS acceptor with a new self-signed certificate for `localhost`, which supports both HTTP/2 and HTTP/1.1.
+fn new_acceptor() -> Result<SslAcceptor, BoxError> {
+	let key = PKey::from_ec_key(EcKey::generate(&*EcGroup::from_curve_name(Nid::X9_62_PRIME256V1)?)?)?;
+
+	let mut name = X509NameBuilder::new()?;
+	name.append_entry_by_text("CN", "localhost")?;
+	let name = name.build();
+
+	let mut certificate = X509::builder()?;
+	certificate.set_version(2)?;
+	certificate.set_serial_number(&*BigNum::from_u32(1)?.to_asn1_integer()?)?;
+	certificate.set_subject_name(&name)?;
+	certificate.set_issuer_name(&name)?;
+	certificate.set_pubkey(&key)?;
+	certificate.set_not_before(&*Asn1Time::days_from_now(0)?)?;
+	certificate.set_not_after(&*Asn1Time::days_from_now(1)?)?;
+	certificate.sign(&key, MessageDigest::sha256())?;
+	let certificate = certificate.build();
+
+	let mut acceptor = SslAcceptor::mozilla_intermediate_v5(SslMethod::tls())?;
+	acceptor.set_private_key(&key)?;
+	acceptor.set_certificate(&certificate)?;
+	acceptor.set_alpn_select_callback(|_, client| {
+		select_next_proto(b"\x02h2\x08http/1.1", client).ok_or(AlpnError::NOACK)
+	});
+
+	Ok(acceptor.build())
+}
+
+/// Captures the fingerprints of a connection and then responds to its first request.
+async fn handle_connection(mut stream: TcpStream, acceptor: &SslAcceptor, observed: &Mutex<Vec<Fingerprint>>) -> Result<(), BoxError> {
+	let (client_hello, raw) = read_client_hello(&mut stream).await?;
+	let ja3 = client_hello.ja3();
+	let mut fingerprint = Fingerprint {
+		ja3_hash: md5_hex(ja3.as_bytes())?,
+		ja3,
+		ja4: client_hello.ja4()?,
+		ja4_raw: client_hello.ja4_raw(),
+		..Fingerprint::default()
+	};
+
+	// The ClientHello has already been read from the socket, so it is replayed to the TLS acceptor
+	let stream = Replay { prefix: raw, position: 0, inner: stream };
+	let mut stream = tokio_boring::accept(acceptor, stream).await
+		.map_err(|err| format!("TLS handshake failed: {err}"))?;
+
+	let http2 = stream.ssl().selected_alpn_protocol() == Some(&b"h2"[..]);
+	if http2 {
+		let connection = read_http2_setup(&mut stream).await?;
+		let akamai = connection.akamai();
+		fingerprint.akamai_hash = Some(md5_hex(akamai.as_bytes())?);
+		fingerprint.akamai = Some(akamai);
+		observed.lock().expect("observed lock poisoned").push(fingerprint.clone());
+
+		write_http2_response(&mut stream, connection.stream_id, fingerprint.to_json().as_bytes()).await?;
+	} else {
+		read_http1_head(&mut stream).await?;
+		observed.lock().expect("observed lock poisoned").push(fingerprint.clone());
+
+		let body = fingerprint.to_json();
+		let response = format!(
+			"HTTP/1.1 200 OK\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{body}",
+			body.len(),
+		);
+		stream.write_all(response.as_bytes()).await?;
+	}
+
+	stream.shutdown().await?;
+	Ok(())
+}
+
+// ------------------------ TLS ------------------------ //
+
+/// The fields of a TLS ClientHello that make up its fingerprints.
+#[derive(Debug, Default)]
+struct ClientHello {
+	legacy_version: u16,
+	cipher_suites: Vec<u16>,
+	/// The types of all extensions, in the order they were sent.
+	extensions: Vec<u16>,
+	supported_groups: Vec<u16>,
+	ec_point_formats: Vec<u8>,
+	signature_algorithms: Vec<u16>,
+	supported_versions: Vec<u16>,
+	alpn: Vec<Vec<u8>>,
+	has_server_name: bool,
+}
+
+/// Checks whether a value is one of the reserved GREASE values (RFC 8701), which are left out of fingerprints.
+fn is_grease(value: u16) -> bool {
+	value & 0x0f0f == 0x0a0a && value >> 8 == value & 0xff
+}
+
+impl ClientHello {
+	/// Parses the body of a ClientHello handshake message.
+	fn parse(data: &[u8]) -> Option<Self> {
+		let mut reader = Reader(data);
+		let mut hello = ClientHello { legacy_version: reader.u16()?, ..Default::default() };
+		reader.skip(32)?; // Random
+		let session_id_len = reader.u8()?;
+		reader.skip(session_id_len as usize)?;
+
+		let mut ciphers = reader.vec16()?;
+		while !ciphers.0.is_empty() {
+			hello.cipher_suites.push(ciphers.u16()?);
+		}
+
+		let compression_len = reader.u8()?;
+		reader.skip(compression_len as usize)?;
+
+		let mut extensions = match reader.0.is_empty() {
+			true => Reader(&[]),
+			false => reader.vec16()?,
+		};
+		while !extensions.0.is_empty() {
+			let kind = extensions.u16()?;
+			let mut data = extensions.vec16()?;
+			hello.extensions.push(kind);
+
+			match kind {
+				0 => hello.has_server_name = true,
+				10 => {
+					let mut groups = data.vec16()?;
+					while !groups.0.is_empty() { hello.supported_groups.push(groups.u16()?); }
+				}
+				11 => {
+					let len = data.u8()?;
+					hello.ec_point_formats = data.take(len as usize)?.to_vec();
+				}
+				13 => {
+					let mut algorithms = data.vec16()?;
+					while !algorithms.0.is_empty() { hello.signature_algorithms.push(algorithms.u16()?); }
+				}
+				16 => {
+					let mut protocols = data.vec16()?;
+					while !protocols.0.is_empty() {
+						let len = protocols.u8()?;
+						hello.alpn.push(protocols.take(len as usize)?.to_vec());
+					}
+				}
+				43 => {
+					let len = data.u8()?;
+					let mut versions = Reader(data.take(len as usize)?);
+					while !versions.0.is_empty() { hello.supported_versions.push(versions.u16()?); }
+				}
+				_ => {}
+			}
+		}
+
+		Some(hello)
+	}
+
+	/// Builds the JA3 string: `version,ciphers,extensions,groups,point_formats` with decimal values joined by `-`.
+	fn ja3(&self) -> String {
+		fn join<T: ToString>(values: impl Iterator<Item=T>) -> String {
+			values.map(|value| value.to_string()).collect::<Vec<_>>().join("-")
+		}
+
+		format!(
+			"{},{},{},{},{}",
+			self.legacy_version,
+			join(self.cipher_suites.iter().filter(|v| !is_grease(**v))),
+			join(self.extensions.iter().filter(|v| !is_grease(**v))),
+			join(self.supported_groups.iter().filter(|v| !is_grease(**v))),
+			join(self.ec_point_formats.iter()),
+		)
+	}
+
+	/// Builds the JA4 fingerprint (TLS over TCP) following the FoxIO specification.
+	fn ja4(&self) -> Result<String, BoxError> {
+		let (prefix, ciphers, extensions) = self.ja4_parts();
+
+		let ciphers_hash = match ciphers.is_empty() {
+			true => "000000000000".to_owned(),
+			false => truncated_sha256(&ciphers)?,
+		};
+		let extensions_hash = match extensions.is_empty() {
+			true => "000000000000".to_owned(),
+			false => truncated_sha256(&extensions)?,
+		};
+
+		Ok(format!("{prefix}_{ciphers_hash}_{extensions_hash}"))
+	}
+
+	/// Builds the raw JA4 fingerprint (JA4_r), which lists the values that are hashed in [ja4].
+	fn ja4_raw(&self) -> String {
+		let (prefix, ciphers, extensions) = self.ja4_parts();
+		format!("{prefix}_{ciphers}_{extensions}")
+	}
+
+	/// Gets the parts of JA4 before hashing: the prefix, the sorted cipher suites,
+	/// and the sorted extensions followed by the signature algorithms.
+	fn ja4_parts(&self) -> (String, String, String) {
+		let version = self.supported_versions.iter()
+			.copied()
+			.filter(|v| !is_grease(*v))
+			.max()
+			.unwrap_or(self.legacy_version);
+		let version = match version {
+			0x0304 => "13",
+			0x0303 => "12",
+			0x0302 => "11",
+			0x0301 => "10",
+			0x0300 => "s3",
+			_ => "00",
+		};
+
+		let mut ciphers: Vec<u16> = self.cipher_suites.iter().copied().filter(|v| !is_grease(*v)).collect();
+		let extensions: Vec<u16> = self.extensions.iter().copied().filter(|v| !is_grease(*v)).collect();
+
+		let alpn = match self.alpn.first().filter(|alpn| !alpn.is_empty()) {
+			None => "00".to_owned(),
+			Some(alpn) => {
+				let (first, last) = (alpn[0], alpn[alpn.len() - 1]);
+				match first.is_ascii_alphanumeric() && last.is_ascii_alphanumeric() {
+					true => format!("{}{}", first as char, last as char),
+					false => format!("{}{}", &format!("{first:02x}")[..1], &format!("{last:02x}")[1..]),
+				}
+			}
+		};
+
+		let prefix = format!(
+			"t{version}{}{:02}{:02}{alpn}",
+			if self.has_server_name { 'd' } else { 'i' },
+			ciphers.len().min(99),
+			extensions.len().min(99),
+		);
+
+		ciphers.sort_unstable();
+
+		// The server name and ALPN extensions are left out since they are already represented in the prefix
+		let mut sorted_extensions: Vec<u16> = extensions.into_iter().filter(|v| *v != 0 && *v != 16).collect();
+		sorted_extensions.sort_unstable();
+		let mut extensions = hex_list(&sorted_extensions);
+		if !sorted_extensions.is_empty() && !self.signature_algorithms.is_empty() {
+			write!(extensions, "_{}", hex_list(&self.signature_algorithms)).unwrap();
+		}
+
+		(prefix, hex_list(&ciphers), extensions)
+	}
+}
+
+/// Reads the records making up the ClientHello from a connection, returning it alongside the raw bytes that were read.
+async fn read_client_hello(stream: &mut TcpStream) -> Result<(ClientHello, Vec<u8>), BoxError> {
+	let mut raw = Vec::new();
+	let mut handshake = Vec::new();
+
+	// The handshake message may be fragmented across multiple records
+	while handshake.len() < 4 || handshake.len() < 4 + u32::from_be_bytes([0, handshake[1], handshake[2], handshake[3]]) as usize {
+		let mut header = [0u8; 5];
+		stream.read_exact(&mut header).await?;
+		if header[0] != 22 { return Err("connection did not start with a TLS handshake".into()); }
+
+		let len = u16::from_be_bytes([header[3], header[4]]) as usize;
+		let mut fragment = vec![0u8; len];
+		stream.read_exact(&mut fragment).await?;
+
+		raw.extend_from_slice(&header);
+		raw.extend_from_slice(&fragment);
+		handshake.extend_from_slice(&fragment);
+		if raw.len() > MAX_READ_SIZE { return Err("ClientHello is too large".into()); }
+	}
+
+	if handshake[0] != 1 { return Err("first handshake message is not a ClientHello".into()); }
+
+	let hello = ClientHello::parse(&handshake[4..]).ok_or("malformed ClientHello")?;
+	Ok((hello, raw))
+}
+
+// ------------------------ HTTP ------------------------ //
+
+/// The frames a client sends when setting up an HTTP/2 connection, up to its first request.
+#[derive(Debug, Default)]
+struct Http2Setup {
+	/// The `id:value` pairs of the first SETTINGS frame, in the order they were sent.
+	settings: Vec<(u16, u32)>,
+	/// The increment of the first connection-level WINDOW_UPDATE frame.
+	window_update: Option<u32>,
+	/// The `stream:exclusive:dependency:weight` of each PRIORITY frame.
+	priorities: Vec<String>,
+	/// The order of the pseudo-headers of the first request, as their first letters.
+	pseudo_headers: Vec<char>,
+	/// The stream of the first request.
+	stream_id: u32,
+}
+
+impl Http2Setup {
+	/// Builds Akamai's HTTP/2 fingerprint: `settings|window_update|priorities|pseudo_header_order`.
+	fn akamai(&self) -> String {
+		let settings: Vec<String> = self.settings.iter().map(|(id, value)| format!("{id}:{value}")).collect();
+		let pseudo_headers: Vec<String> = self.pseudo_headers.iter().map(char::to_string).collect();
+
+		format!(
+			"{}|{}|{}|{}",
+			settings.join(";"),
+			self.window_update.map_or("00".to_owned(), |increment| increment.to_string()),
+			if self.priorities.is_empty() { "0".to_owned() } else { self.priorities.join(",") },
+			pseudo_headers.join(","),
+		)
+	}
+}
+
+/// Reads the frames of an HTTP/2 connection until the first request's HEADERS frame.
+async fn read_http2_setup<S: AsyncRead + AsyncWrite + Unpin>(stream: &mut S) -> Result<Http2Setup, BoxError> {
+	let mut preface = [0u8; 24];
+	stream.read_exact(&mut preface).await?;
+	if &preface != HTTP2_PREFACE { return Err("invalid HTTP/2 connection preface".into()); }
+
+	// An empty SETTINGS frame
+	stream.write_all(&[0, 0, 0, 4, 0, 0, 0, 0, 0]).await?;
+
+	let mut setup = Http2Setup::default();
+	let mut received_settings = false;
+	loop {
+		let mut header = [0u8; 9];
+		stream.read_exact(&mut header).await?;
+		let len = u32::from_be_bytes([0, header[0], header[1], header[2]]) as usize;
+		let (kind, flags) = (header[3], header[4]);
+		let stream_id = u32::from_be_bytes([header[5], header[6], header[7], header[8]]) & 0x7fffffff;
+
+		if len > MAX_READ_SIZE { return Err("HTTP/2 frame is too large".into()); }
+		let mut payload = vec![0u8; len];
+		stream.read_exact(&mut payload).await?;
+		let mut reader = Reader(&payload);
+
+		match kind {
+			// SETTINGS, which is acknowledged unless it is an acknowledgement itself
+			4 if flags & 0x1 == 0 => {
+				if !received_settings {
+					while !reader.0.is_empty() {
+						setup.settings.push((reader.u16().ok_or("malformed SETTINGS")?, reader.u32().ok_or("malformed SETTINGS")?));
+					}
+					received_settings = true;
+				}
+				stream.write_all(&[0, 0, 0, 4, 0x1, 0, 0, 0, 0]).await?;
+			}
+			// WINDOW_UPDATE
+			8 if stream_id == 0 && setup.window_update.is_none() => {
+				setup.window_update = Some(reader.u32().ok_or("malformed WINDOW_UPDATE")? & 0x7fffffff);
+			}
+			// PRIORITY
+			2 => {
+				let dependency = reader.u32().ok_or("malformed PRIORITY")?;
+				let weight = reader.u8().ok_or("malformed PRIORITY")? as u16 + 1;
+				setup.priorities.push(format!("{stream_id}:{}:{}:{weight}", dependency >> 31, dependency & 0x7fffffff));
+			}
+			// HEADERS
+			1 => {
+				if flags & 0x8 != 0 {
+					// Padding is ignored, since pseudo-headers are at the start of the header block
+					reader.skip(1).ok_or("malformed HEADERS")?;
+				}
+				if flags & 0x20 != 0 {
+					reader.skip(5).ok_or("malformed HEADERS")?;
+				}
+				setup.pseudo_headers = pseudo_header_order(reader.0);
+				setup.stream_id = stream_id;
+				return Ok(setup);
+			}
+			_ => {}
+		}
+	}
+}
+
+/// Gets the order of the pseudo-headers at the start of an HPACK header block, based on their static table indices.
+/// Headers are not fully decoded, so pseudo-headers with literal names are not recognized.
+fn pseudo_header_order(block: &[u8]) -> Vec<char> {
+	let mut reader = Reader(block);
+	let mut order = Vec::new();
+
+	while let Some(&first) = reader.0.first() {
+		let name_index = if first & 0x80 != 0 {
+			// Indexed header field
+			reader.hpack_int(7)
+		} else if first & 0xc0 == 0x40 {
+			// Literal with incremental indexing
+			reader.hpack_int(6)
+		} else if first & 0xe0 == 0x20 {
+			// Dynamic table size update
+			reader.hpack_int(5);
+			continue;
+		} else {
+			// Literal without indexing or never indexed
+			reader.hpack_int(4)
+		};
+
+		let pseudo_header = match name_index {
+			Some(1) => 'a',
+			Some(2..=3) => 'm',
+			Some(4..=5) => 'p',
+			Some(6..=7) => 's',
+			_ => break,
+		};
+		order.push(pseudo_header);
+
+		// Skip the literal value, if any
+		if first & 0x80 == 0 {
+			let Some(len) = reader.hpack_int(7) else { break };
+			if reader.skip(len as usize).is_none() { break; }
+		}
+	}
+
+	order
+}
+
+/// Responds to a request on an HTTP/2 stream with a JSON body, and then closes the connection.
+async fn write_http2_response<S: AsyncWrite + Unpin>(stream: &mut S, stream_id: u32, body: &[u8]) -> io::Result<()> {
+	let mut frames = Vec::with_capacity(64 + body.len());
+	let mut frame = |kind: u8, flags: u8, stream_id: u32, payload: &[u8]| {
+		frames.extend_from_slice(&(payload.len() as u32).to_be_bytes()[1..]);
+		frames.extend_from_slice(&[kind, flags]);
+		frames.extend_from_slice(&stream_id.to_be_bytes());
+		frames.extend_from_slice(payload);
+	};
+
+	// `:status: 200` from the static table, and `content-type: application/json` with an indexed name
+	let mut headers = vec![0x88, 0x0f, 0x10, b"application/json".len() as u8];
+	headers.extend_from_slice(b"application/json");
+
+	frame(1, 0x4, stream_id, &headers); // HEADERS with END_HEADERS
+	frame(0, 0x1, stream_id, body); // DATA with END_STREAM
+	frame(7, 0, 0, &[&stream_id.to_be_bytes()[..], &[0, 0, 0, 0]].concat()); // GOAWAY with NO_ERROR
+
+	stream.write_all(&frames).await?;
+	stream.flush().await
+}
+
+/// Reads the head of an HTTP/1.1 request, up to the empty line.
+async fn read_http1_head<S: AsyncRead + Unpin>(stream: &mut S) -> Result<(), BoxError> {
+	let mut head = Vec::new();
+	let mut buf = [0u8; 1024];
+
+	while !head.windows(4).any(|window| window == b"\r\n\r\n") {
+		let read = stream.read(&mut buf).await?;
+		if read == 0 { return Err("connection closed before the request head".into()); }
+		head.extend_from_slice(&buf[..read]);
+		if head.len() > MAX_READ_SIZE { return Err("request head is too large".into()); }
+	}
+
+	Ok(())
+}
+
+// ------------------------ Other ------------------------ //
+
+/// A stream that first returns bytes that were already read from [inner].
+#[derive(Debug)]
+struct Replay<S> {
+	prefix: Vec<u8>,
+	position: usize,
+	inner: S,
+}
+
+impl<S: AsyncRead + Unpin> AsyncRead for Replay<S> {
+	fn poll_read(mut self: Pin<&mut Self>, cx: &mut Context<'_>, buf: &mut ReadBuf<'_>) -> Poll<io::Result<()>> {
+		if self.position < self.prefix.len() {
+			let len = buf.remaining().min(self.prefix.len() - self.position);
+			let start = self.position;
+			buf.put_slice(&self.prefix[start..start + len]);
+			self.position += len;
+			return Poll::Ready(Ok(()));
+		}
+
+		Pin::new(&mut self.inner).poll_read(cx, buf)
+	}
+}
+
+impl<S: AsyncWrite + Unpin> AsyncWrite for Replay<S> {
+	fn poll_write(mut self: Pin<&mut Self>, cx: &mut Context<'_>, buf: &[u8]) -> Poll<io::Result<usize>> {
+		Pin::new(&mut self.inner).poll_write(cx, buf)
+	}
+
+	fn poll_flush(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+		Pin::new(&mut self.inner).poll_flush(cx)
+	}
+
+	fn poll_shutdown(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+		Pin::new(&mut self.inner).poll_shutdown(cx)
+	}
+}
+
+/// Reads big-endian values from the front of a byte slice.
+struct Reader<'a>(&'a [u8]);
+
+impl<'a> Reader<'a> {
+	fn take(&mut self, len: usize) -> Option<&'a [u8]> {
+		if self.0.len() < len { return None; }
+		let (taken, rest) = self.0.split_at(len);
+		self.0 = rest;
+		Some(taken)
+	}
+
+	fn skip(&mut self, len: usize) -> Option<()> {
+		self.take(len).map(|_| ())
+	}
+
+	fn u8(&mut self) -> Option<u8> {
+		self.take(1).map(|bytes| bytes[0])
+	}
+
+	fn u16(&mut self) -> Option<u16> {
+		self.take(2).map(|bytes| u16::from_be_bytes([bytes[0], bytes[1]]))
+	}
+
+	fn u32(&mut self) -> Option<u32> {
+		self.take(4).map(|bytes| u32::from_be_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]))
+	}
+
+	/// Reads a vector prefixed with a 16-bit length.
+	fn vec16(&mut self) -> Option<Reader<'a>> {
+		let len = self.u16()?;
+		self.take(len as usize).map(Reader)
+	}
+
+	/// Reads an HPACK integer (RFC 7541 §5.1) with a prefix of the specified amount of bits.
+	fn hpack_int(&mut self, prefix_bits: u8) -> Option<u64> {
+		let max_prefix = (1u64 << prefix_bits) - 1;
+		let mut value = self.u8()? as u64 & max_prefix;
+		if value < max_prefix { return Some(value); }
+
+		let mut shift = 0;
+		loop {
+			let byte = self.u8()?;
+			value += ((byte & 0x7f) as u64) << shift;
+			if byte & 0x80 == 0 { return Some(value); }
+			shift += 7;
+			if shift > 28 { return None; }
+		}
+	}
+}
+
+/// Formats values as 4-digit lowercase hex, joined by `,`.
+fn hex_list(values: &[u16]) -> String {
+	values.iter().map(|value| format!("{value:04x}")).collect::<Vec<_>>().join(",")
+}
+
+fn md5_hex(data: &[u8]) -> Result<String, BoxError> {
+	Ok(hex(&hash(MessageDigest::md5(), data)?))
+}
+
+/// The first 12 hex characters of the SHA-256 hash of a string, as used by JA4.
+fn truncated_sha256(data: &str) -> Result<String, BoxError> {
+	let mut digest = hex(&hash(MessageDigest::sha256(), data.as_bytes())?);
+	digest.truncate(12);
+	Ok(digest)
+}
+
+fn hex(bytes: &[u8]) -> String {
+	bytes.iter().fold(String::with_capacity(bytes.len() * 2), |mut out, byte| {
+		write!(out, "{byte:02x}").unwrap();
+		out
+	})
+}
+
+#[cfg(test)]
+mod tests;
@@ -0,0 +1,99 @@
+//! Regression tests for the fingerprints produced by each preset, compared against the golden files in `tests/fingerprints`.
+//!
+//! A preset without a golden file fails the test, the same as a changed fingerprint, so that CI cannot pass without them.
+//! Golden files are only written when running with `UPDATE_FINGERPRINTS=1`, ie. after adding a preset or bumping rquest.
+
+use super::*;
+use crate::client::NativeClient;
+use crate::config::ImpersonateConfig;
+use std::fs;
+use std::path::PathBuf;
+use tokio::runtime::Runtime;
+
+/// The presets exposed by the JVM-side `ImpersonatePreset`, which must be kept in sync.
+const PRESETS: &[&str] = &[
+	"chrome_100", "chrome_101", "chrome_104", "chrome_105", "chrome_106", "chrome_107", "chrome_108", "chrome_109",
+	"chrome_114", "chrome_116", "chrome_117", "chrome_118", "chrome_119", "chrome_120", "chrome_123", "chrome_124",
+	"chrome_126", "chrome_127", "chrome_128", "chrome_129",
+	"safari_ios_17.2", "safari_ios_17.4.1", "safari_15.3", "safari_15.5", "safari_15.6.1", "safari_16", "safari_16.5",
+	"safari_ios_16.5", "safari_17.0", "safari_17.2.1", "safari_17.4.1", "safari_17.5", "safari_18", "safari_ipad_18",
+	"okhttp_3.9", "okhttp_3.11", "okhttp_3.13", "okhttp_3.14", "okhttp_4.9", "okhttp_4.10", "okhttp_5",
+	"edge_101", "edge_122", "edge_127",
+];
+
+#[test]
+fn presets_match_golden_fingerprints() {
+	let runtime = Runtime::new().unwrap();
+	let update = std::env::var_os("UPDATE_FINGERPRINTS").is_some();
+	let golden_dir = PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("tests/fingerprints");
+	let mut failures = Vec::new();
+
+	for preset in PRESETS {
+		let config = ImpersonateConfig {
+			preset: Some(preset.to_string()),
+			..ImpersonateConfig::default()
+		};
+		let client = NativeClient::new(config).unwrap();
+		let fingerprint = runtime.block_on(client.fingerprint())
+			.unwrap_or_else(|err| panic!("failed to fingerprint {preset}: {err}"));
+
+		// JA3 is left out since it changes on every connection for presets that permute their extensions
+		let actual = format!(
+			"ja4: {}\nja4_r: {}\nakamai: {}\n",
+			fingerprint.ja4,
+			fingerprint.ja4_raw,
+			fingerprint.akamai.as_deref().unwrap_or("none"),
+		);
+
+		let path = golden_dir.join(format!("{preset}.txt"));
+		match fs::read_to_string(&path) {
+			Ok(expected) if expected == actual => {}
+			_ if update => {
+				fs::create_dir_all(&golden_dir).unwrap();
+				fs::write(&path, &actual).unwrap();
+			}
+			Ok(expected) => {
+				failures.push(format!("{preset} changed:\n--- expected\n{expected}+++ actual\n{actual}"));
+			}
+			Err(_) => {
+				failures.push(format!("{preset} has no golden file, run with UPDATE_FINGERPRINTS=1 to write it:\n{actual}"));
+			}
+		}
+	}
+
+	assert!(failures.is_empty(), "{} preset(s) did not match their golden fingerprints:\n\n{}", failures.len(), failures.join("\n"));
+}
+
+#[test]
+fn presets_are_deterministic() {
+	let runtime = Runtime::new().unwrap();
+
+	// Fingerprints must not depend on which connection they were observed on, otherwise golden files would be flaky
+	for preset in ["chrome_129", "safari_18", "okhttp_5"] {
+		let config = ImpersonateConfig {
+			preset: Some(preset.to_owned()),
+			..ImpersonateConfig::default()
+		};
+		let client = NativeClient::new(config).unwrap();
+		let first = runtime.block_on(client.fingerprint()).unwrap();
+		let second = runtime.block_on(client.fingerprint()).unwrap();
+
+		assert_eq!(first.ja4_raw, second.ja4_raw, "{preset}");
+		assert_eq!(first.akamai, second.akamai, "{preset}");
+	}
+}
+
+#[test]
+fn grease_values() {
+	assert!(is_grease(0x0a0a));
+	assert!(is_grease(0xfafa));
+	assert!(!is_grease(0x0a1a));
+	assert!(!is_grease(0x1301));
+}
+
+#[test]
+fn pseudo_header_order_from_hpack() {
+	// `:method: GET`, `:authority: abc` (literal with indexed name), `:scheme: https`, `:path: /`, `accept: */*`
+	let block = [0x82, 0x41, 0x03, b'a', b'b', b'c', 0x87, 0x84, 0x53, 0x03, b'*', b'/', b'*'];
+	assert_eq!(pseudo_header_order(&block), vec!['m', 'a', 's', 'p']);
+}
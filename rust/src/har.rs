@@ -0,0 +1,447 @@
+use crate::requests::ResponseParts;
+use bytes::BytesMut;
+use futures_util::StreamExt;
+use log::warn;
+use rquest::header::{HeaderMap, HeaderName, CONTENT_TYPE, LOCATION};
+use rquest::tls::TlsInfo;
+use rquest::{Request, Response, Version};
+use std::fmt::{Debug, Write as _};
+use std::fs::File;
+use std::io::{Seek, SeekFrom, Write};
+use std::path::PathBuf;
+use std::sync::{mpsc, Arc};
+use std::thread::{self, JoinHandle};
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+
+/// The end of a HAR file, which is rewritten after every entry so that the file is always a complete document.
+const FILE_TRAILER: &str = "\n]}}\n";
+
+/// Config for recording requests into HAR 1.2 entries.
+#[derive(Debug, Clone)]
+pub struct HarConfig {
+	/// A file to write the recorded log to, which is replaced when the client is created.
+	pub path: Option<PathBuf>,
+	/// Receives each entry as soon as it has been recorded.
+	pub listener: Option<Arc<dyn HarListener>>,
+	/// The maximum size of request and response bodies to include in entries, or `0` to not include them.
+	pub max_body_size: usize,
+}
+
+/// A receiver of recorded HAR entries.
+pub trait HarListener: Send + Sync + Debug {
+	/// Handles a single entry of the `log.entries` array, serialized as JSON.
+	/// This is called on the thread that finished the entry, and should not block.
+	fn on_entry(&self, entry: String);
+}
+
+/// Records every request sent by a client as HAR entries.
+///
+/// Only what is visible outside of rquest is recorded, which leaves out the headers it adds while sending a request
+/// (ie. a preset's default headers, `Host`, `Cookie`, and `Content-Length`) and the timings of DNS, connecting, and TLS.
+/// rquest 0.23 neither exposes the request as it was written to the connection nor hooks into its connector,
+/// so entries say so in the `comment` of their request and timings instead.
+pub struct HarRecorder {
+	config: HarConfig,
+	/// Sends finished entries to the thread appending them to the file, if any.
+	/// Entries are dropped on tokio worker threads, which should not block on file IO.
+	file_writer: Option<(mpsc::Sender<String>, JoinHandle<()>)>,
+}
+
+/// Included in the request of every entry, since rquest adds headers after the request has been recorded.
+const REQUEST_COMMENT: &str = "Headers added by rquest while sending the request (the preset's default headers, Host, Cookie, and Content-Length) are not included";
+
+/// Included in the timings of every entry, since rquest does not expose the timings of its connector.
+const TIMINGS_COMMENT: &str = "dns, connect, and ssl are not measurable, and are included in wait for new connections";
+
+/// A HAR file that entries are appended to.
+struct HarFile {
+	file: File,
+	has_entries: bool,
+}
+
+impl HarFile {
+	fn append(&mut self, entry: &str) {
+		let separator = if self.has_entries { ",\n" } else { "\n" };
+
+		// Overwrite the trailer, and then write it back after the entry
+		let result = self.file.seek(SeekFrom::End(-(FILE_TRAILER.len() as i64)))
+			.and_then(|_| write!(self.file, "{separator}{entry}{FILE_TRAILER}"))
+			.and_then(|_| self.file.flush());
+		match result {
+			Ok(()) => self.has_entries = true,
+			Err(err) => warn!("Failed to write HAR entry: {err}"),
+		}
+	}
+}
+
+/// The parts of a request that are recorded, captured before it is sent.
+/// This is shared between all the attempts made for a request.
+pub struct HarRequest {
+	/// The `request` object of an entry serialized as JSON, without its closing brace.
+	/// `httpVersion` is appended later, since it is only known once a response is received.
+	json: String,
+}
+
+/// An entry that is being recorded for a single attempt at sending a request.
+/// This is written once dropped, which happens after the response body has been read or closed.
+pub struct HarEntry {
+	recorder: Arc<HarRecorder>,
+	request: Arc<HarRequest>,
+	started: SystemTime,
+	start: Instant,
+	/// How long the request was waiting before this attempt was sent.
+	blocked: Duration,
+	/// How long it took to receive the response headers after sending the request.
+	wait: Option<Duration>,
+	response: Option<HarResponse>,
+	error: Option<String>,
+}
+
+/// The parts of a response that are recorded.
+struct HarResponse {
+	version: Version,
+	status: u16,
+	headers: HeaderMap,
+	server_address: Option<String>,
+	peer_certificate: Option<Vec<u8>>,
+	/// The size of the response body that has been read so far.
+	body_size: usize,
+	/// The response body, as long as it is being recorded and has not exceeded the maximum size.
+	body: Option<BytesMut>,
+	/// How long it took to read the response body after receiving the headers.
+	receive: Option<Duration>,
+}
+
+impl HarRecorder {
+	pub fn new(config: HarConfig) -> std::io::Result<Self> {
+		let file_writer = match &config.path {
+			None => None,
+			Some(path) => {
+				let mut file = File::create(path)?;
+				write!(
+					file,
+					r#"{{"log":{{"version":"1.2","creator":{{"name":"ktor-impersonate","version":"{}"}},"entries":["#,
+					env!("CARGO_PKG_VERSION"),
+				)?;
+				file.write_all(FILE_TRAILER.as_bytes())?;
+
+				// The thread exits once the recorder is dropped and every entry sent before then has been written
+				let (sender, receiver) = mpsc::channel::<String>();
+				let handle = thread::Builder::new()
+					.name("har-writer".to_owned())
+					.spawn(move || {
+						let mut file = HarFile { file, has_entries: false };
+						for entry in receiver {
+							file.append(&entry);
+						}
+					})?;
+				Some((sender, handle))
+			}
+		};
+
+		Ok(Self { config, file_writer })
+	}
+
+	/// Captures a request before it is sent, which has to happen before its body is consumed.
+	/// Streaming request bodies are never included, since they are passed through without being buffered.
+	pub fn request(&self, request: &Request) -> Arc<HarRequest> {
+		let url = request.url();
+		let mut json = String::new();
+
+		write!(json, r#"{{"method":{},"url":{},"cookies":[],"headers":"#,
+			json_string(request.method().as_str()),
+			json_string(url.as_str()),
+		).unwrap();
+		write_headers(&mut json, request.headers());
+
+		json.push_str(r#","queryString":["#);
+		for (i, (name, value)) in url.query_pairs().enumerate() {
+			if i > 0 { json.push(','); }
+			write!(json, r#"{{"name":{},"value":{}}}"#, json_string(&name), json_string(&value)).unwrap();
+		}
+		json.push(']');
+
+		let body = request.body().map(|body| body.as_bytes());
+		let body_size = match body {
+			None => 0,
+			Some(Some(bytes)) => bytes.len() as i64,
+			Some(None) => -1,
+		};
+		if let Some(Some(bytes)) = body.filter(|_| self.config.max_body_size > 0) {
+			if bytes.len() <= self.config.max_body_size {
+				let mime_type = header_str(request.headers(), CONTENT_TYPE);
+				let text = String::from_utf8_lossy(bytes);
+				write!(json, r#","postData":{{"mimeType":{},"text":{}}}"#, json_string(mime_type), json_string(&text)).unwrap();
+			}
+		}
+
+		write!(json, r#","headersSize":-1,"bodySize":{body_size},"comment":{}"#, json_string(REQUEST_COMMENT)).unwrap();
+
+		Arc::new(HarRequest { json })
+	}
+
+	/// Starts recording an attempt at sending a request, which is about to be sent.
+	pub fn entry(self: &Arc<Self>, request: &Arc<HarRequest>, blocked: Duration) -> HarEntry {
+		HarEntry {
+			recorder: self.clone(),
+			request: request.clone(),
+			started: SystemTime::now(),
+			start: Instant::now(),
+			blocked,
+			wait: None,
+			response: None,
+			error: None,
+		}
+	}
+
+	/// Passes a finished entry to the listener and queues it to be appended to the file.
+	fn write(&self, entry: String) {
+		if let Some((sender, _)) = &self.file_writer {
+			// The writer thread only stops once this recorder is dropped
+			let _ = sender.send(entry.clone());
+		}
+
+		if let Some(listener) = &self.config.listener {
+			listener.on_entry(entry);
+		}
+	}
+}
+
+impl Drop for HarRecorder {
+	/// Waits for the queued entries to be written, so that the file is complete once the client is closed.
+	fn drop(&mut self) {
+		if let Some((sender, handle)) = self.file_writer.take() {
+			drop(sender);
+			let _ = handle.join();
+		}
+	}
+}
+
+impl HarEntry {
+	/// Records the headers of the response received for this attempt.
+	pub fn set_response(&mut self, response: &Response) {
+		self.wait = Some(self.start.elapsed());
+		self.response = Some(HarResponse {
+			version: response.version(),
+			status: response.status().as_u16(),
+			headers: response.headers().clone(),
+			server_address: response.remote_addr().map(|address| address.ip().to_string()),
+			peer_certificate: response.extensions().get::<TlsInfo>()
+				.and_then(|info| info.peer_certificate())
+				.map(|der| der.to_vec()),
+			body_size: 0,
+			body: (self.recorder.config.max_body_size > 0).then(BytesMut::new),
+			receive: None,
+		});
+	}
+
+	/// Records the error this attempt failed with.
+	pub fn set_error(&mut self, error: String) {
+		self.wait = Some(self.start.elapsed());
+		self.error = Some(error);
+	}
+
+	/// Wraps the body of the response to this attempt, in order to record it as it is read.
+	/// This entry is written once the body has ended, failed, or was closed early.
+	pub fn record_body(self, mut response: ResponseParts) -> ResponseParts {
+		let state = (response.body, self);
+
+		response.body = futures_util::stream::unfold(state, |(mut body, mut entry)| async move {
+			let chunk = body.next().await;
+			let har_response = entry.response.as_mut().expect("BUG: recording body without a response");
+
+			match &chunk {
+				Some(Ok(bytes)) => {
+					har_response.body_size += bytes.len();
+					if har_response.body.as_ref().is_some_and(|buf| buf.len() + bytes.len() > entry.recorder.config.max_body_size) {
+						har_response.body = None;
+					}
+					if let Some(buf) = &mut har_response.body {
+						buf.extend_from_slice(bytes);
+					}
+				}
+				Some(Err(err)) => entry.error = Some(format!("Failed to read response body: {err}")),
+				None => {}
+			}
+
+			if !matches!(chunk, Some(Ok(_))) {
+				har_response.receive = Some(entry.start.elapsed().saturating_sub(entry.wait.unwrap_or_default()));
+			}
+
+			chunk.map(|chunk| (chunk, (body, entry)))
+		}).boxed();
+
+		response
+	}
+
+	/// Serializes this entry into JSON.
+	fn to_json(&self) -> String {
+		let blocked = millis(self.blocked);
+		let wait = self.wait.map_or(0.0, millis);
+		let receive = self.response.as_ref()
+			.map_or(0.0, |response| millis(response.receive.unwrap_or_else(|| self.start.elapsed().saturating_sub(self.wait.unwrap_or_default()))));
+		let version = self.response.as_ref().map_or("", |response| version_name(response.version));
+
+		let mut json = String::new();
+		write!(json, r#"{{"startedDateTime":{},"time":{},"request":{},"httpVersion":{}}},"response":"#,
+			json_string(&iso_8601(self.started)),
+			blocked + wait + receive,
+			self.request.json,
+			json_string(version),
+		).unwrap();
+
+		match &self.response {
+			Some(response) => response.write_json(&mut json),
+			None => json.push_str(r#"{"status":0,"statusText":"","httpVersion":"","cookies":[],"headers":[],"content":{"size":0,"mimeType":""},"redirectURL":"","headersSize":-1,"bodySize":-1}"#),
+		}
+
+		write!(json, r#","cache":{{}},"timings":{{"blocked":{blocked},"dns":-1,"connect":-1,"ssl":-1,"send":0,"wait":{wait},"receive":{receive},"comment":{}}}"#,
+			json_string(TIMINGS_COMMENT)).unwrap();
+
+		if let Some(address) = self.response.as_ref().and_then(|response| response.server_address.as_deref()) {
+			write!(json, r#","serverIPAddress":{}"#, json_string(address)).unwrap();
+		}
+		if let Some(certificate) = self.response.as_ref().and_then(|response| response.peer_certificate.as_deref()) {
+			write!(json, r#","_securityDetails":{{"peerCertificate":{}}}"#, json_string(&base64(certificate))).unwrap();
+		}
+		if let Some(error) = &self.error {
+			write!(json, r#","_error":{}"#, json_string(error)).unwrap();
+		}
+
+		json.push('}');
+		json
+	}
+}
+
+impl Drop for HarEntry {
+	fn drop(&mut self) {
+		let json = self.to_json();
+		self.recorder.write(json);
+	}
+}
+
+impl HarResponse {
+	fn write_json(&self, json: &mut String) {
+		let status_text = rquest::StatusCode::from_u16(self.status).ok()
+			.and_then(|status| status.canonical_reason())
+			.unwrap_or_default();
+
+		write!(json, r#"{{"status":{},"statusText":{},"httpVersion":{},"cookies":[],"headers":"#,
+			self.status,
+			json_string(status_text),
+			json_string(version_name(self.version)),
+		).unwrap();
+		write_headers(json, &self.headers);
+
+		// The body is decoded by rquest, so its size on the wire is not known
+		write!(json, r#","content":{{"size":{},"mimeType":{}"#, self.body_size, json_string(header_str(&self.headers, CONTENT_TYPE))).unwrap();
+		if let Some(body) = &self.body {
+			match std::str::from_utf8(body) {
+				Ok(text) => write!(json, r#","text":{}"#, json_string(text)).unwrap(),
+				Err(_) => write!(json, r#","text":{},"encoding":"base64""#, json_string(&base64(body))).unwrap(),
+			}
+		}
+
+		write!(json, r#"}},"redirectURL":{},"headersSize":-1,"bodySize":-1}}"#, json_string(header_str(&self.headers, LOCATION))).unwrap();
+	}
+}
+
+/// Writes headers as a HAR array of name-value pairs, in the order they are stored in.
+fn write_headers(json: &mut String, headers: &HeaderMap) {
+	json.push('[');
+	for (i, (name, value)) in headers.iter().enumerate() {
+		if i > 0 { json.push(','); }
+		let value = String::from_utf8_lossy(value.as_bytes());
+		write!(json, r#"{{"name":{},"value":{}}}"#, json_string(name.as_str()), json_string(&value)).unwrap();
+	}
+	json.push(']');
+}
+
+/// Gets the value of a header if it is present and valid UTF-8, otherwise an empty string.
+fn header_str(headers: &HeaderMap, name: HeaderName) -> &str {
+	headers.get(name)
+		.and_then(|value| value.to_str().ok())
+		.unwrap_or_default()
+}
+
+/// The name of an HTTP version, in the form used by HAR.
+fn version_name(version: Version) -> &'static str {
+	match version {
+		Version::HTTP_09 => "HTTP/0.9",
+		Version::HTTP_10 => "HTTP/1.0",
+		Version::HTTP_11 => "HTTP/1.1",
+		Version::HTTP_2 => "HTTP/2.0",
+		Version::HTTP_3 => "HTTP/3.0",
+		_ => "",
+	}
+}
+
+/// Serializes a string as a JSON string literal.
+pub fn json_string(value: &str) -> String {
+	let mut out = String::with_capacity(value.len() + 2);
+	out.push('"');
+	for char in value.chars() {
+		match char {
+			'"' => out.push_str("\\\""),
+			'\\' => out.push_str("\\\\"),
+			'\n' => out.push_str("\\n"),
+			'\r' => out.push_str("\\r"),
+			'\t' => out.push_str("\\t"),
+			char if char < ' ' => write!(out, "\\u{:04x}", char as u32).unwrap(),
+			char => out.push(char),
+		}
+	}
+	out.push('"');
+	out
+}
+
+/// Formats a time as an ISO 8601 date in UTC with millisecond precision.
+fn iso_8601(time: SystemTime) -> String {
+	let since_epoch = time.duration_since(UNIX_EPOCH).unwrap_or_default();
+	let seconds = since_epoch.as_secs();
+	let (days, seconds_of_day) = ((seconds / 86400) as i64, seconds % 86400);
+
+	// Converts days since the epoch into a civil date (https://howardhinnant.github.io/date_algorithms.html#civil_from_days)
+	let z = days + 719468;
+	let era = z.div_euclid(146097);
+	let day_of_era = z.rem_euclid(146097);
+	let year_of_era = (day_of_era - day_of_era / 1460 + day_of_era / 36524 - day_of_era / 146096) / 365;
+	let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+	let mp = (5 * day_of_year + 2) / 153;
+	let day = day_of_year - (153 * mp + 2) / 5 + 1;
+	let month = if mp < 10 { mp + 3 } else { mp - 9 };
+	let year = year_of_era + era * 400 + if month <= 2 { 1 } else { 0 };
+
+	format!(
+		"{year:04}-{month:02}-{day:02}T{:02}:{:02}:{:02}.{:03}Z",
+		seconds_of_day / 3600,
+		seconds_of_day / 60 % 60,
+		seconds_of_day % 60,
+		since_epoch.subsec_millis(),
+	)
+}
+
+/// Encodes bytes as standard base64 with padding.
+fn base64(data: &[u8]) -> String {
+	const ALPHABET: &[u8; 64] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+
+	let mut out = String::with_capacity(data.len().div_ceil(3) * 4);
+	for chunk in data.chunks(3) {
+		let bits = chunk.iter().enumerate().fold(0u32, |bits, (i, byte)| bits | (*byte as u32) << (16 - i * 8));
+		for i in 0..4 {
+			match i <= chunk.len() {
+				true => out.push(ALPHABET[(bits >> (18 - i * 6)) as usize & 63] as char),
+				false => out.push('='),
+			}
+		}
+	}
+	out
+}
+
+/// Converts a duration into fractional milliseconds.
+fn millis(duration: Duration) -> f64 {
+	duration.as_secs_f64() * 1000.0
+}
+
+#[cfg(test)]
+mod tests;
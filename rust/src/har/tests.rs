@@ -0,0 +1,70 @@
+use super::*;
+
+fn time(seconds: u64, millis: u64) -> SystemTime {
+	UNIX_EPOCH + Duration::from_secs(seconds) + Duration::from_millis(millis)
+}
+
+#[test]
+fn escapes_json_strings() {
+	assert_eq!(json_string(""), r#""""#);
+	assert_eq!(json_string(r#"say "hi" \ bye"#), r#""say \"hi\" \\ bye""#);
+	assert_eq!(json_string("a\nb\rc\td"), r#""a\nb\rc\td""#);
+	assert_eq!(json_string("\u{0}\u{8}\u{1f}"), r#""\u0000\u0008\u001f""#);
+	assert_eq!(json_string("\u{7f} é 🦀"), "\"\u{7f} é 🦀\"");
+}
+
+#[test]
+fn formats_epoch() {
+	assert_eq!(iso_8601(UNIX_EPOCH), "1970-01-01T00:00:00.000Z");
+	assert_eq!(iso_8601(time(0, 999)), "1970-01-01T00:00:00.999Z");
+}
+
+#[test]
+fn formats_leap_days() {
+	// 2000 is a leap year despite being divisible by 100, since it is divisible by 400
+	assert_eq!(iso_8601(time(951_782_400, 0)), "2000-02-29T00:00:00.000Z");
+	assert_eq!(iso_8601(time(951_868_800, 0)), "2000-03-01T00:00:00.000Z");
+	assert_eq!(iso_8601(time(1_709_251_199, 0)), "2024-02-29T23:59:59.000Z");
+	// 2100 is not a leap year
+	assert_eq!(iso_8601(time(4_107_456_000, 0)), "2100-02-28T00:00:00.000Z");
+	assert_eq!(iso_8601(time(4_107_542_400, 0)), "2100-03-01T00:00:00.000Z");
+}
+
+#[test]
+fn formats_end_of_year() {
+	assert_eq!(iso_8601(time(1_704_067_199, 123)), "2023-12-31T23:59:59.123Z");
+	assert_eq!(iso_8601(time(1_704_067_200, 0)), "2024-01-01T00:00:00.000Z");
+}
+
+#[test]
+fn formats_times_before_epoch_as_epoch() {
+	assert_eq!(iso_8601(UNIX_EPOCH - Duration::from_secs(1)), "1970-01-01T00:00:00.000Z");
+}
+
+#[test]
+fn encodes_base64_with_padding() {
+	assert_eq!(base64(b""), "");
+	assert_eq!(base64(b"f"), "Zg==");
+	assert_eq!(base64(b"fo"), "Zm8=");
+	assert_eq!(base64(b"foo"), "Zm9v");
+	assert_eq!(base64(b"foob"), "Zm9vYg==");
+	assert_eq!(base64(b"fooba"), "Zm9vYmE=");
+	assert_eq!(base64(b"foobar"), "Zm9vYmFy");
+	assert_eq!(base64(&[0xFF, 0xFE, 0xFD]), "//79");
+}
+
+#[test]
+fn writes_complete_file() {
+	let path = std::env::temp_dir().join(format!("ktor-impersonate-har-test-{}.har", std::process::id()));
+	let recorder = HarRecorder::new(HarConfig { path: Some(path.clone()), listener: None, max_body_size: 0 }).unwrap();
+
+	recorder.write(r#"{"entry":1}"#.to_owned());
+	recorder.write(r#"{"entry":2}"#.to_owned());
+	drop(recorder);
+
+	let contents = std::fs::read_to_string(&path).unwrap();
+	std::fs::remove_file(&path).unwrap();
+
+	assert!(contents.starts_with(r#"{"log":{"version":"1.2","#), "{contents}");
+	assert!(contents.ends_with("\"entries\":[\n{\"entry\":1},\n{\"entry\":2}\n]}}\n"), "{contents}");
+}
@@ -0,0 +1,93 @@
+use crate::requests::{RequestBodyChunk, RequestTask, ACTIVE_REQUESTS};
+use crate::throw;
+use bytes::Bytes;
+use catch_panic::catch_panic;
+use futures_core::Stream;
+use jni::objects::{JByteArray, JClass, JString};
+use jni::sys::{jboolean, jint, JNI_FALSE, JNI_TRUE};
+use jni::JNIEnv;
+use jni_fn::jni_fn;
+use tokio::sync::mpsc;
+
+/// The amount of chunks that can be buffered before [writeRequestBody] blocks.
+/// This is kept at 1 so that the JVM side only reads the body as fast as it is sent.
+const REQUEST_BODY_BUFFER: usize = 1;
+
+/// Creates a channel for a streaming request body. The JVM side writes chunks into the sender
+/// through [writeRequestBody], and the receiver is converted into a stream used as the request's body.
+pub fn request_body_channel() -> (mpsc::Sender<RequestBodyChunk>, impl Stream<Item=RequestBodyChunk>) {
+	let (sender, receiver) = mpsc::channel::<RequestBodyChunk>(REQUEST_BODY_BUFFER);
+
+	let stream = futures_util::stream::unfold(receiver, |mut receiver| async move {
+		receiver.recv().await.map(|chunk| (chunk, receiver))
+	});
+
+	(sender, stream)
+}
+
+#[catch_panic(default = "JNI_FALSE")]
+#[jni_fn("dev.rushii.ktor_impersonate.internal.NativeEngine")]
+pub fn writeRequestBody<'l>(
+	mut env: JNIEnv<'l>,
+	_cls: JClass<'l>,
+	request_id: u32, // matches jint with different representation
+	bytes: JByteArray<'l>,
+	length: jint,
+) -> jboolean {
+	// Clone the sender so the ACTIVE_REQUESTS entry is not locked while blocking
+	let Some(sender) = get_request_body_sender(request_id) else {
+		return JNI_FALSE;
+	};
+
+	let mut chunk = vec![0u8; length as usize];
+	// SAFETY: i8 and u8 have the same layout
+	let chunk_signed = unsafe { std::slice::from_raw_parts_mut(chunk.as_mut_ptr() as *mut i8, chunk.len()) };
+	if let Err(err) = env.get_byte_array_region(&bytes, 0, chunk_signed) {
+		throw!(env, &*format!("Failed to read request body chunk: {err:?}"), JNI_FALSE);
+	}
+
+	// This is never called from a tokio worker thread, so blocking is fine
+	match sender.blocking_send(Ok(Bytes::from(chunk))) {
+		Ok(()) => JNI_TRUE,
+		Err(_) => JNI_FALSE, // Request was cancelled or already failed
+	}
+}
+
+#[catch_panic]
+#[jni_fn("dev.rushii.ktor_impersonate.internal.NativeEngine")]
+pub fn closeRequestBody<'l>(
+	mut env: JNIEnv<'l>,
+	_cls: JClass<'l>,
+	request_id: u32, // matches jint with different representation
+	error: JString<'l>,
+) {
+	// Take the sender out of the request so that the body stream ends once it is dropped
+	let sender = match ACTIVE_REQUESTS.get_mut(&request_id) {
+		None => return,
+		Some(mut entry) => match entry.value_mut() {
+			RequestTask::PendingResponse { request_body, .. } => request_body.take(),
+			_ => None,
+		}
+	};
+	let Some(sender) = sender else { return; };
+
+	if !error.is_null() {
+		let message: String = match env.get_string(&error) {
+			Ok(str) => str.into(),
+			Err(err) => throw!(env, &*format!("Failed to get request body error: {err:?}")),
+		};
+
+		// Request might have already been cancelled
+		let _ = sender.blocking_send(Err(format!("Failed to write request body: {message}").into()));
+	}
+}
+
+/// Gets a clone of the request body sender for an active request, if it is still accepting chunks.
+fn get_request_body_sender(request_id: u32) -> Option<mpsc::Sender<RequestBodyChunk>> {
+	let entry = ACTIVE_REQUESTS.get(&request_id)?;
+
+	match entry.value() {
+		RequestTask::PendingResponse { request_body, .. } => request_body.clone(),
+		_ => None,
+	}
+}
@@ -55,30 +55,94 @@ cache_ref!(Class_getName: JMethodID);
 cache_ref!(IllegalArgumentException: GlobalRef);
 cache_ref!(List: GlobalRef);
 cache_ref!(List_toArray: JMethodID);
+cache_ref!(Integer: GlobalRef);
+cache_ref!(Integer_intValue: JMethodID);
 cache_ref!(Long: GlobalRef);
 cache_ref!(Long_longValue: JMethodID);
+cache_ref!(Object: GlobalRef);
+cache_ref!(Object_toString: JMethodID);
 cache_ref!(RuntimeException: GlobalRef);
 cache_ref!(Set: GlobalRef);
 cache_ref!(Set_toArray: JMethodID);
 
-// Kotlinx
-cache_ref!(Sink: GlobalRef);
-cache_ref!(Sink_write: JMethodID);
-
 // ktor-impersonate
+cache_ref!(DnsResolver: GlobalRef);
+cache_ref!(DnsResolver_resolve: JMethodID);
+cache_ref!(HarListener: GlobalRef);
+cache_ref!(HarListener_onEntry: JMethodID);
 cache_ref!(ImpersonateConfig: GlobalRef);
 cache_ref!(ImpersonateConfig_getVerboseLogging: JMethodID);
 cache_ref!(ImpersonateConfig_getPreset: JMethodID);
 cache_ref!(ImpersonateConfig_getRequestTimeoutMillis: JMethodID);
 cache_ref!(ImpersonateConfig_getConnectTimeoutMillis: JMethodID);
 cache_ref!(ImpersonateConfig_getIdleTimeout: JMethodID);
+cache_ref!(ImpersonateConfig_getMaxIdleConnectionsPerHost: JMethodID);
+cache_ref!(ImpersonateConfig_getTcpKeepAliveMillis: JMethodID);
+cache_ref!(ImpersonateConfig_getTcpNoDelay: JMethodID);
+cache_ref!(ImpersonateConfig_getHttp2KeepAliveIntervalMillis: JMethodID);
+cache_ref!(ImpersonateConfig_getHttp2KeepAliveTimeoutMillis: JMethodID);
+cache_ref!(ImpersonateConfig_getHttp2KeepAliveWhileIdle: JMethodID);
 cache_ref!(ImpersonateConfig_getAllowInvalidCertificates: JMethodID);
 cache_ref!(ImpersonateConfig_getHttpsOnly: JMethodID);
+cache_ref!(ImpersonateConfig_getHeaderOrder: JMethodID);
+cache_ref!(ImpersonateConfig_getHttp1TitleCaseHeaders: JMethodID);
+cache_ref!(ImpersonateConfig_getDecompression: JMethodID);
+cache_ref!(ImpersonateConfig_getGzip: JMethodID);
+cache_ref!(ImpersonateConfig_getBrotli: JMethodID);
+cache_ref!(ImpersonateConfig_getDeflate: JMethodID);
+cache_ref!(ImpersonateConfig_getZstd: JMethodID);
+cache_ref!(ImpersonateConfig_getDnsResolver: JMethodID);
+cache_ref!(ImpersonateConfig_getDnsOverHttpsUrl: JMethodID);
+cache_ref!(ImpersonateConfig_getDnsOverHttpsFallback: JMethodID);
+cache_ref!(ImpersonateConfig_getIpPreferenceName: JMethodID);
+cache_ref!(ImpersonateConfig_getHttpVersionName: JMethodID);
+cache_ref!(ImpersonateConfig_getRetryMaxAttempts: JMethodID);
+cache_ref!(ImpersonateConfig_getRetryOnConnectionFailure: JMethodID);
+cache_ref!(ImpersonateConfig_getRetryStatusCodesArray: JMethodID);
+cache_ref!(ImpersonateConfig_getRetryBaseDelayMillis: JMethodID);
+cache_ref!(ImpersonateConfig_getRetryMaxDelayMillis: JMethodID);
+cache_ref!(ImpersonateConfig_getRespectRetryAfter: JMethodID);
+cache_ref!(ImpersonateConfig_getRateLimitValues: JMethodID);
+cache_ref!(ImpersonateConfig_getRateLimitPerHostValues: JMethodID);
+cache_ref!(ImpersonateConfig_getMaxConcurrentRequests: JMethodID);
+cache_ref!(ImpersonateConfig_getMaxConcurrentRequestsPerHost: JMethodID);
+cache_ref!(ImpersonateConfig_getResponseCacheSizes: JMethodID);
+cache_ref!(ImpersonateConfig_getResponseCacheDirectory: JMethodID);
+cache_ref!(ImpersonateConfig_getHarFile: JMethodID);
+cache_ref!(ImpersonateConfig_getHarListener: JMethodID);
+cache_ref!(ImpersonateConfig_getHarMaxBodySize: JMethodID);
+cache_ref!(ImpersonateConfig_getLocalAddress: JMethodID);
+cache_ref!(ImpersonateConfig_getNetworkInterface: JMethodID);
+cache_ref!(ImpersonateConfig_getUnixSocket: JMethodID);
+cache_ref!(ImpersonateConfig_getHostOverridesArray: JMethodID);
 cache_ref!(NativeCallbacks: GlobalRef);
 cache_ref!(NativeCallbacks_onError: JMethodID);
 cache_ref!(NativeCallbacks_onResponse: JMethodID);
+cache_ref!(NativeDownloadCallbacks: GlobalRef);
+cache_ref!(NativeDownloadCallbacks_onComplete: JMethodID);
+cache_ref!(NativeDownloadCallbacks_onError: JMethodID);
+cache_ref!(NativePreconnectCallbacks: GlobalRef);
+cache_ref!(NativePreconnectCallbacks_onConnected: JMethodID);
+cache_ref!(NativePreconnectCallbacks_onError: JMethodID);
+cache_ref!(NativeFileContent: GlobalRef);
+cache_ref!(NativeFileContent_getPath: JMethodID);
+cache_ref!(NativeFileContent_getOffset: JMethodID);
+cache_ref!(NativeFileContent_getLengthValue: JMethodID);
+cache_ref!(NativeFileContent_getProgressListener: JMethodID);
+cache_ref!(RequestConfig: GlobalRef);
+cache_ref!(RequestConfig_getHeaderOrder: JMethodID);
+cache_ref!(RequestConfig_getRequestTimeoutMillis: JMethodID);
+cache_ref!(RequestConfig_getConnectTimeoutMillis: JMethodID);
+cache_ref!(RequestConfig_getSocketTimeoutMillis: JMethodID);
+cache_ref!(RequestConfig_getLocalAddress: JMethodID);
+cache_ref!(RequestConfig_getNetworkInterface: JMethodID);
+cache_ref!(RequestConfig_getHttpVersionName: JMethodID);
+cache_ref!(RequestConfig_getUnixSocket: JMethodID);
 cache_ref!(ResponseSource: GlobalRef);
 cache_ref!(ResponseSource_requestId: JFieldID);
+cache_ref!(ResponseSource_onTrailers: JMethodID);
+cache_ref!(UploadProgressListener: GlobalRef);
+cache_ref!(UploadProgressListener_onProgress: JMethodID);
 
 // Ktor
 cache_ref!(HeadersBuilder: GlobalRef);
@@ -108,30 +172,94 @@ pub(super) fn init_cache(mut env: JNIEnv) -> bool {
 	init_IllegalArgumentException(class_ref(&mut env, "java/lang/IllegalArgumentException"));
 	init_List(class_ref(&mut env, "java/util/List"));
 	init_List_toArray(env.get_method_id(&List(), "toArray", "()[Ljava/lang/Object;").unwrap());
+	init_Integer(class_ref(&mut env, "java/lang/Integer"));
+	init_Integer_intValue(env.get_method_id(&Integer(), "intValue", "()I").unwrap());
 	init_Long(class_ref(&mut env, "java/lang/Long"));
 	init_Long_longValue(env.get_method_id(&Long(), "longValue", "()J").unwrap());
+	init_Object(class_ref(&mut env, "java/lang/Object"));
+	init_Object_toString(env.get_method_id(&Object(), "toString", "()Ljava/lang/String;").unwrap());
 	init_RuntimeException(class_ref(&mut env, "java/lang/RuntimeException"));
 	init_Set(class_ref(&mut env, "java/util/Set"));
 	init_Set_toArray(env.get_method_id(&Set(), "toArray", "()[Ljava/lang/Object;").unwrap());
 
-	// Kotlinx
-	init_Sink(class_ref(&mut env, "kotlinx/io/Sink"));
-	init_Sink_write(env.get_method_id(&Sink(), "write", "([BII)V").unwrap());
-
 	// ktor-impersonate
+	init_DnsResolver(class_ref(&mut env, "dev/rushii/ktor_impersonate/DnsResolver"));
+	init_DnsResolver_resolve(env.get_method_id(&DnsResolver(), "resolve", "(Ljava/lang/String;)Ljava/util/List;").unwrap());
+	init_HarListener(class_ref(&mut env, "dev/rushii/ktor_impersonate/HarListener"));
+	init_HarListener_onEntry(env.get_method_id(&HarListener(), "onEntry", "(Ljava/lang/String;)V").unwrap());
 	init_ImpersonateConfig(class_ref(&mut env, "dev/rushii/ktor_impersonate/ImpersonateConfig"));
 	init_ImpersonateConfig_getVerboseLogging(env.get_method_id(&ImpersonateConfig(), "getVerboseLogging", "()Z").unwrap());
 	init_ImpersonateConfig_getPreset(env.get_method_id(&ImpersonateConfig(), "getPreset", "()Ljava/lang/String;").unwrap());
 	init_ImpersonateConfig_getRequestTimeoutMillis(env.get_method_id(&ImpersonateConfig(), "getRequestTimeoutMillis", "()Ljava/lang/Long;").unwrap());
 	init_ImpersonateConfig_getConnectTimeoutMillis(env.get_method_id(&ImpersonateConfig(), "getConnectTimeoutMillis", "()Ljava/lang/Long;").unwrap());
 	init_ImpersonateConfig_getIdleTimeout(env.get_method_id(&ImpersonateConfig(), "getIdleTimeout", "()Ljava/lang/Long;").unwrap());
+	init_ImpersonateConfig_getMaxIdleConnectionsPerHost(env.get_method_id(&ImpersonateConfig(), "getMaxIdleConnectionsPerHost", "()Ljava/lang/Integer;").unwrap());
+	init_ImpersonateConfig_getTcpKeepAliveMillis(env.get_method_id(&ImpersonateConfig(), "getTcpKeepAliveMillis", "()Ljava/lang/Long;").unwrap());
+	init_ImpersonateConfig_getTcpNoDelay(env.get_method_id(&ImpersonateConfig(), "getTcpNoDelay", "()Ljava/lang/Boolean;").unwrap());
+	init_ImpersonateConfig_getHttp2KeepAliveIntervalMillis(env.get_method_id(&ImpersonateConfig(), "getHttp2KeepAliveIntervalMillis", "()Ljava/lang/Long;").unwrap());
+	init_ImpersonateConfig_getHttp2KeepAliveTimeoutMillis(env.get_method_id(&ImpersonateConfig(), "getHttp2KeepAliveTimeoutMillis", "()Ljava/lang/Long;").unwrap());
+	init_ImpersonateConfig_getHttp2KeepAliveWhileIdle(env.get_method_id(&ImpersonateConfig(), "getHttp2KeepAliveWhileIdle", "()Ljava/lang/Boolean;").unwrap());
 	init_ImpersonateConfig_getAllowInvalidCertificates(env.get_method_id(&ImpersonateConfig(), "getAllowInvalidCertificates", "()Ljava/lang/Boolean;").unwrap());
 	init_ImpersonateConfig_getHttpsOnly(env.get_method_id(&ImpersonateConfig(), "getHttpsOnly", "()Ljava/lang/Boolean;").unwrap());
+	init_ImpersonateConfig_getHeaderOrder(env.get_method_id(&ImpersonateConfig(), "getHeaderOrder", "()Ljava/util/List;").unwrap());
+	init_ImpersonateConfig_getHttp1TitleCaseHeaders(env.get_method_id(&ImpersonateConfig(), "getHttp1TitleCaseHeaders", "()Ljava/lang/Boolean;").unwrap());
+	init_ImpersonateConfig_getDecompression(env.get_method_id(&ImpersonateConfig(), "getDecompression", "()Z").unwrap());
+	init_ImpersonateConfig_getGzip(env.get_method_id(&ImpersonateConfig(), "getGzip", "()Ljava/lang/Boolean;").unwrap());
+	init_ImpersonateConfig_getBrotli(env.get_method_id(&ImpersonateConfig(), "getBrotli", "()Ljava/lang/Boolean;").unwrap());
+	init_ImpersonateConfig_getDeflate(env.get_method_id(&ImpersonateConfig(), "getDeflate", "()Ljava/lang/Boolean;").unwrap());
+	init_ImpersonateConfig_getZstd(env.get_method_id(&ImpersonateConfig(), "getZstd", "()Ljava/lang/Boolean;").unwrap());
+	init_ImpersonateConfig_getDnsResolver(env.get_method_id(&ImpersonateConfig(), "getDnsResolver", "()Ldev/rushii/ktor_impersonate/DnsResolver;").unwrap());
+	init_ImpersonateConfig_getDnsOverHttpsUrl(env.get_method_id(&ImpersonateConfig(), "getDnsOverHttpsUrl", "()Ljava/lang/String;").unwrap());
+	init_ImpersonateConfig_getDnsOverHttpsFallback(env.get_method_id(&ImpersonateConfig(), "getDnsOverHttpsFallback", "()Z").unwrap());
+	init_ImpersonateConfig_getIpPreferenceName(env.get_method_id(&ImpersonateConfig(), "getIpPreferenceName", "()Ljava/lang/String;").unwrap());
+	init_ImpersonateConfig_getHttpVersionName(env.get_method_id(&ImpersonateConfig(), "getHttpVersionName", "()Ljava/lang/String;").unwrap());
+	init_ImpersonateConfig_getRetryMaxAttempts(env.get_method_id(&ImpersonateConfig(), "getRetryMaxAttempts", "()I").unwrap());
+	init_ImpersonateConfig_getRetryOnConnectionFailure(env.get_method_id(&ImpersonateConfig(), "getRetryOnConnectionFailure", "()Z").unwrap());
+	init_ImpersonateConfig_getRetryStatusCodesArray(env.get_method_id(&ImpersonateConfig(), "getRetryStatusCodesArray", "()[I").unwrap());
+	init_ImpersonateConfig_getRetryBaseDelayMillis(env.get_method_id(&ImpersonateConfig(), "getRetryBaseDelayMillis", "()J").unwrap());
+	init_ImpersonateConfig_getRetryMaxDelayMillis(env.get_method_id(&ImpersonateConfig(), "getRetryMaxDelayMillis", "()J").unwrap());
+	init_ImpersonateConfig_getRespectRetryAfter(env.get_method_id(&ImpersonateConfig(), "getRespectRetryAfter", "()Z").unwrap());
+	init_ImpersonateConfig_getRateLimitValues(env.get_method_id(&ImpersonateConfig(), "getRateLimitValues", "()[J").unwrap());
+	init_ImpersonateConfig_getRateLimitPerHostValues(env.get_method_id(&ImpersonateConfig(), "getRateLimitPerHostValues", "()[J").unwrap());
+	init_ImpersonateConfig_getMaxConcurrentRequests(env.get_method_id(&ImpersonateConfig(), "getMaxConcurrentRequests", "()Ljava/lang/Integer;").unwrap());
+	init_ImpersonateConfig_getMaxConcurrentRequestsPerHost(env.get_method_id(&ImpersonateConfig(), "getMaxConcurrentRequestsPerHost", "()Ljava/lang/Integer;").unwrap());
+	init_ImpersonateConfig_getResponseCacheSizes(env.get_method_id(&ImpersonateConfig(), "getResponseCacheSizes", "()[J").unwrap());
+	init_ImpersonateConfig_getResponseCacheDirectory(env.get_method_id(&ImpersonateConfig(), "getResponseCacheDirectory", "()Ljava/lang/String;").unwrap());
+	init_ImpersonateConfig_getHarFile(env.get_method_id(&ImpersonateConfig(), "getHarFile", "()Ljava/lang/String;").unwrap());
+	init_ImpersonateConfig_getHarListener(env.get_method_id(&ImpersonateConfig(), "getHarListener", "()Ldev/rushii/ktor_impersonate/HarListener;").unwrap());
+	init_ImpersonateConfig_getHarMaxBodySize(env.get_method_id(&ImpersonateConfig(), "getHarMaxBodySize", "()J").unwrap());
+	init_ImpersonateConfig_getLocalAddress(env.get_method_id(&ImpersonateConfig(), "getLocalAddress", "()Ljava/lang/String;").unwrap());
+	init_ImpersonateConfig_getNetworkInterface(env.get_method_id(&ImpersonateConfig(), "getNetworkInterface", "()Ljava/lang/String;").unwrap());
+	init_ImpersonateConfig_getUnixSocket(env.get_method_id(&ImpersonateConfig(), "getUnixSocket", "()Ljava/lang/String;").unwrap());
+	init_ImpersonateConfig_getHostOverridesArray(env.get_method_id(&ImpersonateConfig(), "getHostOverridesArray", "()[Ljava/lang/String;").unwrap());
 	init_NativeCallbacks(class_ref(&mut env, "dev/rushii/ktor_impersonate/internal/NativeEngine$Callbacks"));
-	init_NativeCallbacks_onError(env.get_method_id(&NativeCallbacks(), "onError", "(Ljava/lang/String;)V").unwrap());
-	init_NativeCallbacks_onResponse(env.get_method_id(&NativeCallbacks(), "onResponse", "(Ljava/lang/String;ILio/ktor/http/Headers;)V").unwrap());
+	init_NativeCallbacks_onError(env.get_method_id(&NativeCallbacks(), "onError", "(ILjava/lang/String;)V").unwrap());
+	init_NativeCallbacks_onResponse(env.get_method_id(&NativeCallbacks(), "onResponse", "(IIIILio/ktor/http/Headers;IJI)V").unwrap());
+	init_NativeDownloadCallbacks(class_ref(&mut env, "dev/rushii/ktor_impersonate/internal/NativeEngine$DownloadCallbacks"));
+	init_NativeDownloadCallbacks_onComplete(env.get_method_id(&NativeDownloadCallbacks(), "onComplete", "(IIILio/ktor/http/Headers;J)V").unwrap());
+	init_NativeDownloadCallbacks_onError(env.get_method_id(&NativeDownloadCallbacks(), "onError", "(ILjava/lang/String;)V").unwrap());
+	init_NativePreconnectCallbacks(class_ref(&mut env, "dev/rushii/ktor_impersonate/internal/NativeEngine$PreconnectCallbacks"));
+	init_NativePreconnectCallbacks_onConnected(env.get_method_id(&NativePreconnectCallbacks(), "onConnected", "()V").unwrap());
+	init_NativePreconnectCallbacks_onError(env.get_method_id(&NativePreconnectCallbacks(), "onError", "(ILjava/lang/String;)V").unwrap());
+	init_NativeFileContent(class_ref(&mut env, "dev/rushii/ktor_impersonate/NativeFileContent"));
+	init_NativeFileContent_getPath(env.get_method_id(&NativeFileContent(), "getPath", "()Ljava/lang/String;").unwrap());
+	init_NativeFileContent_getOffset(env.get_method_id(&NativeFileContent(), "getOffset", "()J").unwrap());
+	init_NativeFileContent_getLengthValue(env.get_method_id(&NativeFileContent(), "getLengthValue", "()J").unwrap());
+	init_NativeFileContent_getProgressListener(env.get_method_id(&NativeFileContent(), "getProgressListener", "()Ldev/rushii/ktor_impersonate/UploadProgressListener;").unwrap());
+	init_RequestConfig(class_ref(&mut env, "dev/rushii/ktor_impersonate/internal/RequestConfig"));
+	init_RequestConfig_getHeaderOrder(env.get_method_id(&RequestConfig(), "getHeaderOrder", "()[Ljava/lang/String;").unwrap());
+	init_RequestConfig_getRequestTimeoutMillis(env.get_method_id(&RequestConfig(), "getRequestTimeoutMillis", "()Ljava/lang/Long;").unwrap());
+	init_RequestConfig_getConnectTimeoutMillis(env.get_method_id(&RequestConfig(), "getConnectTimeoutMillis", "()Ljava/lang/Long;").unwrap());
+	init_RequestConfig_getSocketTimeoutMillis(env.get_method_id(&RequestConfig(), "getSocketTimeoutMillis", "()Ljava/lang/Long;").unwrap());
+	init_RequestConfig_getLocalAddress(env.get_method_id(&RequestConfig(), "getLocalAddress", "()Ljava/lang/String;").unwrap());
+	init_RequestConfig_getNetworkInterface(env.get_method_id(&RequestConfig(), "getNetworkInterface", "()Ljava/lang/String;").unwrap());
+	init_RequestConfig_getHttpVersionName(env.get_method_id(&RequestConfig(), "getHttpVersionName", "()Ljava/lang/String;").unwrap());
+	init_RequestConfig_getUnixSocket(env.get_method_id(&RequestConfig(), "getUnixSocket", "()Ljava/lang/String;").unwrap());
 	init_ResponseSource(class_ref(&mut env, "dev/rushii/ktor_impersonate/internal/ResponseSource"));
 	init_ResponseSource_requestId(env.get_field_id(&ResponseSource(), "requestId", "I").unwrap());
+	init_ResponseSource_onTrailers(env.get_method_id(&ResponseSource(), "onTrailers", "(Lio/ktor/http/Headers;)V").unwrap());
+	init_UploadProgressListener(class_ref(&mut env, "dev/rushii/ktor_impersonate/UploadProgressListener"));
+	init_UploadProgressListener_onProgress(env.get_method_id(&UploadProgressListener(), "onProgress", "(JJ)V").unwrap());
 
 	// Ktor
 	init_HeadersBuilder(class_ref(&mut env, "io/ktor/http/HeadersBuilder"));
@@ -157,29 +285,93 @@ pub(super) fn release_cache() {
 		RuntimeException,
 		List_toArray,
 		List,
+		Integer_intValue,
+		Integer,
 		Long_longValue,
 		Long,
+		Object_toString,
+		Object,
 		Set_toArray,
 		Set,
 
-		// Kotlinx
-		Sink_write,
-		Sink,
-
 		// ktor-impersonate
+		DnsResolver_resolve,
+		DnsResolver,
+		HarListener_onEntry,
+		HarListener,
 		ImpersonateConfig_getVerboseLogging,
 		ImpersonateConfig_getPreset,
 		ImpersonateConfig_getRequestTimeoutMillis,
 		ImpersonateConfig_getConnectTimeoutMillis,
 		ImpersonateConfig_getIdleTimeout,
+		ImpersonateConfig_getMaxIdleConnectionsPerHost,
+		ImpersonateConfig_getTcpKeepAliveMillis,
+		ImpersonateConfig_getTcpNoDelay,
+		ImpersonateConfig_getHttp2KeepAliveIntervalMillis,
+		ImpersonateConfig_getHttp2KeepAliveTimeoutMillis,
+		ImpersonateConfig_getHttp2KeepAliveWhileIdle,
 		ImpersonateConfig_getAllowInvalidCertificates,
 		ImpersonateConfig_getHttpsOnly,
+		ImpersonateConfig_getHeaderOrder,
+		ImpersonateConfig_getHttp1TitleCaseHeaders,
+		ImpersonateConfig_getDecompression,
+		ImpersonateConfig_getGzip,
+		ImpersonateConfig_getBrotli,
+		ImpersonateConfig_getDeflate,
+		ImpersonateConfig_getZstd,
+		ImpersonateConfig_getDnsResolver,
+		ImpersonateConfig_getDnsOverHttpsUrl,
+		ImpersonateConfig_getDnsOverHttpsFallback,
+		ImpersonateConfig_getIpPreferenceName,
+		ImpersonateConfig_getHostOverridesArray,
+		ImpersonateConfig_getHttpVersionName,
+		ImpersonateConfig_getRetryMaxAttempts,
+		ImpersonateConfig_getRetryOnConnectionFailure,
+		ImpersonateConfig_getRetryStatusCodesArray,
+		ImpersonateConfig_getRetryBaseDelayMillis,
+		ImpersonateConfig_getRetryMaxDelayMillis,
+		ImpersonateConfig_getRespectRetryAfter,
+		ImpersonateConfig_getRateLimitValues,
+		ImpersonateConfig_getRateLimitPerHostValues,
+		ImpersonateConfig_getMaxConcurrentRequests,
+		ImpersonateConfig_getMaxConcurrentRequestsPerHost,
+		ImpersonateConfig_getResponseCacheSizes,
+		ImpersonateConfig_getResponseCacheDirectory,
+		ImpersonateConfig_getHarFile,
+		ImpersonateConfig_getHarListener,
+		ImpersonateConfig_getHarMaxBodySize,
+		ImpersonateConfig_getLocalAddress,
+		ImpersonateConfig_getNetworkInterface,
+		ImpersonateConfig_getUnixSocket,
 		ImpersonateConfig,
 		NativeCallbacks_onError,
 		NativeCallbacks_onResponse,
 		NativeCallbacks,
+		NativeDownloadCallbacks_onComplete,
+		NativeDownloadCallbacks_onError,
+		NativeDownloadCallbacks,
+		NativePreconnectCallbacks_onConnected,
+		NativePreconnectCallbacks_onError,
+		NativePreconnectCallbacks,
+		NativeFileContent_getPath,
+		NativeFileContent_getOffset,
+		NativeFileContent_getLengthValue,
+		NativeFileContent_getProgressListener,
+		NativeFileContent,
+		RequestConfig_getHeaderOrder,
+		RequestConfig_getRequestTimeoutMillis,
+		RequestConfig_getConnectTimeoutMillis,
+		RequestConfig_getSocketTimeoutMillis,
+		RequestConfig_getLocalAddress,
+		RequestConfig_getNetworkInterface,
+		RequestConfig_getHttpVersionName,
+		RequestConfig_getUnixSocket,
+		RequestConfig,
 		ResponseSource_requestId,
+		ResponseSource_onTrailers,
 		ResponseSource,
+		UploadProgressListener_onProgress,
+		UploadProgressListener,
 
 		// Ktor
 		HeadersBuilder_build,
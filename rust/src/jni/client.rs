@@ -1,21 +1,36 @@
-use crate::jni::headers::{headers_to_jni, jni_to_headers};
+use crate::cache::{CacheLookup, CacheStatus};
+use crate::client::NativeClient;
+use crate::jni::body::request_body_channel;
+use crate::jni::headers::{headers_to_jni, jni_to_headers, sort_headers};
+use crate::jni::upload::get_file_body;
+use crate::jni::utils::get_string_array_values;
+use crate::limits::{self, LimitPermit};
+use crate::multipart::{FormData, Part, PartSource};
 use crate::jni::{cache, config};
-use crate::requests::{new_request_id, RequestTask, ACTIVE_REQUESTS};
-use crate::{root_certs, throw, throw_argument, TOKIO_RUNTIME};
+use crate::requests::{new_request_id, RequestBodyChunk, RequestTask, ResponseBody, ResponseParts, ACTIVE_REQUESTS};
+use crate::timeout::SocketTimeout;
+use crate::unix;
+use crate::{throw, throw_argument, TOKIO_RUNTIME};
 use catch_panic::catch_panic;
 use dashmap::Entry;
+use futures_core::stream::BoxStream;
 use futures_util::StreamExt;
-use jni::objects::{GlobalRef, JClass, JObject, JString, JValueGen, JValueOwned};
+use jni::errors::Error as JNIError;
+use jni::objects::{GlobalRef, JByteArray, JClass, JObject, JObjectArray, JString, JValueGen, JValueOwned};
 use jni::signature::{Primitive, ReturnType};
 use jni::sys::{jboolean, jint, jlong};
 use jni::{JNIEnv, JavaVM};
 use jni_fn::jni_fn;
-use rquest::{Client, Request, Response};
+use log::debug;
+use rquest::header::{CONTENT_LENGTH, CONTENT_TYPE};
+use rquest::{Body, Client, Request, Version};
 use std::borrow::Cow;
-use std::fmt::Write;
 use std::ops::Deref;
+use std::path::PathBuf;
 use std::str::FromStr;
 use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+use tokio::sync::mpsc;
 
 // ------------------------ JNI ------------------------ //
 
@@ -26,18 +41,14 @@ pub fn createClient<'l>(
 	_cls: JClass<'l>,
 	config: JObject<'l>,
 ) -> jlong {
-	let mut builder = Client::builder();
-
-	builder = config::apply_jni_config(&mut env, &config, builder)
-		.expect("failed to apply config");
-
-	match root_certs::get_cached_verify_store() {
-		Ok(store) => builder = builder.ca_cert_store(store),
-		Err(err) => throw!(env, &*format!("Failed to load certificates: {err:#?}"), 0),
-	}
+	let config = match config::get_jni_config(&mut env, &config) {
+		Ok(config) => config,
+		Err(JNIError::JavaException) => return 0,
+		Err(err) => throw!(env, &*format!("Failed to get config: {err:?}"), 0),
+	};
 
-	let client_ptr = match builder.build() {
-		Ok(client) => Box::leak(Box::new(client)) as *const Client,
+	let client_ptr = match NativeClient::new(config) {
+		Ok(client) => Box::leak(Box::new(client)) as *const NativeClient,
 		Err(err) => throw_argument!(env, &*format!("Failed to build rquest Client: {err}"), 0)
 	};
 
@@ -51,10 +62,10 @@ pub fn destroyClient<'l>(
 	_cls: JClass<'l>,
 	client_ptr: jlong,
 ) {
-	let client_ptr = client_ptr as *mut Client;
+	let client_ptr = client_ptr as *mut NativeClient;
 	if client_ptr.is_null() { return; }
 
-	// Free the Box and decrease Client's Arc count
+	// Free the Box and decrease the Clients' Arc counts
 	// SAFETY: This works as long as the Java-side invariant is preserved
 	drop(unsafe { Box::from_raw(client_ptr) });
 }
@@ -69,6 +80,11 @@ pub fn executeRequest<'l>(
 	url: JString<'l>,
 	http_method: JString<'l>,
 	headers: JObject<'l>,
+	body: JByteArray<'l>,
+	stream_body: jboolean,
+	form_data: JObjectArray<'l>,
+	file_body: JObject<'l>,
+	request_config: JObject<'l>,
 	is_websocket: jboolean,
 ) -> jint {
 	// Convert JNI types into rust types
@@ -77,7 +93,16 @@ pub fn executeRequest<'l>(
 	let j_http_method = unsafe { env.get_string_unchecked(&http_method) }.unwrap();
 	let url: Cow<str> = j_url.deref().into();
 	let http_method: Cow<str> = j_http_method.deref().into();
-	let headers = jni_to_headers(&mut env, &headers).expect("failed to get headers from jni");
+	let headers = match jni_to_headers(&mut env, &headers) {
+		Ok(headers) => headers,
+		Err(JNIError::JavaException) => return -1,
+		Err(err) => throw!(env, &*format!("Failed to get headers: {err:?}"), -1),
+	};
+	let request_config = match config::get_jni_request_config(&mut env, &request_config) {
+		Ok(config) => config,
+		Err(JNIError::JavaException) => return -1,
+		Err(err) => throw!(env, &*format!("Failed to get request config: {err:?}"), -1),
+	};
 	let callbacks = env.new_global_ref(callbacks).unwrap();
 
 	// Parse url & http method
@@ -93,11 +118,88 @@ pub fn executeRequest<'l>(
 	// Retrieve rquest::Client from a pointer stored in the class
 	// SAFETY: This works as long as the Java-side invariant is preserved
 	if client_ptr == 0 { throw!(env, "Client is already closed!", -1); }
-	let client = unsafe { &*(client_ptr as *mut Client) }.clone();
+	let native_client = unsafe { &*(client_ptr as *const NativeClient) };
+	let client = match native_client.client_for(&request_config) {
+		Ok(client) => client,
+		Err(err) => throw_argument!(env, &*format!("Failed to build rquest Client: {err}"), -1),
+	};
+
+	// The variant client applies the header order of this request to every header, including the preset's defaults.
+	// Requests over Unix domain sockets are not sent by rquest, so their headers are also ordered here.
+	let headers = match request_config.header_order.as_deref() {
+		Some(order) => sort_headers(&headers, order),
+		None => headers,
+	};
 
 	// Create & setup request builder
-	let builder = client.request(http_method, url)
+	let mut builder = client.request(http_method, url)
 		.headers(headers);
+	if let Some(timeout) = request_config.request_timeout {
+		builder = builder.timeout(timeout);
+	}
+
+	// Attach the request body, which is either passed immediately, streamed in later by the JVM, or read natively
+	let unix_socket = native_client.unix_socket_for(&request_config);
+	let mut request_body = None;
+	let mut body_stream = None;
+	if !file_body.is_null() {
+		let file_body = match get_file_body(&mut env, &file_body) {
+			Ok(file_body) => file_body,
+			Err(JNIError::JavaException) => return -1,
+			Err(err) => throw!(env, &*format!("Failed to get file body: {err:?}"), -1),
+		};
+		let (length, stream) = match file_body.open() {
+			Ok(opened) => opened,
+			Err(err) => throw_argument!(env, &*err.to_string(), -1),
+		};
+
+		builder = builder.header(CONTENT_LENGTH, length);
+		body_stream = Some(stream);
+	} else if !form_data.is_null() {
+		let form = match get_form_data(&mut env, &form_data, native_client.preset()) {
+			Ok(form) => form,
+			Err(JNIError::JavaException) => return -1,
+			Err(err) => throw!(env, &*format!("Failed to get form data: {err:?}"), -1),
+		};
+
+		builder = builder.header(CONTENT_TYPE, form.content_type());
+		if let Some(length) = form.content_length() {
+			builder = builder.header(CONTENT_LENGTH, length);
+		}
+
+		// Parts provided by the JVM are streamed in through the same channel as other streaming bodies
+		let mut jvm_parts = None;
+		if form.has_jvm_parts() {
+			let (sender, stream) = request_body_channel();
+			jvm_parts = Some(stream.boxed());
+			request_body = Some(sender);
+		}
+		body_stream = Some(form.into_stream(jvm_parts));
+	} else if stream_body > 0 {
+		let (sender, stream) = request_body_channel();
+		body_stream = Some(stream.boxed());
+		request_body = Some(sender);
+	} else if !body.is_null() {
+		match env.convert_byte_array(&body) {
+			Ok(bytes) => builder = builder.body(bytes),
+			Err(err) => throw!(env, &*format!("Failed to get request body: {err:?}"), -1),
+		}
+	}
+
+	// Sending each chunk of the body resets the socket timeout
+	let socket_timeout = request_config.socket_timeout.map(SocketTimeout::new);
+	if let Some(timeout) = &socket_timeout {
+		body_stream = body_stream.map(|stream| timeout.track(stream));
+	}
+
+	// Requests over Unix domain sockets are not sent by rquest, so the stream is passed along separately
+	let mut unix_body = None;
+	if let Some(stream) = body_stream {
+		match unix_socket {
+			Some(_) => unix_body = Some(stream),
+			None => builder = builder.body(Body::wrap_stream(stream)),
+		}
+	}
 
 	if is_websocket > 0 {
 		todo!()
@@ -106,8 +208,10 @@ pub fn executeRequest<'l>(
 			Ok(req) => req,
 			Err(err) => throw!(env, &*format!("Failed to build request: {err}"), -1),
 		};
-
-		execute_request(env, callbacks, client, request)
+		match unix_socket {
+			None => execute_request(env, callbacks, native_client, client, request, request_body, socket_timeout),
+			Some(socket) => execute_unix_request(env, callbacks, native_client, socket, request, unix_body, request_body, socket_timeout),
+		}
 	}
 }
 
@@ -122,26 +226,60 @@ pub fn cancelRequest<'l>(
 		Some(RequestTask::PendingResponse { abort, .. }) if abort.is_some() => {
 			abort.unwrap().abort();
 		}
+		Some(RequestTask::Preconnect { abort: Some(abort) }) => abort.abort(),
 		_ => return,
 	}
 }
 
+/// Reads the parts of a natively encoded `multipart/form-data` body,
+/// flattened by `NativeFormDataContent.toValues()` as `[kind, name, fileName, contentType, value, ...]`.
+fn get_form_data(env: &mut JNIEnv, values: &JObjectArray, preset: Option<&str>) -> Result<FormData, JNIError> {
+	// SAFETY: The array is always a String[]
+	let values = unsafe { get_string_array_values(env, values) }?;
+
+	let mut parts = Vec::with_capacity(values.len() / 5);
+	for part in values.chunks_exact(5) {
+		let [kind, name, file_name, content_type, value] = part else { unreachable!() };
+		let source = match &**kind {
+			"text" => PartSource::Text(value.clone()),
+			"file" => PartSource::File(PathBuf::from(value)),
+			"stream" => PartSource::Jvm { length: value.parse().ok() },
+			_ => panic!("BUG: invalid form part kind {kind:?}"),
+		};
+
+		parts.push(Part {
+			name: name.clone(),
+			file_name: (&**kind != "text").then(|| file_name.clone()),
+			content_type: (!content_type.is_empty()).then(|| content_type.clone()),
+			source,
+		});
+	}
+
+	match FormData::new(preset, parts) {
+		Ok(form) => Ok(form),
+		Err(err) => throw_argument!(env, &*err.to_string(), Err(JNIError::JavaException)),
+	}
+}
+
 // ------------------------ JNI Callbacks ------------------------ //
 
-fn callback_response(vm: JavaVM, callbacks: GlobalRef, request_id: u32, response: Response) {
+fn callback_response(vm: JavaVM, callbacks: GlobalRef, request_id: u32, response: ResponseParts, execution: Execution, read_timeout: Option<Duration>) {
 	// We assume this thread is already attached to the VM based on the tokio runtime config
 	let mut env = vm.get_env().expect("Thread is not attached to JavaVM");
 
-	let status = response.status().as_u16();
+	let request_id_jni = JValueOwned::from(request_id as i32).as_jni();
+	let status = response.status.as_u16();
 	let status_jni = JValueOwned::from(status as i32).as_jni();
 
-	// Format HTTP version to string
-	let mut version = String::with_capacity(8);
-	write!(version, "{:?}", response.version()).unwrap();
-	let version_jni = JValueOwned::from(env.new_string(version).unwrap()).as_jni();
+	let (version_major, version_minor) = version_numbers(response.version);
+	let version_major_jni = JValueOwned::from(version_major).as_jni();
+	let version_minor_jni = JValueOwned::from(version_minor).as_jni();
+	let attempts_jni = JValueOwned::from(execution.attempts as i32).as_jni();
+	let queue_time_jni = JValueOwned::from(execution.queue_time.as_millis() as i64).as_jni();
+	let cache_status_jni = JValueOwned::from(execution.cache_status as i32).as_jni();
 
 	// Convert the headers to jni
-	let headers_jni = headers_to_jni(&mut env, response.headers())
+	let headers_jni = headers_to_jni(&mut env, &response.headers)
 		.map(JValueOwned::from)
 		.expect("failed to convert headers map") // TODO: return error like callback_request_error does
 		.as_jni();
@@ -149,10 +287,10 @@ fn callback_response(vm: JavaVM, callbacks: GlobalRef, request_id: u32, response
 	// Store the response body into the global ACTIVE_REQUESTS and remove the AbortHandle (task is almost finished)
 	if let Some(mut entry) = ACTIVE_REQUESTS.get_mut(&request_id) {
 		match entry.value_mut() {
-			RequestTask::PendingResponse { abort, body } => {
-				let stream = response.bytes_stream().boxed();
+			RequestTask::PendingResponse { abort, body, permit, .. } => {
 				*abort = None;
-				*body = Some(Arc::new(Mutex::new(stream)));
+				*body = Some(Arc::new(Mutex::new(ResponseBody::new(response, read_timeout))));
+				*permit = execution.permit;
 			}
 			_ => unreachable!(),
 		}
@@ -166,16 +304,16 @@ fn callback_response(vm: JavaVM, callbacks: GlobalRef, request_id: u32, response
 			callbacks,
 			&cache::NativeCallbacks_onResponse(),
 			ReturnType::Primitive(Primitive::Void),
-			&[version_jni, status_jni, headers_jni],
+			&[request_id_jni, version_major_jni, version_minor_jni, status_jni, headers_jni, attempts_jni, queue_time_jni, cache_status_jni],
 		).expect("Failed to invoke onResponse callback");
 	};
 }
 
-fn callback_request_error(vm: JavaVM, callbacks: GlobalRef, request_id: u32, error: rquest::Error) {
+fn callback_request_error(vm: JavaVM, callbacks: GlobalRef, request_id: u32, kind: ErrorKind, message: String) {
 	// We assume this thread is already attached to the VM based on the tokio runtime config
 	let mut env = vm.get_env().expect("Thread is not attached to JavaVM");
 
-	let message = format!("Failed to execute request: {error}");
+	let kind_jni = JValueOwned::from(kind as i32).as_jni();
 	let message_jni = JValueGen::from(env.new_string(message).unwrap()).as_jni();
 
 	// Remove the request record from ACTIVE_REQUESTS
@@ -187,35 +325,279 @@ fn callback_request_error(vm: JavaVM, callbacks: GlobalRef, request_id: u32, err
 			callbacks,
 			&cache::NativeCallbacks_onError(),
 			ReturnType::Primitive(Primitive::Void),
-			&[message_jni],
+			&[kind_jni, message_jni],
 		).expect("Failed to invoke onError callback");
 	}
 }
 
 // ------------------------ Other ------------------------ //
 
-fn execute_request(env: JNIEnv, callbacks: GlobalRef, client: Client, request: Request) -> jint {
+/// Details of how a request was executed, reported alongside its response.
+struct Execution {
+	/// The amount of attempts made, including the first one.
+	attempts: u32,
+	/// How long the request was queued by the client's limits before being sent.
+	queue_time: Duration,
+	/// The request's slot in the client's concurrency limits, which is held until the response is closed.
+	permit: Option<LimitPermit>,
+	/// How the response was produced by the client's cache.
+	cache_status: CacheStatus,
+}
+
+/// Gets the major and minor numbers of the HTTP version a response was received with.
+pub fn version_numbers(version: Version) -> (i32, i32) {
+	match version {
+		Version::HTTP_09 => (0, 9),
+		Version::HTTP_10 => (1, 0),
+		Version::HTTP_11 => (1, 1),
+		Version::HTTP_2 => (2, 0),
+		Version::HTTP_3 => (3, 0),
+		_ => unreachable!("unknown http version {version:?}"),
+	}
+}
+
+/// The kind of error a request failed with, which is mapped to a specific exception type on the JVM side.
+/// This must be kept in sync with the `ERROR_*` constants in `NativeEngine`.
+#[repr(i32)]
+#[derive(Clone, Copy)]
+pub enum ErrorKind {
+	Other = 0,
+	ConnectTimeout = 1,
+	SocketTimeout = 2,
+	RequestTimeout = 3,
+}
+
+impl ErrorKind {
+	pub fn of(error: &rquest::Error) -> Self {
+		match (error.is_timeout(), error.is_connect()) {
+			(true, true) => ErrorKind::ConnectTimeout,
+			(true, false) => ErrorKind::RequestTimeout,
+			_ => ErrorKind::Other,
+		}
+	}
+}
+
+fn execute_request(
+	env: JNIEnv,
+	callbacks: GlobalRef,
+	native_client: &NativeClient,
+	client: Client,
+	request: Request,
+	request_body: Option<mpsc::Sender<RequestBodyChunk>>,
+	socket_timeout: Option<SocketTimeout>,
+) -> jint {
 	let runtime_lock = TOKIO_RUNTIME.read().expect("runtime lock poisoned");
 	let runtime = runtime_lock.as_ref().expect("runtime not initialized");
 
 	let request_id = new_request_id();
 	let vm = env.get_java_vm().unwrap();
+	let retry = native_client.retry_policy();
+	let limiter = native_client.limiter();
+	let authority = limits::authority(request.url());
+	let response_cache = native_client.response_cache();
+	let har_recorder = native_client.har_recorder();
+	let read_timeout = socket_timeout.as_ref().map(|timeout| timeout.duration);
+	let mut request = request;
+
+	// The request is registered before it starts, since responses served from the cache are available immediately
+	match ACTIVE_REQUESTS.entry(request_id) {
+		Entry::Occupied(_) => panic!("BUG: broken atomic or id overflow"),
+		Entry::Vacant(entry) => entry.insert(RequestTask::PendingResponse {
+			abort: None,
+			body: None,
+			request_body,
+			permit: None,
+		}),
+	};
+
 	let task_handle = runtime.spawn(async move {
-		let result = client.execute(request).await;
+		// Serve the response from the cache if it is fresh, otherwise revalidate it
+		let cache_request = response_cache.as_ref().and_then(|cache| cache.request(&request));
+		let mut stale = None;
+		if let (Some(cache), Some(cache_request)) = (&response_cache, &cache_request) {
+			match cache.lookup(cache_request).await {
+				CacheLookup::Fresh(entry) => {
+					let execution = Execution { attempts: 0, queue_time: Duration::ZERO, permit: None, cache_status: CacheStatus::Hit };
+					return callback_response(vm, callbacks, request_id, entry.to_parts(), execution, read_timeout);
+				}
+				CacheLookup::Stale(entry) => {
+					entry.add_validators(&mut request);
+					stale = Some(entry);
+				}
+				CacheLookup::Miss => {}
+			}
+		}
+
+		// Wait for the client's rate and concurrency limits to allow sending this request
+		let queue_start = Instant::now();
+		let permit = match &limiter {
+			None => None,
+			Some(limiter) => Some(limiter.acquire(&*authority).await),
+		};
+		let queue_time = queue_start.elapsed();
+
+		// Each attempt is recorded as a separate entry, which is written once its response body is done with
+		let har_request = har_recorder.as_ref().map(|recorder| recorder.request(&request));
+		let mut har_entry = None;
+		let mut blocked = queue_time;
+
+		let method = request.method().clone();
+		let max_attempts = retry.as_ref().map_or(1, |policy| policy.max_attempts);
+		let mut attempts = 0;
+		let mut request = Some(request);
+
+		let result = loop {
+			attempts += 1;
+			let current = request.take().unwrap();
+			har_entry = har_recorder.as_ref().zip(har_request.as_ref())
+				.map(|(recorder, har_request)| recorder.entry(har_request, blocked));
+
+			// Streaming request bodies cannot be cloned, which prevents retrying them
+			let next = if attempts < max_attempts { current.try_clone() } else { None };
+
+			// The socket timeout applies to sending the request and waiting for the response headers without any activity,
+			// and then separately to each chunk of the response body
+			let result = match &socket_timeout {
+				None => Ok(client.execute(current).await),
+				Some(timeout) => timeout.run(client.execute(timeout.track_request(current))).await,
+			};
+
+			if let Some(entry) = &mut har_entry {
+				match &result {
+					Err(_) => entry.set_error("Socket timeout has expired while waiting for a response".to_owned()),
+					Ok(Err(err)) => entry.set_error(err.to_string()),
+					Ok(Ok(resp)) => entry.set_response(resp),
+				}
+			}
+
+			let (Some(policy), Some(next)) = (retry.as_ref(), next) else { break result };
+			let delay = match &result {
+				Ok(Err(err)) if policy.should_retry_error(err) => Some(policy.backoff(attempts)),
+				Ok(Ok(resp)) => policy.response_retry_delay(&method, resp, attempts),
+				_ => None,
+			};
+			let Some(delay) = delay else { break result };
+
+			debug!("Retrying request in {}ms after attempt {attempts}", delay.as_millis());
+			drop(result);
+			drop(har_entry.take());
+
+			let retry_start = Instant::now();
+			tokio::time::sleep(delay).await;
+			if let Some(limiter) = &limiter {
+				limiter.pace(&*authority).await;
+			}
+			blocked = retry_start.elapsed();
+			request = Some(next);
+		};
 
 		match result {
-			Err(err) => callback_request_error(vm, callbacks, request_id, err),
-			Ok(resp) => callback_response(vm, callbacks, request_id, resp),
+			Err(_) => {
+				let message = format!("Socket timeout of {}ms has expired while waiting for a response", read_timeout.unwrap().as_millis());
+				callback_request_error(vm, callbacks, request_id, ErrorKind::SocketTimeout, message)
+			}
+			Ok(Err(err)) => {
+				let message = match attempts {
+					1 => format!("Failed to execute request: {err}"),
+					_ => format!("Failed to execute request after {attempts} attempts: {err}"),
+				};
+				callback_request_error(vm, callbacks, request_id, ErrorKind::of(&err), message)
+			}
+			Ok(Ok(resp)) => {
+				let mut response = ResponseParts::from(resp);
+				if let Some(entry) = har_entry {
+					response = entry.record_body(response);
+				}
+
+				let mut cache_status = CacheStatus::Uncached;
+				if let (Some(cache), Some(cache_request)) = (&response_cache, &cache_request) {
+					(response, cache_status) = cache.handle_response(cache_request, stale, response);
+				}
+
+				let execution = Execution { attempts, queue_time, permit, cache_status };
+				callback_response(vm, callbacks, request_id, response, execution, read_timeout)
+			}
 		};
 	});
 
+	// Allow cancelling the request, unless it has already completed
+	if let Some(mut entry) = ACTIVE_REQUESTS.get_mut(&request_id) {
+		if let RequestTask::PendingResponse { abort, body: None, .. } = entry.value_mut() {
+			if !task_handle.is_finished() {
+				*abort = Some(task_handle.abort_handle());
+			}
+		}
+	}
+
+	request_id as jint
+}
+
+/// Executes a request over a Unix domain socket instead of through rquest.
+/// These are meant for local daemons, so the client's cache, limits, retries and HAR recording are not applied.
+#[allow(clippy::too_many_arguments)]
+fn execute_unix_request(
+	env: JNIEnv,
+	callbacks: GlobalRef,
+	native_client: &NativeClient,
+	socket: PathBuf,
+	mut request: Request,
+	body: Option<BoxStream<'static, RequestBodyChunk>>,
+	request_body: Option<mpsc::Sender<RequestBodyChunk>>,
+	socket_timeout: Option<SocketTimeout>,
+) -> jint {
+	let runtime_lock = TOKIO_RUNTIME.read().expect("runtime lock poisoned");
+	let runtime = runtime_lock.as_ref().expect("runtime not initialized");
+
+	let request_id = new_request_id();
+	let vm = env.get_java_vm().unwrap();
+	let read_timeout = socket_timeout.as_ref().map(|timeout| timeout.duration);
+
+	// Byte bodies are also sent in tracked chunks, so that the socket timeout only expires without activity
+	let body = match &socket_timeout {
+		Some(timeout) if body.is_none() => timeout.take_bytes_body(&mut request),
+		_ => body,
+	};
+
 	match ACTIVE_REQUESTS.entry(request_id) {
 		Entry::Occupied(_) => panic!("BUG: broken atomic or id overflow"),
 		Entry::Vacant(entry) => entry.insert(RequestTask::PendingResponse {
-			abort: Some(task_handle.abort_handle()),
+			abort: None,
 			body: None,
+			request_body,
+			permit: None,
 		}),
 	};
 
+	let task_handle = runtime.spawn(async move {
+		let result = match &socket_timeout {
+			None => Ok(unix::execute(&socket, request, body).await),
+			Some(timeout) => timeout.run(unix::execute(&socket, request, body)).await,
+		};
+
+		match result {
+			Err(_) => {
+				let message = format!("Socket timeout of {}ms has expired while waiting for a response", read_timeout.unwrap().as_millis());
+				callback_request_error(vm, callbacks, request_id, ErrorKind::SocketTimeout, message)
+			}
+			Ok(Err(err)) => {
+				let message = format!("Failed to execute request: {err}");
+				callback_request_error(vm, callbacks, request_id, ErrorKind::Other, message)
+			}
+			Ok(Ok(response)) => {
+				let execution = Execution { attempts: 1, queue_time: Duration::ZERO, permit: None, cache_status: CacheStatus::Uncached };
+				callback_response(vm, callbacks, request_id, response, execution, read_timeout)
+			}
+		};
+	});
+
+	// Allow cancelling the request, unless it has already completed
+	if let Some(mut entry) = ACTIVE_REQUESTS.get_mut(&request_id) {
+		if let RequestTask::PendingResponse { abort, body: None, .. } = entry.value_mut() {
+			if !task_handle.is_finished() {
+				*abort = Some(task_handle.abort_handle());
+			}
+		}
+	}
+
 	request_id as jint
 }
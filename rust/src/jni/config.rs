@@ -1,51 +1,85 @@
+use crate::cache::CacheConfig;
+use crate::config::{HttpVersionPolicy, ImpersonateConfig, RequestConfig};
+use crate::dns::{IpPreference, Lookup};
+use crate::doh::DohConfig;
+use crate::har::{HarConfig, HarListener};
+use crate::limits::{LimitsConfig, RateLimit};
+use crate::retry::RetryPolicy;
 use crate::jni::cache;
-use crate::jni::utils::boxed_jni_to_primitive;
+use crate::jni::dns::JvmLookup;
+use crate::jni::har::JvmHarListener;
+use crate::jni::utils::{boxed_jni_to_primitive, get_string_array_values, get_string_list_values};
+use crate::throw_argument;
 use jni::errors::Error as JNIError;
-use jni::objects::JObject;
+use jni::objects::{JIntArray, JLongArray, JMethodID, JObject, JObjectArray};
 use jni::signature::{Primitive, ReturnType};
 use jni::JNIEnv;
-use rquest::tls::Impersonate;
-use rquest::ClientBuilder;
+use rquest::header::HeaderName;
+use std::net::{IpAddr, SocketAddr};
+use std::path::PathBuf;
 use std::str::FromStr;
+use std::sync::Arc;
 use std::time::Duration;
 
-/// Applies the JVM-side impersonate config to a rquest [ClientBuilder].
+/// Reads the JVM-side impersonate config.
 /// [config_obj]: An instance of `dev/rushii/ktor_impersonate/ImpersonateConfig`
-pub fn apply_jni_config(
-	env: &mut JNIEnv,
-	config_obj: &JObject,
-	mut client: ClientBuilder,
-) -> Result<ClientBuilder, JNIError> {
-	let config = env.with_local_frame(0, |env| unsafe {
-		get_jni_config(env, config_obj)
-	})?;
-
-	client = client
-		.connection_verbose(config.verbose_logging);
-	if let Some(preset) = config.preset.as_deref() {
-		client = client.impersonate(Impersonate::from_str(preset)
-			.expect("BUG: invalid impersonate preset"));
-	}
-	if let Some(duration) = config.request_timeout {
-		client = client.timeout(duration);
-	}
-	if let Some(duration) = config.connect_timeout {
-		client = client.connect_timeout(duration);
-	}
-	if let Some(duration) = config.idle_timeout {
-		client = client.pool_idle_timeout(duration);
-	}
-	if let Some(enabled) = config.invalid_certs {
-		client = client.danger_accept_invalid_certs(enabled);
-	}
-	if let Some(enabled) = config.https_only {
-		client = client.https_only(enabled);
-	}
+pub fn get_jni_config(env: &mut JNIEnv, config_obj: &JObject) -> Result<ImpersonateConfig, JNIError> {
+	env.with_local_frame(0, |env| unsafe {
+		get_jni_config_inner(env, config_obj)
+	})
+}
+
+/// Reads the JVM-side per-request config.
+/// [config_obj]: An instance of `dev/rushii/ktor_impersonate/internal/RequestConfig`
+pub fn get_jni_request_config(env: &mut JNIEnv, config_obj: &JObject) -> Result<RequestConfig, JNIError> {
+	env.with_local_frame(0, |env| unsafe {
+		let header_order = env.call_method_unchecked(config_obj, cache::RequestConfig_getHeaderOrder(), ReturnType::Array, &[])?.l()?;
+		let header_order = if header_order.is_null() { None } else {
+			let names = get_string_array_values(env, &JObjectArray::from(header_order))?;
+			Some(Arc::from(parse_header_names(env, names)?))
+		};
+
+		let request_timeout = env.call_method_unchecked(config_obj, cache::RequestConfig_getRequestTimeoutMillis(), ReturnType::Object, &[])?.l()?;
+		let request_timeout = boxed_jni_to_primitive(env, &request_timeout)?.map(|v| v.j().unwrap());
 
-	Ok(client)
+		let connect_timeout = env.call_method_unchecked(config_obj, cache::RequestConfig_getConnectTimeoutMillis(), ReturnType::Object, &[])?.l()?;
+		let connect_timeout = boxed_jni_to_primitive(env, &connect_timeout)?.map(|v| v.j().unwrap());
+
+		let socket_timeout = env.call_method_unchecked(config_obj, cache::RequestConfig_getSocketTimeoutMillis(), ReturnType::Object, &[])?.l()?;
+		let socket_timeout = boxed_jni_to_primitive(env, &socket_timeout)?.map(|v| v.j().unwrap());
+
+		let (local_address, interface) = get_local_binding(
+			env,
+			config_obj,
+			cache::RequestConfig_getLocalAddress(),
+			cache::RequestConfig_getNetworkInterface(),
+		)?;
+
+		let http_version = env.call_method_unchecked(config_obj, cache::RequestConfig_getHttpVersionName(), ReturnType::Object, &[])?.l()?;
+		let http_version = if http_version.is_null() { None } else {
+			let http_version: String = env.get_string((&http_version).into())?.into();
+			Some(HttpVersionPolicy::from_str(&*http_version).expect("BUG: invalid http version policy"))
+		};
+
+		let unix_socket = env.call_method_unchecked(config_obj, cache::RequestConfig_getUnixSocket(), ReturnType::Object, &[])?.l()?;
+		let unix_socket: Option<String> = if unix_socket.is_null() { None } else {
+			Some(env.get_string((&unix_socket).into())?.into())
+		};
+
+		Ok(RequestConfig {
+			header_order,
+			request_timeout: request_timeout.map(|millis| Duration::from_millis(millis as u64)),
+			connect_timeout: connect_timeout.map(|millis| Duration::from_millis(millis as u64)),
+			socket_timeout: socket_timeout.map(|millis| Duration::from_millis(millis as u64)),
+			local_address,
+			interface,
+			http_version,
+			unix_socket: unix_socket.map(PathBuf::from),
+		})
+	})
 }
 
-unsafe fn get_jni_config(env: &mut JNIEnv, config_obj: &JObject) -> Result<ImpersonateConfig, JNIError> {
+unsafe fn get_jni_config_inner(env: &mut JNIEnv, config_obj: &JObject) -> Result<ImpersonateConfig, JNIError> {
 	if !env.is_instance_of(config_obj, &cache::ImpersonateConfig())? {
 		panic!("supplied config_obj is not of subtype ImpersonateConfig")
 	}
@@ -66,30 +100,313 @@ unsafe fn get_jni_config(env: &mut JNIEnv, config_obj: &JObject) -> Result<Imper
 	let idle_timeout = env.call_method_unchecked(config_obj, cache::ImpersonateConfig_getIdleTimeout(), ReturnType::Object, &[])?.l()?;
 	let idle_timeout = boxed_jni_to_primitive(env, &idle_timeout)?.map(|v| v.j().unwrap());
 
+	let max_idle_per_host = env.call_method_unchecked(config_obj, cache::ImpersonateConfig_getMaxIdleConnectionsPerHost(), ReturnType::Object, &[])?.l()?;
+	let max_idle_per_host = boxed_jni_to_primitive(env, &max_idle_per_host)?.map(|v| v.i().unwrap());
+	if max_idle_per_host.is_some_and(|max| max < 0) {
+		throw_argument!(env, "maxIdleConnectionsPerHost cannot be negative", Err(JNIError::JavaException));
+	}
+
+	let tcp_keepalive = env.call_method_unchecked(config_obj, cache::ImpersonateConfig_getTcpKeepAliveMillis(), ReturnType::Object, &[])?.l()?;
+	let tcp_keepalive = boxed_jni_to_primitive(env, &tcp_keepalive)?.map(|v| v.j().unwrap());
+
+	let tcp_nodelay = env.call_method_unchecked(config_obj, cache::ImpersonateConfig_getTcpNoDelay(), ReturnType::Object, &[])?.l()?;
+	let tcp_nodelay = boxed_jni_to_primitive(env, &tcp_nodelay)?.map(|v| v.z().unwrap());
+
+	let http2_keep_alive_interval = env.call_method_unchecked(config_obj, cache::ImpersonateConfig_getHttp2KeepAliveIntervalMillis(), ReturnType::Object, &[])?.l()?;
+	let http2_keep_alive_interval = boxed_jni_to_primitive(env, &http2_keep_alive_interval)?.map(|v| v.j().unwrap());
+
+	let http2_keep_alive_timeout = env.call_method_unchecked(config_obj, cache::ImpersonateConfig_getHttp2KeepAliveTimeoutMillis(), ReturnType::Object, &[])?.l()?;
+	let http2_keep_alive_timeout = boxed_jni_to_primitive(env, &http2_keep_alive_timeout)?.map(|v| v.j().unwrap());
+
+	let http2_keep_alive_while_idle = env.call_method_unchecked(config_obj, cache::ImpersonateConfig_getHttp2KeepAliveWhileIdle(), ReturnType::Object, &[])?.l()?;
+	let http2_keep_alive_while_idle = boxed_jni_to_primitive(env, &http2_keep_alive_while_idle)?.map(|v| v.z().unwrap());
+
 	let invalid_certs = env.call_method_unchecked(config_obj, cache::ImpersonateConfig_getAllowInvalidCertificates(), ReturnType::Object, &[])?.l()?;
 	let invalid_certs = boxed_jni_to_primitive(env, &invalid_certs)?.map(|v| v.z().unwrap());
 
 	let https_only = env.call_method_unchecked(config_obj, cache::ImpersonateConfig_getHttpsOnly(), ReturnType::Object, &[])?.l()?;
 	let https_only = boxed_jni_to_primitive(env, &https_only)?.map(|v| v.z().unwrap());
 
+	let header_order = env.call_method_unchecked(config_obj, cache::ImpersonateConfig_getHeaderOrder(), ReturnType::Object, &[])?.l()?;
+	let header_order = if header_order.is_null() { None } else {
+		let names = get_string_list_values(env, &header_order)?;
+		Some(Arc::from(parse_header_names(env, names)?))
+	};
+
+	let http1_title_case_headers = env.call_method_unchecked(config_obj, cache::ImpersonateConfig_getHttp1TitleCaseHeaders(), ReturnType::Object, &[])?.l()?;
+	let http1_title_case_headers = boxed_jni_to_primitive(env, &http1_title_case_headers)?.map(|v| v.z().unwrap());
+
+	let decompression = env.call_method_unchecked(config_obj, cache::ImpersonateConfig_getDecompression(), ReturnType::Primitive(Primitive::Boolean), &[])?.z()?;
+
+	let gzip = env.call_method_unchecked(config_obj, cache::ImpersonateConfig_getGzip(), ReturnType::Object, &[])?.l()?;
+	let gzip = boxed_jni_to_primitive(env, &gzip)?.map(|v| v.z().unwrap());
+
+	let brotli = env.call_method_unchecked(config_obj, cache::ImpersonateConfig_getBrotli(), ReturnType::Object, &[])?.l()?;
+	let brotli = boxed_jni_to_primitive(env, &brotli)?.map(|v| v.z().unwrap());
+
+	let deflate = env.call_method_unchecked(config_obj, cache::ImpersonateConfig_getDeflate(), ReturnType::Object, &[])?.l()?;
+	let deflate = boxed_jni_to_primitive(env, &deflate)?.map(|v| v.z().unwrap());
+
+	let zstd = env.call_method_unchecked(config_obj, cache::ImpersonateConfig_getZstd(), ReturnType::Object, &[])?.l()?;
+	let zstd = boxed_jni_to_primitive(env, &zstd)?.map(|v| v.z().unwrap());
+
+	let dns_resolver = env.call_method_unchecked(config_obj, cache::ImpersonateConfig_getDnsResolver(), ReturnType::Object, &[])?.l()?;
+	let dns_lookup = if dns_resolver.is_null() { None } else {
+		Some(Arc::new(JvmLookup::new(env, &dns_resolver)?) as Arc<dyn Lookup>)
+	};
+
+	let doh_url = env.call_method_unchecked(config_obj, cache::ImpersonateConfig_getDnsOverHttpsUrl(), ReturnType::Object, &[])?.l()?;
+	let doh = if doh_url.is_null() { None } else {
+		let doh_url: String = env.get_string((&doh_url).into())?.into();
+		let url = match rquest::Url::parse(&*doh_url) {
+			Ok(url) => url,
+			Err(err) => throw_argument!(env, &*format!("Invalid DNS-over-HTTPS url {doh_url:?}: {err}"), Err(JNIError::JavaException)),
+		};
+		let fallback = env.call_method_unchecked(config_obj, cache::ImpersonateConfig_getDnsOverHttpsFallback(), ReturnType::Primitive(Primitive::Boolean), &[])?.z()?;
+		Some(DohConfig { url, fallback })
+	};
+	if dns_lookup.is_some() && doh.is_some() {
+		throw_argument!(env, "dnsResolver and dnsOverHttpsUrl cannot be used together", Err(JNIError::JavaException));
+	}
+
+	let ip_preference = env.call_method_unchecked(config_obj, cache::ImpersonateConfig_getIpPreferenceName(), ReturnType::Object, &[])?.l()?;
+	let ip_preference: String = env.get_string((&ip_preference).into())?.into();
+	let ip_preference = IpPreference::from_str(&*ip_preference)
+		.expect("BUG: invalid ip preference");
+
+	let http_version = env.call_method_unchecked(config_obj, cache::ImpersonateConfig_getHttpVersionName(), ReturnType::Object, &[])?.l()?;
+	let http_version: String = env.get_string((&http_version).into())?.into();
+	let http_version = HttpVersionPolicy::from_str(&*http_version)
+		.expect("BUG: invalid http version policy");
+
+	let retry = get_retry_policy(env, config_obj)?;
+	let limits = get_limits(env, config_obj)?;
+	let cache = get_cache_config(env, config_obj)?;
+	let har = get_har_config(env, config_obj)?;
+
+	let (local_address, interface) = get_local_binding(
+		env,
+		config_obj,
+		cache::ImpersonateConfig_getLocalAddress(),
+		cache::ImpersonateConfig_getNetworkInterface(),
+	)?;
+
+	let unix_socket = env.call_method_unchecked(config_obj, cache::ImpersonateConfig_getUnixSocket(), ReturnType::Object, &[])?.l()?;
+	let unix_socket: Option<String> = if unix_socket.is_null() { None } else {
+		Some(env.get_string((&unix_socket).into())?.into())
+	};
+
+	let host_overrides = env.call_method_unchecked(config_obj, cache::ImpersonateConfig_getHostOverridesArray(), ReturnType::Array, &[])?.l()?;
+	let host_overrides = get_string_array_values(env, &JObjectArray::from(host_overrides))?;
+	let dns_overrides = parse_host_overrides(env, host_overrides)?;
+
 	Ok(ImpersonateConfig {
 		verbose_logging,
 		preset: preset.map(|str| str.into()),
 		request_timeout: request_timeout.map(|millis| Duration::from_millis(millis as u64)),
 		connect_timeout: connect_timeout.map(|millis| Duration::from_millis(millis as u64)),
 		idle_timeout: idle_timeout.map(|millis| Duration::from_millis(millis as u64)),
+		max_idle_per_host: max_idle_per_host.map(|max| max as usize),
+		tcp_keepalive: tcp_keepalive.map(|millis| Duration::from_millis(millis as u64)),
+		tcp_nodelay,
+		http2_keep_alive_interval: http2_keep_alive_interval.map(|millis| Duration::from_millis(millis as u64)),
+		http2_keep_alive_timeout: http2_keep_alive_timeout.map(|millis| Duration::from_millis(millis as u64)),
+		http2_keep_alive_while_idle,
 		invalid_certs,
 		https_only,
+		header_order,
+		http1_title_case_headers,
+		decompression,
+		gzip,
+		brotli,
+		deflate,
+		zstd,
+		dns_overrides,
+		dns_lookup,
+		doh,
+		ip_preference,
+		http_version,
+		retry,
+		limits,
+		cache,
+		har,
+		local_address,
+		interface,
+		unix_socket: unix_socket.map(PathBuf::from),
 	})
 }
 
-#[derive(Debug)]
-struct ImpersonateConfig {
-	verbose_logging: bool,
-	preset: Option<String>,
-	request_timeout: Option<Duration>,
-	connect_timeout: Option<Duration>,
-	idle_timeout: Option<Duration>,
-	invalid_certs: Option<bool>,
-	https_only: Option<bool>,
+/// Reads the config of the native response cache, if enabled.
+unsafe fn get_cache_config(env: &mut JNIEnv, config_obj: &JObject) -> Result<Option<CacheConfig>, JNIError> {
+	let sizes = env.call_method_unchecked(config_obj, cache::ImpersonateConfig_getResponseCacheSizes(), ReturnType::Array, &[])?.l()?;
+	if sizes.is_null() { return Ok(None); }
+
+	let mut buf = [0; 2];
+	env.get_long_array_region(&JLongArray::from(sizes), 0, &mut buf)?;
+
+	let directory = env.call_method_unchecked(config_obj, cache::ImpersonateConfig_getResponseCacheDirectory(), ReturnType::Object, &[])?.l()?;
+	let directory: Option<String> = if directory.is_null() { None } else {
+		Some(env.get_string((&directory).into())?.into())
+	};
+
+	Ok(Some(CacheConfig {
+		max_memory_size: buf[0].max(0) as u64,
+		directory: directory.map(PathBuf::from),
+		max_disk_size: buf[1].max(0) as u64,
+	}))
+}
+
+/// Reads the config of the HAR recorder, if enabled.
+unsafe fn get_har_config(env: &mut JNIEnv, config_obj: &JObject) -> Result<Option<HarConfig>, JNIError> {
+	let path = env.call_method_unchecked(config_obj, cache::ImpersonateConfig_getHarFile(), ReturnType::Object, &[])?.l()?;
+	let path: Option<String> = if path.is_null() { None } else {
+		Some(env.get_string((&path).into())?.into())
+	};
+
+	let listener = env.call_method_unchecked(config_obj, cache::ImpersonateConfig_getHarListener(), ReturnType::Object, &[])?.l()?;
+	let listener = if listener.is_null() { None } else {
+		Some(Arc::new(JvmHarListener::new(env, &listener)?) as Arc<dyn HarListener>)
+	};
+
+	if path.is_none() && listener.is_none() { return Ok(None); }
+
+	let max_body_size = env.call_method_unchecked(config_obj, cache::ImpersonateConfig_getHarMaxBodySize(), ReturnType::Primitive(Primitive::Long), &[])?.j()?;
+
+	Ok(Some(HarConfig {
+		path: path.map(PathBuf::from),
+		listener,
+		max_body_size: max_body_size.max(0) as usize,
+	}))
+}
+
+/// Reads the rate and concurrency limits.
+unsafe fn get_limits(env: &mut JNIEnv, config_obj: &JObject) -> Result<LimitsConfig, JNIError> {
+	let rate_limit = get_rate_limit(env, config_obj, cache::ImpersonateConfig_getRateLimitValues())?;
+	let rate_limit_per_host = get_rate_limit(env, config_obj, cache::ImpersonateConfig_getRateLimitPerHostValues())?;
+
+	let max_concurrent = env.call_method_unchecked(config_obj, cache::ImpersonateConfig_getMaxConcurrentRequests(), ReturnType::Object, &[])?.l()?;
+	let max_concurrent = boxed_jni_to_primitive(env, &max_concurrent)?.map(|v| v.i().unwrap());
+
+	let max_concurrent_per_host = env.call_method_unchecked(config_obj, cache::ImpersonateConfig_getMaxConcurrentRequestsPerHost(), ReturnType::Object, &[])?.l()?;
+	let max_concurrent_per_host = boxed_jni_to_primitive(env, &max_concurrent_per_host)?.map(|v| v.i().unwrap());
+
+	if max_concurrent.is_some_and(|max| max < 1) || max_concurrent_per_host.is_some_and(|max| max < 1) {
+		throw_argument!(env, "Concurrent request limits must be at least 1", Err(JNIError::JavaException));
+	}
+
+	Ok(LimitsConfig {
+		rate_limit,
+		rate_limit_per_host,
+		max_concurrent: max_concurrent.map(|max| max as usize),
+		max_concurrent_per_host: max_concurrent_per_host.map(|max| max as usize),
+	})
+}
+
+/// Reads a rate limit from a getter returning `[requests, periodMillis]`, which has already been validated by the JVM side.
+unsafe fn get_rate_limit(env: &mut JNIEnv, config_obj: &JObject, getter: JMethodID) -> Result<Option<RateLimit>, JNIError> {
+	let values = env.call_method_unchecked(config_obj, getter, ReturnType::Array, &[])?.l()?;
+	if values.is_null() { return Ok(None); }
+
+	let mut buf = [0; 2];
+	env.get_long_array_region(&JLongArray::from(values), 0, &mut buf)?;
+
+	Ok(Some(RateLimit {
+		requests: buf[0] as u32,
+		period: Duration::from_millis(buf[1] as u64),
+	}))
+}
+
+/// Reads the native retry policy, which is disabled when only a single attempt is allowed.
+unsafe fn get_retry_policy(env: &mut JNIEnv, config_obj: &JObject) -> Result<Option<RetryPolicy>, JNIError> {
+	let max_attempts = env.call_method_unchecked(config_obj, cache::ImpersonateConfig_getRetryMaxAttempts(), ReturnType::Primitive(Primitive::Int), &[])?.i()?;
+	if max_attempts < 1 {
+		throw_argument!(env, "retryMaxAttempts must be at least 1", Err(JNIError::JavaException));
+	}
+	if max_attempts == 1 {
+		return Ok(None);
+	}
+
+	let retry_on_connect_failure = env.call_method_unchecked(config_obj, cache::ImpersonateConfig_getRetryOnConnectionFailure(), ReturnType::Primitive(Primitive::Boolean), &[])?.z()?;
+	let respect_retry_after = env.call_method_unchecked(config_obj, cache::ImpersonateConfig_getRespectRetryAfter(), ReturnType::Primitive(Primitive::Boolean), &[])?.z()?;
+	let base_delay = env.call_method_unchecked(config_obj, cache::ImpersonateConfig_getRetryBaseDelayMillis(), ReturnType::Primitive(Primitive::Long), &[])?.j()?;
+	let max_delay = env.call_method_unchecked(config_obj, cache::ImpersonateConfig_getRetryMaxDelayMillis(), ReturnType::Primitive(Primitive::Long), &[])?.j()?;
+
+	let status_codes = env.call_method_unchecked(config_obj, cache::ImpersonateConfig_getRetryStatusCodesArray(), ReturnType::Array, &[])?.l()?;
+	let status_codes = JIntArray::from(status_codes);
+	let mut codes = vec![0; env.get_array_length(&status_codes)? as usize];
+	env.get_int_array_region(&status_codes, 0, &mut codes)?;
+
+	Ok(Some(RetryPolicy {
+		max_attempts: max_attempts as u32,
+		retry_on_connect_failure,
+		retry_status_codes: codes.into_iter().map(|code| code as u16).collect(),
+		base_delay: Duration::from_millis(base_delay.max(0) as u64),
+		max_delay: Duration::from_millis(max_delay.max(0) as u64),
+		respect_retry_after,
+	}))
+}
+
+/// Reads and validates the local address and network interface to bind connections to,
+/// from the getters of either the client or request config.
+unsafe fn get_local_binding(
+	env: &mut JNIEnv,
+	config_obj: &JObject,
+	get_local_address: JMethodID,
+	get_network_interface: JMethodID,
+) -> Result<(Option<IpAddr>, Option<String>), JNIError> {
+	let local_address = env.call_method_unchecked(config_obj, get_local_address, ReturnType::Object, &[])?.l()?;
+	let local_address = if local_address.is_null() { None } else {
+		let address: String = env.get_string((&local_address).into())?.into();
+		match IpAddr::from_str(&*address) {
+			Ok(ip) => Some(ip),
+			Err(_) => throw_argument!(env, &*format!("Invalid local address: {address}"), Err(JNIError::JavaException)),
+		}
+	};
+
+	let interface = env.call_method_unchecked(config_obj, get_network_interface, ReturnType::Object, &[])?.l()?;
+	let interface: Option<String> = if interface.is_null() { None } else {
+		Some(env.get_string((&interface).into())?.into())
+	};
+
+	#[cfg(not(any(target_os = "android", target_os = "linux")))]
+	if interface.is_some() {
+		throw_argument!(env, "Binding to a network interface is not supported on this platform", Err(JNIError::JavaException));
+	}
+
+	Ok((local_address, interface))
+}
+
+/// Groups a flattened array of `[host, address, host, address, ...]` pairs by host,
+/// throwing an `IllegalArgumentException` naming the first invalid address.
+fn parse_host_overrides(env: &mut JNIEnv, pairs: Vec<String>) -> Result<Vec<(String, Vec<SocketAddr>)>, JNIError> {
+	let mut overrides: Vec<(String, Vec<SocketAddr>)> = Vec::new();
+
+	for pair in pairs.chunks_exact(2) {
+		let (host, address) = (&pair[0], &pair[1]);
+		let ip = match IpAddr::from_str(&*address) {
+			Ok(ip) => ip,
+			Err(_) => throw_argument!(env, &*format!("Invalid IP address for host override {host}: {address}"), Err(JNIError::JavaException)),
+		};
+
+		// Ports are ignored by rquest in favor of the port of the URL
+		let addr = SocketAddr::new(ip, 0);
+		match overrides.iter_mut().find(|(existing, _)| existing == host) {
+			Some((_, addrs)) => addrs.push(addr),
+			None => overrides.push((host.clone(), vec![addr])),
+		}
+	}
+
+	Ok(overrides)
+}
+
+/// Parses a list of header names, throwing an `IllegalArgumentException` naming the first invalid one.
+fn parse_header_names(env: &mut JNIEnv, names: Vec<String>) -> Result<Vec<HeaderName>, JNIError> {
+	let mut header_names = Vec::with_capacity(names.len());
+	for name in names {
+		match HeaderName::from_str(&*name) {
+			Ok(v) => header_names.push(v),
+			Err(_) => throw_argument!(env, &*format!("Invalid header name in header order: {name}"), Err(JNIError::JavaException)),
+		}
+	}
+	Ok(header_names)
 }
@@ -0,0 +1,99 @@
+use crate::dns::Lookup;
+use crate::jni::{cache, utils};
+use futures_util::future::BoxFuture;
+use jni::errors::Error as JNIError;
+use jni::objects::{GlobalRef, JObject, JString, JValueGen};
+use jni::signature::ReturnType;
+use jni::{JNIEnv, JavaVM};
+use std::fmt::{Debug, Formatter};
+use std::net::IpAddr;
+use std::str::FromStr;
+use std::sync::Arc;
+
+type BoxError = Box<dyn std::error::Error + Send + Sync>;
+
+/// A [Lookup] that calls into a JVM-side `dev/rushii/ktor_impersonate/DnsResolver` instance.
+#[derive(Clone)]
+pub struct JvmLookup {
+	vm: Arc<JavaVM>,
+	resolver: GlobalRef,
+}
+
+impl JvmLookup {
+	pub fn new(env: &mut JNIEnv, resolver_obj: &JObject) -> Result<Self, JNIError> {
+		Ok(Self {
+			vm: Arc::new(env.get_java_vm()?),
+			resolver: env.new_global_ref(resolver_obj)?,
+		})
+	}
+}
+
+impl Debug for JvmLookup {
+	fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+		f.debug_struct("JvmLookup").finish_non_exhaustive()
+	}
+}
+
+impl Lookup for JvmLookup {
+	fn lookup(&self, host: String) -> BoxFuture<'static, Result<Vec<IpAddr>, BoxError>> {
+		let lookup = self.clone();
+
+		// The JVM-side resolver is allowed to block, so it is run on the blocking thread pool
+		Box::pin(async move {
+			tokio::task::spawn_blocking(move || lookup.lookup_blocking(&*host)).await?
+		})
+	}
+}
+
+impl JvmLookup {
+	fn lookup_blocking(&self, host: &str) -> Result<Vec<IpAddr>, BoxError> {
+		// We assume this thread is already attached to the VM based on the tokio runtime config
+		let mut env = self.vm.get_env().expect("Thread is not attached to JavaVM");
+
+		let result = env.with_local_frame(4, |env| {
+			let host_jni = JValueGen::from(env.new_string(host)?).as_jni();
+
+			// SAFETY: Method ID is always valid and sig types are correct
+			let list = unsafe {
+				env.call_method_unchecked(
+					&self.resolver,
+					cache::DnsResolver_resolve(),
+					ReturnType::Object,
+					&[host_jni],
+				)
+			}?.l()?;
+			if list.is_null() { return Ok(Vec::new()); }
+
+			unsafe { utils::get_string_list_values(env, &list) }
+		});
+
+		let addresses = match result {
+			Ok(addresses) => addresses,
+			Err(JNIError::JavaException) => {
+				let message = take_exception_message(&mut env)?;
+				return Err(format!("DnsResolver failed to resolve {host}: {message}").into());
+			}
+			Err(err) => return Err(err.into()),
+		};
+
+		addresses.iter()
+			.map(|address| IpAddr::from_str(&*address)
+				.map_err(|_| BoxError::from(format!("DnsResolver returned an invalid IP address for {host}: {address}"))))
+			.collect()
+	}
+}
+
+/// Clears the currently thrown JVM exception and returns its `toString()`.
+fn take_exception_message(env: &mut JNIEnv) -> Result<String, JNIError> {
+	let throwable = env.exception_occurred()?;
+	env.exception_clear()?;
+
+	// SAFETY: Method ID is always valid and sig types are correct
+	let message = unsafe {
+		env.call_method_unchecked(&throwable, cache::Object_toString(), ReturnType::Object, &[])
+	}?.l()?;
+	let message = env.auto_local(JString::from(message));
+	let message = env.get_string(&*message)?;
+
+	Ok(message.into())
+}
@@ -0,0 +1,146 @@
+use crate::client::NativeClient;
+use crate::download::{download, DownloadError, DownloadResult};
+use crate::jni::cache;
+use crate::jni::client::{version_numbers, ErrorKind};
+use crate::jni::headers::{headers_to_jni, jni_to_headers};
+use crate::requests::{new_request_id, RequestTask, ACTIVE_REQUESTS};
+use crate::{throw, throw_argument, TOKIO_RUNTIME};
+use catch_panic::catch_panic;
+use dashmap::Entry;
+use jni::errors::Error as JNIError;
+use jni::objects::{GlobalRef, JClass, JObject, JString, JValueGen, JValueOwned};
+use jni::signature::{Primitive, ReturnType};
+use jni::sys::{jboolean, jint, jlong};
+use jni::{JNIEnv, JavaVM};
+use jni_fn::jni_fn;
+use std::path::PathBuf;
+
+#[catch_panic]
+#[jni_fn("dev.rushii.ktor_impersonate.internal.NativeEngine")]
+pub fn executeDownload<'l>(
+	mut env: JNIEnv<'l>,
+	_cls: JClass<'l>,
+	client_ptr: jlong,
+	callbacks: JObject<'l>,
+	url: JString<'l>,
+	headers: JObject<'l>,
+	path: JString<'l>,
+	resume: jboolean,
+) -> jint {
+	// SAFETY: Parameters are java/lang/String without a doubt
+	let url: String = unsafe { env.get_string_unchecked(&url) }.unwrap().into();
+	let path: String = unsafe { env.get_string_unchecked(&path) }.unwrap().into();
+	let headers = match jni_to_headers(&mut env, &headers) {
+		Ok(headers) => headers,
+		Err(JNIError::JavaException) => return -1,
+		Err(err) => throw!(env, &*format!("Failed to get headers: {err:?}"), -1),
+	};
+	let callbacks = env.new_global_ref(callbacks).unwrap();
+
+	let url = match rquest::Url::parse(&url) {
+		Err(err) => throw_argument!(env, &*format!("Failed to parse url: {err}"), -1),
+		Ok(url) => url,
+	};
+
+	// SAFETY: This works as long as the Java-side invariant is preserved
+	if client_ptr == 0 { throw!(env, "Client is already closed!", -1); }
+	let native_client = unsafe { &*(client_ptr as *const NativeClient) };
+	let client = match native_client.client_for(&Default::default()) {
+		Ok(client) => client,
+		Err(err) => throw_argument!(env, &*format!("Failed to build rquest Client: {err}"), -1),
+	};
+
+	let request = match client.get(url).headers(headers).build() {
+		Ok(req) => req,
+		Err(err) => throw!(env, &*format!("Failed to build request: {err}"), -1),
+	};
+
+	let runtime_lock = TOKIO_RUNTIME.read().expect("runtime lock poisoned");
+	let runtime = runtime_lock.as_ref().expect("runtime not initialized");
+
+	let request_id = new_request_id();
+	let vm = env.get_java_vm().unwrap();
+	let path = PathBuf::from(path);
+
+	match ACTIVE_REQUESTS.entry(request_id) {
+		Entry::Occupied(_) => panic!("BUG: broken atomic or id overflow"),
+		Entry::Vacant(entry) => entry.insert(RequestTask::PendingResponse {
+			abort: None,
+			body: None,
+			request_body: None,
+			permit: None,
+		}),
+	};
+
+	let task_handle = runtime.spawn(async move {
+		let result = download(&client, request, &path, resume > 0).await;
+
+		// The partial file is kept when the download is cancelled or fails, so that it can be resumed later
+		ACTIVE_REQUESTS.remove(&request_id);
+
+		match result {
+			Ok(result) => callback_download_complete(vm, callbacks, result),
+			Err(DownloadError::Request(err)) => {
+				let message = format!("Failed to download: {err}");
+				callback_download_error(vm, callbacks, ErrorKind::of(&err), message)
+			}
+			Err(err) => callback_download_error(vm, callbacks, ErrorKind::Other, err.to_string()),
+		}
+	});
+
+	// Allow cancelling the download, unless it has already completed
+	if let Some(mut entry) = ACTIVE_REQUESTS.get_mut(&request_id) {
+		if let RequestTask::PendingResponse { abort, .. } = entry.value_mut() {
+			if !task_handle.is_finished() {
+				*abort = Some(task_handle.abort_handle());
+			}
+		}
+	}
+
+	request_id as jint
+}
+
+// ------------------------ JNI Callbacks ------------------------ //
+
+fn callback_download_complete(vm: JavaVM, callbacks: GlobalRef, result: DownloadResult) {
+	// We assume this thread is already attached to the VM based on the tokio runtime config
+	let mut env = vm.get_env().expect("Thread is not attached to JavaVM");
+
+	let (version_major, version_minor) = version_numbers(result.version);
+	let version_major_jni = JValueOwned::from(version_major).as_jni();
+	let version_minor_jni = JValueOwned::from(version_minor).as_jni();
+	let status_jni = JValueOwned::from(result.status.as_u16() as i32).as_jni();
+	let size_jni = JValueOwned::from(result.size as i64).as_jni();
+	let headers_jni = headers_to_jni(&mut env, &result.headers)
+		.map(JValueOwned::from)
+		.expect("failed to convert headers map")
+		.as_jni();
+
+	// SAFETY: Method ID is always valid and sig types are correct
+	unsafe {
+		env.call_method_unchecked(
+			callbacks,
+			&cache::NativeDownloadCallbacks_onComplete(),
+			ReturnType::Primitive(Primitive::Void),
+			&[version_major_jni, version_minor_jni, status_jni, headers_jni, size_jni],
+		).expect("Failed to invoke onComplete callback");
+	}
+}
+
+fn callback_download_error(vm: JavaVM, callbacks: GlobalRef, kind: ErrorKind, message: String) {
+	// We assume this thread is already attached to the VM based on the tokio runtime config
+	let mut env = vm.get_env().expect("Thread is not attached to JavaVM");
+
+	let kind_jni = JValueOwned::from(kind as i32).as_jni();
+	let message_jni = JValueGen::from(env.new_string(message).unwrap()).as_jni();
+
+	// SAFETY: Method ID is always valid and sig types are correct
+	unsafe {
+		env.call_method_unchecked(
+			callbacks,
+			&cache::NativeDownloadCallbacks_onError(),
+			ReturnType::Primitive(Primitive::Void),
+			&[kind_jni, message_jni],
+		).expect("Failed to invoke onError callback");
+	}
+}
@@ -0,0 +1,111 @@
+use crate::client::NativeClient;
+use crate::fingerprint::{EchoServer, Fingerprint};
+use crate::jni::utils::new_string_array;
+use crate::{throw, TOKIO_RUNTIME};
+use catch_panic::catch_panic;
+use jni::objects::JClass;
+use jni::sys::{jint, jlong, jobjectArray};
+use jni::JNIEnv;
+use jni_fn::jni_fn;
+
+#[catch_panic(default = "std::ptr::null_mut()")]
+#[jni_fn("dev.rushii.ktor_impersonate.internal.NativeEngine")]
+pub fn getFingerprint<'l>(
+	mut env: JNIEnv<'l>,
+	_cls: JClass<'l>,
+	client_ptr: jlong,
+) -> jobjectArray {
+	if client_ptr == 0 { throw!(env, "Client is already closed!", std::ptr::null_mut()); }
+	// SAFETY: This works as long as the Java-side invariant is preserved
+	let native_client = unsafe { &*(client_ptr as *const NativeClient) };
+
+	let runtime_lock = TOKIO_RUNTIME.read().expect("runtime lock poisoned");
+	let runtime = runtime_lock.as_ref().expect("runtime not initialized");
+
+	// This is called from a JVM thread that is allowed to block
+	let fingerprint = match runtime.block_on(native_client.fingerprint()) {
+		Ok(fingerprint) => fingerprint,
+		Err(err) => throw!(env, &*format!("Failed to determine fingerprint: {err}"), std::ptr::null_mut()),
+	};
+
+	match new_string_array(&mut env, &fingerprint_values(fingerprint)) {
+		Ok(array) => array.into_raw(),
+		Err(err) => throw!(env, &*format!("Failed to create fingerprint array: {err:?}"), std::ptr::null_mut()),
+	}
+}
+
+#[catch_panic]
+#[jni_fn("dev.rushii.ktor_impersonate.internal.NativeEngine")]
+pub fn startEchoServer<'l>(
+	mut env: JNIEnv<'l>,
+	_cls: JClass<'l>,
+) -> jlong {
+	let runtime_lock = TOKIO_RUNTIME.read().expect("runtime lock poisoned");
+	let runtime = runtime_lock.as_ref().expect("runtime not initialized");
+
+	match runtime.block_on(EchoServer::start()) {
+		Ok(server) => Box::leak(Box::new(server)) as *const EchoServer as jlong,
+		Err(err) => throw!(env, &*format!("Failed to start echo server: {err}"), 0),
+	}
+}
+
+#[catch_panic]
+#[jni_fn("dev.rushii.ktor_impersonate.internal.NativeEngine")]
+pub fn stopEchoServer<'l>(
+	_env: JNIEnv<'l>,
+	_cls: JClass<'l>,
+	server_ptr: jlong,
+) {
+	let server_ptr = server_ptr as *mut EchoServer;
+	if server_ptr.is_null() { return; }
+
+	// SAFETY: This works as long as the Java-side invariant is preserved
+	drop(unsafe { Box::from_raw(server_ptr) });
+}
+
+#[catch_panic]
+#[jni_fn("dev.rushii.ktor_impersonate.internal.NativeEngine")]
+pub fn getEchoServerPort<'l>(
+	mut env: JNIEnv<'l>,
+	_cls: JClass<'l>,
+	server_ptr: jlong,
+) -> jint {
+	if server_ptr == 0 { throw!(env, "Echo server is already closed!", 0); }
+	// SAFETY: This works as long as the Java-side invariant is preserved
+	let server = unsafe { &*(server_ptr as *const EchoServer) };
+
+	server.address().port() as jint
+}
+
+#[catch_panic(default = "std::ptr::null_mut()")]
+#[jni_fn("dev.rushii.ktor_impersonate.internal.NativeEngine")]
+pub fn getEchoServerFingerprints<'l>(
+	mut env: JNIEnv<'l>,
+	_cls: JClass<'l>,
+	server_ptr: jlong,
+) -> jobjectArray {
+	if server_ptr == 0 { throw!(env, "Echo server is already closed!", std::ptr::null_mut()); }
+	// SAFETY: This works as long as the Java-side invariant is preserved
+	let server = unsafe { &*(server_ptr as *const EchoServer) };
+
+	let values: Vec<String> = server.observed()
+		.into_iter()
+		.flat_map(fingerprint_values)
+		.collect();
+
+	match new_string_array(&mut env, &values) {
+		Ok(array) => array.into_raw(),
+		Err(err) => throw!(env, &*format!("Failed to create fingerprint array: {err:?}"), std::ptr::null_mut()),
+	}
+}
+
+/// Flattens a fingerprint into `[ja3, ja3Hash, ja4, akamai, akamaiHash]`, with empty strings for absent values.
+fn fingerprint_values(fingerprint: Fingerprint) -> [String; 5] {
+	[
+		fingerprint.ja3,
+		fingerprint.ja3_hash,
+		fingerprint.ja4,
+		fingerprint.akamai.unwrap_or_default(),
+		fingerprint.akamai_hash.unwrap_or_default(),
+	]
+}
@@ -0,0 +1,63 @@
+use crate::har::HarListener;
+use crate::jni::cache;
+use jni::errors::Error as JNIError;
+use jni::objects::{GlobalRef, JObject, JValueGen};
+use jni::signature::{Primitive, ReturnType};
+use jni::{JNIEnv, JavaVM};
+use log::warn;
+use std::fmt::{Debug, Formatter};
+use std::sync::Arc;
+
+/// A [HarListener] that calls into a JVM-side `dev/rushii/ktor_impersonate/HarListener` instance.
+pub struct JvmHarListener {
+	vm: Arc<JavaVM>,
+	listener: GlobalRef,
+}
+
+impl JvmHarListener {
+	pub fn new(env: &mut JNIEnv, listener_obj: &JObject) -> Result<Self, JNIError> {
+		Ok(Self {
+			vm: Arc::new(env.get_java_vm()?),
+			listener: env.new_global_ref(listener_obj)?,
+		})
+	}
+}
+
+impl Debug for JvmHarListener {
+	fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+		f.debug_struct("JvmHarListener").finish_non_exhaustive()
+	}
+}
+
+impl HarListener for JvmHarListener {
+	fn on_entry(&self, entry: String) {
+		// Entries can be finished on JVM threads that are closing a response body, so this attaches if needed
+		let mut env = match self.vm.attach_current_thread_as_daemon() {
+			Ok(env) => env,
+			Err(err) => return warn!("Failed to attach thread to pass HAR entry: {err}"),
+		};
+
+		let result = env.with_local_frame(2, |env| {
+			let entry_jni = JValueGen::from(env.new_string(entry)?).as_jni();
+
+			// SAFETY: Method ID is always valid and sig types are correct
+			unsafe {
+				env.call_method_unchecked(
+					&self.listener,
+					cache::HarListener_onEntry(),
+					ReturnType::Primitive(Primitive::Void),
+					&[entry_jni],
+				)
+			}?;
+			Ok::<_, JNIError>(())
+		});
+
+		// Exceptions thrown by the listener cannot be propagated to any request
+		if let Err(JNIError::JavaException) = result {
+			let _ = env.exception_describe();
+			let _ = env.exception_clear();
+		} else if let Err(err) = result {
+			warn!("Failed to pass HAR entry to listener: {err}");
+		}
+	}
+}
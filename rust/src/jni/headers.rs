@@ -4,11 +4,12 @@ use jni::errors::Error as JNIError;
 use jni::objects::{JObject, JObjectArray, JString, JValue, JValueGen, JValueOwned};
 use jni::signature::{Primitive, ReturnType};
 use jni::JNIEnv;
-use rquest::header::{HeaderMap, HeaderName, HeaderValue};
+use rquest::header::{HeaderMap, HeaderName, HeaderValue, InvalidHeaderValue};
 use std::borrow::Cow;
 use std::str::FromStr;
 
 /// Converts a rquest [HeaderMap] into a JVM Ktor `io/ktor/http/Headers` instance.
+/// Header values that are not plain ASCII are decoded as ISO-8859-1, see [decode_header_value].
 pub fn headers_to_jni<'local>(env: &mut JNIEnv<'local>, headers: &HeaderMap) -> Result<JObject<'local>, JNIError> {
 	let key_count_jni = JValueOwned::from(headers.keys_len() as i32).as_jni();
 
@@ -23,10 +24,7 @@ pub fn headers_to_jni<'local>(env: &mut JNIEnv<'local>, headers: &HeaderMap) ->
 
 	for (key, value) in headers {
 		env.with_local_frame(2, |env| {
-			let value_str = match value.to_str() {
-				Ok(str) => Cow::from(str),
-				Err(_) => String::from_utf8_lossy(value.as_bytes())
-			};
+			let value_str = decode_header_value(value);
 
 			let key_jni = JValueGen::from(env.new_string(key)?).as_jni();
 			let value_jni = JValueGen::from(env.new_string(&*value_str)?).as_jni();
@@ -60,6 +58,7 @@ pub fn headers_to_jni<'local>(env: &mut JNIEnv<'local>, headers: &HeaderMap) ->
 }
 
 /// Converts a JVM Ktor `io/ktor/http/Headers` instance into a rquest [HeaderMap].
+/// Header values are encoded as ISO-8859-1 when possible, see [encode_header_value].
 pub fn jni_to_headers(env: &mut JNIEnv, headers_obj: &JObject) -> Result<HeaderMap, JNIError> {
 	if !env.is_instance_of(headers_obj, &cache::StringValues())? {
 		panic!("supplied headers_obj is not of subtype StringValues")
@@ -91,14 +90,14 @@ pub fn jni_to_headers(env: &mut JNIEnv, headers_obj: &JObject) -> Result<HeaderM
 
 		let header_name = match HeaderName::from_str(&*key_string) {
 			Ok(v) => v,
-			Err(_) => throw_argument!(env, "Invalid header name!", Err(JNIError::JavaException)),
+			Err(_) => throw_argument!(env, &*format!("Invalid header name: {key_string:?}"), Err(JNIError::JavaException)),
 		};
 
 		headers.reserve(values.len());
 		for value in values {
-			let header_value = match HeaderValue::try_from(&value) {
+			let header_value = match encode_header_value(&*value) {
 				Ok(v) => v,
-				Err(_) => throw_argument!(env, "Invalid header value", Err(JNIError::JavaException)),
+				Err(_) => throw_argument!(env, &*format!("Invalid value for header {key_string}: {value:?}"), Err(JNIError::JavaException)),
 			};
 
 			headers.append(header_name.clone(), header_value);
@@ -108,6 +107,48 @@ pub fn jni_to_headers(env: &mut JNIEnv, headers_obj: &JObject) -> Result<HeaderM
 	Ok(headers)
 }
 
+/// Decodes a header value into a string, treating any non-ASCII bytes (obs-text) as ISO-8859-1.
+/// Every byte maps to exactly one char, so this can be losslessly encoded back with [encode_header_value].
+fn decode_header_value(value: &HeaderValue) -> Cow<str> {
+	match value.to_str() {
+		Ok(str) => Cow::from(str),
+		Err(_) => Cow::from(value.as_bytes().iter().map(|&byte| byte as char).collect::<String>()),
+	}
+}
+
+/// Encodes a header value string into bytes as ISO-8859-1,
+/// unless it contains chars outside that range, in which case it is encoded as UTF-8 instead.
+fn encode_header_value(value: &str) -> Result<HeaderValue, InvalidHeaderValue> {
+	if value.is_ascii() || !value.chars().all(|char| char <= '\u{FF}') {
+		HeaderValue::from_bytes(value.as_bytes())
+	} else {
+		let bytes: Vec<u8> = value.chars().map(|char| char as u8).collect();
+		HeaderValue::from_bytes(&*bytes)
+	}
+}
+
+/// Reorders a [HeaderMap] so that the headers named in [order] come first, in that order.
+/// All other headers are kept after those, in their original relative order.
+// HeaderMap iterates in insertion order as long as no entries have been removed from it
+pub fn sort_headers(headers: &HeaderMap, order: &[HeaderName]) -> HeaderMap {
+	let mut sorted = HeaderMap::with_capacity(headers.len());
+
+	for name in order {
+		if sorted.contains_key(name) { continue; }
+
+		for value in headers.get_all(name) {
+			sorted.append(name.clone(), value.clone());
+		}
+	}
+	for (name, value) in headers {
+		if !order.contains(name) {
+			sorted.append(name.clone(), value.clone());
+		}
+	}
+
+	sorted
+}
+
 /// Gets all the keys into an object array of type `String[]`.
 /// [headers_obj]: Must be an instance of `io/ktor/util/StringValues`.
 unsafe fn get_stringvalues_keys<'local>(
@@ -120,3 +161,6 @@ unsafe fn get_stringvalues_keys<'local>(
 	let keys_array_obj = env.call_method_unchecked(&keys_set, cache::Set_toArray(), ReturnType::Array, &[])?.l()?;
 	Ok(JObjectArray::from(keys_array_obj))
 }
+
+#[cfg(test)]
+mod tests;
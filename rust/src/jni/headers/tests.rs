@@ -0,0 +1,87 @@
+use super::*;
+
+fn headers(entries: &[(&'static str, &'static str)]) -> HeaderMap {
+	let mut headers = HeaderMap::new();
+	for (name, value) in entries {
+		headers.append(*name, HeaderValue::from_static(value));
+	}
+	headers
+}
+
+fn names(headers: &HeaderMap) -> Vec<&str> {
+	headers.iter().map(|(name, _)| name.as_str()).collect()
+}
+
+#[test]
+fn sort_headers_moves_listed_headers_first() {
+	let headers = headers(&[("accept", "*/*"), ("user-agent", "test"), ("cookie", "a=b"), ("x-custom", "1")]);
+	let order = [HeaderName::from_static("cookie"), HeaderName::from_static("user-agent")];
+
+	let sorted = sort_headers(&headers, &order);
+
+	assert_eq!(names(&sorted), ["cookie", "user-agent", "accept", "x-custom"]);
+}
+
+#[test]
+fn sort_headers_keeps_all_values_of_repeated_headers() {
+	let headers = headers(&[("accept", "*/*"), ("cookie", "a=b"), ("cookie", "c=d")]);
+	let order = [HeaderName::from_static("cookie")];
+
+	let sorted = sort_headers(&headers, &order);
+
+	let cookies: Vec<_> = sorted.get_all("cookie").iter().map(|value| value.to_str().unwrap()).collect();
+	assert_eq!(cookies, ["a=b", "c=d"]);
+	assert_eq!(names(&sorted), ["cookie", "cookie", "accept"]);
+}
+
+#[test]
+fn sort_headers_ignores_absent_and_duplicate_names_in_order() {
+	let headers = headers(&[("accept", "*/*"), ("user-agent", "test")]);
+	let order = [
+		HeaderName::from_static("x-missing"),
+		HeaderName::from_static("user-agent"),
+		HeaderName::from_static("user-agent"),
+	];
+
+	let sorted = sort_headers(&headers, &order);
+
+	assert_eq!(names(&sorted), ["user-agent", "accept"]);
+	assert_eq!(sorted.len(), 2);
+}
+
+#[test]
+fn sort_headers_with_empty_order_keeps_original_order() {
+	let headers = headers(&[("x-b", "1"), ("x-a", "2"), ("x-c", "3")]);
+
+	let sorted = sort_headers(&headers, &[]);
+
+	assert_eq!(names(&sorted), ["x-b", "x-a", "x-c"]);
+}
+
+#[test]
+fn latin1_header_values_round_trip() {
+	let value = encode_header_value("caf\u{e9} \u{ff}").unwrap();
+
+	assert_eq!(value.as_bytes(), b"caf\xe9 \xff");
+	assert_eq!(decode_header_value(&value), "caf\u{e9} \u{ff}");
+}
+
+#[test]
+fn ascii_header_values_are_borrowed() {
+	let value = HeaderValue::from_static("text/html");
+
+	assert!(matches!(decode_header_value(&value), Cow::Borrowed("text/html")));
+}
+
+#[test]
+fn header_values_outside_latin1_are_encoded_as_utf8() {
+	let value = encode_header_value("\u{263a} caf\u{e9}").unwrap();
+
+	assert_eq!(value.as_bytes(), "\u{263a} caf\u{e9}".as_bytes());
+}
+
+#[test]
+fn header_values_with_control_chars_are_rejected() {
+	assert!(encode_header_value("a\r\nInjected: 1").is_err());
+	assert!(encode_header_value("a\0").is_err());
+}
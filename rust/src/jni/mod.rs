@@ -10,6 +10,13 @@ mod headers;
 mod config;
 mod utils;
 mod source;
+mod body;
+mod dns;
+mod fingerprint;
+mod har;
+mod upload;
+mod download;
+mod preconnect;
 
 #[no_mangle]
 pub extern "system" fn JNI_OnLoad(vm: JavaVM, _reserved: c_void) -> jint {
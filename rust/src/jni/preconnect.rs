@@ -0,0 +1,121 @@
+use crate::client::NativeClient;
+use crate::jni::cache;
+use crate::jni::client::ErrorKind;
+use crate::requests::{new_request_id, RequestTask, ACTIVE_REQUESTS};
+use crate::{throw, throw_argument, TOKIO_RUNTIME};
+use catch_panic::catch_panic;
+use dashmap::Entry;
+use jni::objects::{GlobalRef, JClass, JObject, JString, JValueGen, JValueOwned};
+use jni::signature::{Primitive, ReturnType};
+use jni::sys::{jint, jlong};
+use jni::{JNIEnv, JavaVM};
+use jni_fn::jni_fn;
+
+/// Establishes a connection by sending a `HEAD` request, since rquest does not expose its connector.
+/// The request bypasses the client's limits, retries, cache and HAR recording,
+/// and its response is read to the end natively so that the connection is returned to the pool.
+#[catch_panic]
+#[jni_fn("dev.rushii.ktor_impersonate.internal.NativeEngine")]
+pub fn preconnect<'l>(
+	mut env: JNIEnv<'l>,
+	_cls: JClass<'l>,
+	client_ptr: jlong,
+	callbacks: JObject<'l>,
+	url: JString<'l>,
+) -> jint {
+	// SAFETY: Parameter is java/lang/String without a doubt
+	let url: String = unsafe { env.get_string_unchecked(&url) }.unwrap().into();
+	let callbacks = env.new_global_ref(callbacks).unwrap();
+
+	let url = match rquest::Url::parse(&url) {
+		Err(err) => throw_argument!(env, &*format!("Failed to parse url: {err}"), -1),
+		Ok(url) => url,
+	};
+
+	// SAFETY: This works as long as the Java-side invariant is preserved
+	if client_ptr == 0 { throw!(env, "Client is already closed!", -1); }
+	let native_client = unsafe { &*(client_ptr as *const NativeClient) };
+	let client = match native_client.client_for(&Default::default()) {
+		Ok(client) => client,
+		Err(err) => throw_argument!(env, &*format!("Failed to build rquest Client: {err}"), -1),
+	};
+
+	let request = match client.head(url).build() {
+		Ok(req) => req,
+		Err(err) => throw!(env, &*format!("Failed to build request: {err}"), -1),
+	};
+
+	let runtime_lock = TOKIO_RUNTIME.read().expect("runtime lock poisoned");
+	let runtime = runtime_lock.as_ref().expect("runtime not initialized");
+
+	let request_id = new_request_id();
+	let vm = env.get_java_vm().unwrap();
+
+	match ACTIVE_REQUESTS.entry(request_id) {
+		Entry::Occupied(_) => panic!("BUG: broken atomic or id overflow"),
+		Entry::Vacant(entry) => entry.insert(RequestTask::Preconnect { abort: None }),
+	};
+
+	let task_handle = runtime.spawn(async move {
+		let result = match client.execute(request).await {
+			Ok(response) => response.bytes().await.map(drop),
+			Err(err) => Err(err),
+		};
+
+		ACTIVE_REQUESTS.remove(&request_id);
+
+		match result {
+			Ok(()) => callback_preconnect_connected(vm, callbacks),
+			Err(err) => {
+				let message = format!("Failed to preconnect: {err}");
+				callback_preconnect_error(vm, callbacks, ErrorKind::of(&err), message)
+			}
+		}
+	});
+
+	// Allow cancelling the request, unless it has already completed
+	if let Some(mut entry) = ACTIVE_REQUESTS.get_mut(&request_id) {
+		if let RequestTask::Preconnect { abort } = entry.value_mut() {
+			if !task_handle.is_finished() {
+				*abort = Some(task_handle.abort_handle());
+			}
+		}
+	}
+
+	request_id as jint
+}
+
+// ------------------------ JNI Callbacks ------------------------ //
+
+fn callback_preconnect_connected(vm: JavaVM, callbacks: GlobalRef) {
+	// We assume this thread is already attached to the VM based on the tokio runtime config
+	let mut env = vm.get_env().expect("Thread is not attached to JavaVM");
+
+	// SAFETY: Method ID is always valid and sig types are correct
+	unsafe {
+		env.call_method_unchecked(
+			callbacks,
+			&cache::NativePreconnectCallbacks_onConnected(),
+			ReturnType::Primitive(Primitive::Void),
+			&[],
+		).expect("Failed to invoke onConnected callback");
+	}
+}
+
+fn callback_preconnect_error(vm: JavaVM, callbacks: GlobalRef, kind: ErrorKind, message: String) {
+	// We assume this thread is already attached to the VM based on the tokio runtime config
+	let mut env = vm.get_env().expect("Thread is not attached to JavaVM");
+
+	let kind_jni = JValueOwned::from(kind as i32).as_jni();
+	let message_jni = JValueGen::from(env.new_string(message).unwrap()).as_jni();
+
+	// SAFETY: Method ID is always valid and sig types are correct
+	unsafe {
+		env.call_method_unchecked(
+			callbacks,
+			&cache::NativePreconnectCallbacks_onError(),
+			ReturnType::Primitive(Primitive::Void),
+			&[kind_jni, message_jni],
+		).expect("Failed to invoke onError callback");
+	}
+}
@@ -1,14 +1,16 @@
 use crate::jni::cache;
-use crate::requests::{RequestTask, ACTIVE_REQUESTS};
+use crate::jni::headers::headers_to_jni;
+use crate::requests::{RequestTask, Trailers, ACTIVE_REQUESTS};
 use crate::{throw, throw_argument, TOKIO_RUNTIME};
 use catch_panic::catch_panic;
 use futures_util::StreamExt;
 use jni::errors::Error as JNIError;
-use jni::objects::{JObject, JValue};
+use jni::objects::{JByteArray, JObject, JValue};
 use jni::signature::{Primitive, ReturnType};
-use jni::sys::{jint, jlong};
+use jni::sys::jint;
 use jni::JNIEnv;
 use jni_fn::jni_fn;
+use rquest::header::HeaderMap;
 
 #[catch_panic]
 #[jni_fn("dev.rushii.ktor_impersonate.internal.ResponseSource")]
@@ -47,14 +49,22 @@ pub fn close<'l>(mut env: JNIEnv<'l>, instance: JObject<'l>) {
 	}
 }
 
+/// Returned by [readInto] when no part of the body was received within the socket timeout.
+/// This must be kept in sync with `ResponseSource.READ_TIMED_OUT`.
+const READ_TIMED_OUT: jint = -2;
+
+/// Copies the next part of the response body directly into a JVM byte array (a segment of the `Buffer` being read into).
+/// This blocks until at least one byte is available, and returns the amount of bytes copied or `-1` once the body has ended.
+/// Any part of a received chunk that does not fit into the array is kept for the next call.
 #[catch_panic]
 #[jni_fn("dev.rushii.ktor_impersonate.internal.ResponseSource")]
-pub fn readAtMostTo<'l>(
+pub fn readInto<'l>(
 	mut env: JNIEnv<'l>,
 	instance: JObject<'l>,
-	sink: JObject<'l>,
-	min_bytes: jlong,
-) -> jlong {
+	array: JByteArray<'l>,
+	offset: jint,
+	length: jint,
+) -> jint {
 	// Get the request id stored in a field in the class
 	let request_id = match get_request_id(&mut env, &instance) {
 		Err(err) => throw!(env, &*format!("Failed to get request id: {err:?}"), 0),
@@ -62,8 +72,8 @@ pub fn readAtMostTo<'l>(
 		Ok(Some(id)) => id,
 	};
 
-	// Get the body stream from the global ACTIVE_REQUESTS store
-	let stream_mutex = match ACTIVE_REQUESTS.get(&request_id) {
+	// Get the body from the global ACTIVE_REQUESTS store
+	let body_mutex = match ACTIVE_REQUESTS.get(&request_id) {
 		None => return -1,
 		Some(entry) => {
 			let body_cell = match entry.value() {
@@ -76,59 +86,68 @@ pub fn readAtMostTo<'l>(
 			}
 		}
 	};
-	let mut stream = stream_mutex
+	let mut body = body_mutex
 		.lock()
 		.unwrap(); // Propagate poison error
 
-	// Get tokio runtime to run async stream collector
-	let runtime_lock = TOKIO_RUNTIME.read().expect("runtime lock poisoned");
-	let runtime = runtime_lock.as_ref().expect("runtime not initialized");
+	// Receive the next chunk if the previous one has been fully read
+	if body.pending.is_empty() {
+		// Get tokio runtime to run async stream collector
+		let runtime_lock = TOKIO_RUNTIME.read().expect("runtime lock poisoned");
+		let runtime = runtime_lock.as_ref().expect("runtime not initialized");
 
-	let mut total_bytes: usize = 0;
-	while total_bytes < min_bytes as usize {
 		// FIXME: This should not block! Figure out a way to use a suspend RawSource to connect this to a cancellableCoroutine instead
+		let read_timeout = body.read_timeout;
 		let result = runtime.block_on(async {
-			stream.next().await
+			let next_chunk = async {
+				loop {
+					match body.stream.next().await {
+						Some(Ok(bytes)) if bytes.is_empty() => continue,
+						result => break result,
+					}
+				}
+			};
+
+			match read_timeout {
+				None => Ok(next_chunk.await),
+				Some(timeout) => tokio::time::timeout(timeout, next_chunk).await,
+			}
 		});
-		let bytes = match result {
+		let result = match result {
+			Ok(result) => result,
+			Err(_) => return READ_TIMED_OUT,
+		};
+		match result {
 			// EOF
 			None => {
+				let trailers = body.trailers.as_ref().and_then(Trailers::get);
+				drop(body);
 				if let Err(err) = clear_request(&mut env, &instance, request_id) {
 					throw!(env, &*format!("Failed to clear request id: {err:?}"), 0);
 				}
-				break;
+				if let Some(trailers) = trailers {
+					if let Err(err) = callback_trailers(&mut env, &instance, &trailers) {
+						throw!(env, &*format!("Failed to pass response trailers: {err:?}"), 0);
+					}
+				}
+				return -1;
 			}
-			Some(result) => result.unwrap(), // TODO: handle errors
-		};
+			Some(Err(err)) => throw!(env, &*format!("Failed to read response body: {err}"), 0),
+			Some(Ok(bytes)) => body.pending = bytes,
+		}
+	}
 
-		// Convert the bytes chunk into a JVM bytearray
-		let bytes_len = bytes.len();
-		let bytes_obj = match env.byte_array_from_slice(bytes.as_ref()) {
-			Ok(arr) => env.auto_local(arr),
-			Err(err) => throw!(env, &*format!("Failed to make response chunk bytearray: {err:?}"), 0)
-		};
+	// Copy as much of the chunk as fits, keeping the rest for later
+	let count = body.pending.len().min(length as usize);
+	let chunk = body.pending.split_to(count);
 
-		total_bytes += bytes_len;
-		drop(bytes);
-
-		let result = unsafe {
-			env.call_method_unchecked(
-				&sink,
-				cache::Sink_write(),
-				ReturnType::Primitive(Primitive::Void),
-				&[
-					JValue::from(&*bytes_obj).as_jni(),
-					JValue::from(0).as_jni(),
-					JValue::from(bytes_len as jint).as_jni()
-				],
-			)
-		};
-		if let Err(err) = result {
-			throw!(env, &*format!("Failed to write response chunk to sink: {err:?}"), 0);
-		}
+	// SAFETY: i8 and u8 have the same layout
+	let chunk_signed = unsafe { std::slice::from_raw_parts(chunk.as_ptr() as *const i8, chunk.len()) };
+	if let Err(err) = env.set_byte_array_region(&array, offset, chunk_signed) {
+		throw!(env, &*format!("Failed to copy response chunk: {err:?}"), 0);
 	}
 
-	if total_bytes == 0 { -1 } else { total_bytes as jlong }
+	count as jint
 }
 
 /// Extract the request ID from the `ResponseEngine#requestId` field.
@@ -145,6 +164,22 @@ fn get_request_id(env: &mut JNIEnv, source_obj: &JObject) -> Result<Option<u32>,
 	}
 }
 
+/// Passes the trailers of a response to its `ResponseSource` once the body has ended.
+fn callback_trailers(env: &mut JNIEnv, source_obj: &JObject, trailers: &HeaderMap) -> Result<(), JNIError> {
+	let headers = headers_to_jni(env, trailers)?;
+
+	// SAFETY: Method ID is always valid and sig types are correct
+	unsafe {
+		env.call_method_unchecked(
+			source_obj,
+			&cache::ResponseSource_onTrailers(),
+			ReturnType::Primitive(Primitive::Void),
+			&[JValue::from(&headers).as_jni()],
+		)?;
+	}
+	Ok(())
+}
+
 /// Clears the request ID from the `ResponseEngine#requestId` field,
 /// and removes the request from [ACTIVE_REQUESTS].
 fn clear_request(env: &mut JNIEnv, source_obj: &JObject, request_id: u32) -> Result<(), JNIError> {
@@ -0,0 +1,85 @@
+use crate::jni::cache;
+use crate::upload::{FileBody, UploadProgressListener};
+use jni::errors::Error as JNIError;
+use jni::objects::{GlobalRef, JObject, JValueOwned};
+use jni::signature::{Primitive, ReturnType};
+use jni::{JNIEnv, JavaVM};
+use log::warn;
+use std::fmt::{Debug, Formatter};
+use std::path::PathBuf;
+use std::sync::Arc;
+
+/// Reads a JVM-side `dev/rushii/ktor_impersonate/NativeFileContent` into a [FileBody].
+pub fn get_file_body(env: &mut JNIEnv, content_obj: &JObject) -> Result<FileBody, JNIError> {
+	env.with_local_frame(4, |env| unsafe {
+		let path = env.call_method_unchecked(content_obj, cache::NativeFileContent_getPath(), ReturnType::Object, &[])?.l()?;
+		let path: String = env.get_string((&path).into())?.into();
+
+		let offset = env.call_method_unchecked(content_obj, cache::NativeFileContent_getOffset(), ReturnType::Primitive(Primitive::Long), &[])?.j()?;
+		let length = env.call_method_unchecked(content_obj, cache::NativeFileContent_getLengthValue(), ReturnType::Primitive(Primitive::Long), &[])?.j()?;
+
+		let listener = env.call_method_unchecked(content_obj, cache::NativeFileContent_getProgressListener(), ReturnType::Object, &[])?.l()?;
+		let progress = if listener.is_null() { None } else {
+			Some(Arc::new(JvmUploadProgressListener::new(env, &listener)?) as Arc<dyn UploadProgressListener>)
+		};
+
+		Ok(FileBody {
+			path: PathBuf::from(path),
+			offset: offset as u64,
+			length: (length >= 0).then_some(length as u64),
+			progress,
+		})
+	})
+}
+
+/// An [UploadProgressListener] that calls into a JVM-side `dev/rushii/ktor_impersonate/UploadProgressListener` instance.
+pub struct JvmUploadProgressListener {
+	vm: Arc<JavaVM>,
+	listener: GlobalRef,
+}
+
+impl JvmUploadProgressListener {
+	pub fn new(env: &mut JNIEnv, listener_obj: &JObject) -> Result<Self, JNIError> {
+		Ok(Self {
+			vm: Arc::new(env.get_java_vm()?),
+			listener: env.new_global_ref(listener_obj)?,
+		})
+	}
+}
+
+impl Debug for JvmUploadProgressListener {
+	fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+		f.debug_struct("JvmUploadProgressListener").finish_non_exhaustive()
+	}
+}
+
+impl UploadProgressListener for JvmUploadProgressListener {
+	fn on_progress(&self, bytes_sent: u64, total_bytes: u64) {
+		// We assume this thread is already attached to the VM based on the tokio runtime config
+		let mut env = match self.vm.get_env() {
+			Ok(env) => env,
+			Err(err) => return warn!("Failed to get JNIEnv to report upload progress: {err}"),
+		};
+
+		let bytes_sent_jni = JValueOwned::from(bytes_sent as i64).as_jni();
+		let total_bytes_jni = JValueOwned::from(total_bytes as i64).as_jni();
+
+		// SAFETY: Method ID is always valid and sig types are correct
+		let result = unsafe {
+			env.call_method_unchecked(
+				&self.listener,
+				cache::UploadProgressListener_onProgress(),
+				ReturnType::Primitive(Primitive::Void),
+				&[bytes_sent_jni, total_bytes_jni],
+			)
+		};
+
+		// Exceptions thrown by the listener cannot be propagated to the request
+		if let Err(JNIError::JavaException) = result {
+			let _ = env.exception_describe();
+			let _ = env.exception_clear();
+		} else if let Err(err) = result {
+			warn!("Failed to report upload progress to listener: {err}");
+		}
+	}
+}
@@ -25,8 +25,8 @@ pub fn boxed_jni_to_primitive<'local>(
 		"java.lang.Byte" |
 		"java.lang.Character" |
 		"java.lang.Double" |
-		"java.lang.Float" |
-		"java.lang.Integer" => unimplemented!("other primitives"),
+		"java.lang.Float" => unimplemented!("other primitives"),
+		"java.lang.Integer" => (cache::Integer_intValue(), Primitive::Int),
 		"java.lang.Long" => (cache::Long_longValue(), Primitive::Long),
 		"java.lang.Short" => unimplemented!("other primitives"),
 		_ => throw_argument!(env, "boxed_value is not a boxed primitive", Err(JNIError::JavaException)),
@@ -40,12 +40,18 @@ pub fn boxed_jni_to_primitive<'local>(
 pub unsafe fn get_string_list_values(env: &mut JNIEnv, list_obj: &JObject) -> Result<Vec<String>, JNIError> {
 	let array = env.call_method_unchecked(list_obj, cache::List_toArray(), ReturnType::Array, &[])?.l()?;
 	let array = env.auto_local(JObjectArray::from(array));
-	let array_length = env.get_array_length(&*array)?;
+	get_string_array_values(env, &*array)
+}
+
+/// Gets all the values contained within a String[]
+/// [array]: Must be an array with a component type of `java/lang/String`.
+pub unsafe fn get_string_array_values(env: &mut JNIEnv, array: &JObjectArray) -> Result<Vec<String>, JNIError> {
+	let array_length = env.get_array_length(array)?;
 
 	let mut vec: Vec<String> = Vec::with_capacity(array_length as usize);
 
 	for i in 0..array_length {
-		let item = env.get_object_array_element(&*array, i)?;
+		let item = env.get_object_array_element(array, i)?;
 		let item = env.auto_local(JString::from(item));
 
 		let string = env.get_string_unchecked(&*item)?;
@@ -53,3 +59,14 @@ pub unsafe fn get_string_list_values(env: &mut JNIEnv, list_obj: &JObject) -> Re
 	}
 	Ok(vec)
 }
+
+/// Creates a new String[] containing all the supplied values.
+pub fn new_string_array<'local>(env: &mut JNIEnv<'local>, values: &[String]) -> Result<JObjectArray<'local>, JNIError> {
+	let array = env.new_object_array(values.len() as i32, "java/lang/String", JObject::null())?;
+
+	for (i, value) in values.iter().enumerate() {
+		let item = env.auto_local(env.new_string(value)?);
+		env.set_object_array_element(&array, i as i32, &*item)?;
+	}
+	Ok(array)
+}
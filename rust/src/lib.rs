@@ -1,6 +1,20 @@
 mod root_certs;
 mod jni;
 mod requests;
+mod cache;
+mod client;
+mod config;
+mod dns;
+mod download;
+mod doh;
+mod fingerprint;
+mod har;
+mod limits;
+mod multipart;
+mod retry;
+mod timeout;
+mod unix;
+mod upload;
 
 use std::sync::RwLock;
 use tokio::runtime::Runtime;
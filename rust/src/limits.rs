@@ -0,0 +1,209 @@
+use dashmap::DashMap;
+use rquest::Url;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+use tokio::sync::{OwnedSemaphorePermit, Semaphore};
+
+/// A token bucket allowing up to [requests] to be sent in bursts, refilling at a rate of [requests] per [period].
+#[derive(Debug, Clone, Copy)]
+pub struct RateLimit {
+	pub requests: u32,
+	pub period: Duration,
+}
+
+/// Limits on how fast and how many requests are sent, both globally and per host.
+#[derive(Debug, Clone, Default)]
+pub struct LimitsConfig {
+	pub rate_limit: Option<RateLimit>,
+	pub rate_limit_per_host: Option<RateLimit>,
+	pub max_concurrent: Option<usize>,
+	pub max_concurrent_per_host: Option<usize>,
+}
+
+impl LimitsConfig {
+	pub fn is_empty(&self) -> bool {
+		self.rate_limit.is_none()
+			&& self.rate_limit_per_host.is_none()
+			&& self.max_concurrent.is_none()
+			&& self.max_concurrent_per_host.is_none()
+	}
+}
+
+/// Queues requests until they are allowed to be sent by a [LimitsConfig].
+pub struct Limiter {
+	config: LimitsConfig,
+	global: HostLimiter,
+	hosts: DashMap<String, Arc<HostLimiter>>,
+}
+
+/// Held by a request for as long as it counts towards the concurrency limits.
+pub struct LimitPermit {
+	_host: Option<OwnedSemaphorePermit>,
+	_global: Option<OwnedSemaphorePermit>,
+}
+
+struct HostLimiter {
+	semaphore: Option<Arc<Semaphore>>,
+	bucket: Option<TokenBucket>,
+}
+
+impl HostLimiter {
+	fn new(rate_limit: Option<RateLimit>, max_concurrent: Option<usize>) -> Self {
+		Self {
+			semaphore: max_concurrent.map(|max| Arc::new(Semaphore::new(max))),
+			bucket: rate_limit.map(TokenBucket::new),
+		}
+	}
+
+	async fn acquire_slot(&self) -> Option<OwnedSemaphorePermit> {
+		match &self.semaphore {
+			// The semaphores are never closed
+			Some(semaphore) => Some(semaphore.clone().acquire_owned().await.unwrap()),
+			None => None,
+		}
+	}
+
+	async fn acquire_token(&self) {
+		if let Some(bucket) = &self.bucket {
+			bucket.acquire().await;
+		}
+	}
+
+	/// Whether no requests hold or wait for this host's slots, and its rate limit has fully refilled,
+	/// in which case a new limiter for the host would behave the same.
+	fn is_idle(&self) -> bool {
+		// Permits hold a reference to the semaphore
+		self.semaphore.as_ref().is_none_or(|semaphore| Arc::strong_count(semaphore) == 1)
+			&& self.bucket.as_ref().is_none_or(TokenBucket::is_full)
+	}
+}
+
+/// Gets the `host:port` of a URL, which requests are limited per.
+pub fn authority(url: &Url) -> String {
+	format!(
+		"{}:{}",
+		url.host_str().unwrap_or_default(),
+		url.port_or_known_default().unwrap_or_default(),
+	)
+}
+
+impl Limiter {
+	pub fn new(config: LimitsConfig) -> Self {
+		Self {
+			global: HostLimiter::new(config.rate_limit, config.max_concurrent),
+			hosts: DashMap::new(),
+			config,
+		}
+	}
+
+	/// Waits until a request to a host is allowed to be sent.
+	/// [authority]: The `host:port` that the request is being sent to.
+	pub async fn acquire(&self, authority: &str) -> LimitPermit {
+		let host = self.host(authority);
+
+		// Host slots are acquired before global ones, to avoid holding global slots while waiting on a busy host
+		let host_permit = host.acquire_slot().await;
+		let global_permit = self.global.acquire_slot().await;
+
+		// Tokens are only taken once the request can actually be sent, so that it is paced by the rate limit
+		host.acquire_token().await;
+		self.global.acquire_token().await;
+
+		LimitPermit { _host: host_permit, _global: global_permit }
+	}
+
+	/// Waits until the rate limits allow sending another request to a host, ie. when retrying a request that already holds a [LimitPermit].
+	/// [authority]: The `host:port` that the request is being sent to.
+	pub async fn pace(&self, authority: &str) {
+		self.host(authority).acquire_token().await;
+		self.global.acquire_token().await;
+	}
+
+	/// Gets the limiter of a host, creating it if needed.
+	fn host(&self, authority: &str) -> Arc<HostLimiter> {
+		if let Some(host) = self.hosts.get(authority) {
+			return host.clone();
+		}
+
+		// Evicting idle hosts whenever a new one is added keeps the map from growing with every host ever requested
+		self.hosts.retain(|_, host| Arc::strong_count(host) > 1 || !host.is_idle());
+
+		self.hosts.entry(authority.to_owned())
+			.or_insert_with(|| Arc::new(HostLimiter::new(self.config.rate_limit_per_host, self.config.max_concurrent_per_host)))
+			.clone()
+	}
+}
+
+/// A token bucket where tokens are reserved in order, waiting until the reserved token has been refilled.
+struct TokenBucket {
+	capacity: f64,
+	/// Tokens refilled per second.
+	rate: f64,
+	/// The amount of available tokens, which is negative when there are tokens reserved ahead of time.
+	state: Mutex<(f64, Instant)>,
+}
+
+impl TokenBucket {
+	fn new(limit: RateLimit) -> Self {
+		let capacity = limit.requests as f64;
+		Self {
+			capacity,
+			rate: capacity / limit.period.as_secs_f64(),
+			state: Mutex::new((capacity, Instant::now())),
+		}
+	}
+
+	/// Takes a token, which is reserved ahead of time when none are available.
+	/// Returns how long to wait until the reserved token has been refilled, if any.
+	fn reserve(&self) -> Option<Duration> {
+		let mut state = self.state.lock().expect("token bucket lock poisoned");
+		let (tokens, last_refill) = &mut *state;
+
+		let now = Instant::now();
+		*tokens = (*tokens + now.duration_since(*last_refill).as_secs_f64() * self.rate).min(self.capacity);
+		*last_refill = now;
+		*tokens -= 1.0;
+
+		if *tokens >= 0.0 { None } else {
+			Some(Duration::from_secs_f64(-*tokens / self.rate))
+		}
+	}
+
+	/// Returns a reserved token that is no longer needed.
+	fn release(&self) {
+		let mut state = self.state.lock().expect("token bucket lock poisoned");
+		state.0 = (state.0 + 1.0).min(self.capacity);
+	}
+
+	fn is_full(&self) -> bool {
+		let (tokens, last_refill) = *self.state.lock().expect("token bucket lock poisoned");
+		tokens + last_refill.elapsed().as_secs_f64() * self.rate >= self.capacity
+	}
+
+	async fn acquire(&self) {
+		let Some(wait) = self.reserve() else { return };
+
+		// Requests can be cancelled while waiting, which returns their token so that requests reserving one later wait less.
+		// Requests that are already waiting keep the delay they were given when reserving their own token.
+		let mut reservation = Reservation { bucket: self, taken: false };
+		tokio::time::sleep(wait).await;
+		reservation.taken = true;
+	}
+}
+
+/// Returns a reserved token to its bucket when dropped before the token was taken.
+struct Reservation<'a> {
+	bucket: &'a TokenBucket,
+	taken: bool,
+}
+
+impl Drop for Reservation<'_> {
+	fn drop(&mut self) {
+		if !self.taken {
+			self.bucket.release();
+		}
+	}
+}
+
+#[cfg(test)]
+mod tests;
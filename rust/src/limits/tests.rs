@@ -0,0 +1,101 @@
+use super::*;
+use futures_util::FutureExt;
+use tokio::runtime::Runtime;
+
+fn bucket(requests: u32, period: Duration) -> TokenBucket {
+	TokenBucket::new(RateLimit { requests, period })
+}
+
+fn assert_near(actual: Option<Duration>, expected: Duration) {
+	let actual = actual.expect("expected a wait");
+	let difference = actual.abs_diff(expected);
+	assert!(difference < Duration::from_millis(50), "waited {actual:?} instead of {expected:?}");
+}
+
+#[test]
+fn bucket_allows_bursts_up_to_capacity() {
+	let bucket = bucket(3, Duration::from_secs(10));
+
+	assert_eq!(bucket.reserve(), None);
+	assert_eq!(bucket.reserve(), None);
+	assert_eq!(bucket.reserve(), None);
+	assert!(bucket.reserve().is_some());
+}
+
+#[test]
+fn bucket_reserves_tokens_in_order() {
+	let bucket = bucket(1, Duration::from_secs(10));
+
+	assert_eq!(bucket.reserve(), None);
+	assert_near(bucket.reserve(), Duration::from_secs(10));
+	assert_near(bucket.reserve(), Duration::from_secs(20));
+}
+
+#[test]
+fn bucket_refills_over_time() {
+	let bucket = bucket(2, Duration::from_millis(200));
+
+	assert_eq!(bucket.reserve(), None);
+	assert_eq!(bucket.reserve(), None);
+	assert!(!bucket.is_full());
+
+	std::thread::sleep(Duration::from_millis(250));
+	assert!(bucket.is_full());
+	assert_eq!(bucket.reserve(), None);
+}
+
+#[test]
+fn bucket_does_not_refill_past_capacity() {
+	let bucket = bucket(1, Duration::from_millis(10));
+
+	std::thread::sleep(Duration::from_millis(50));
+	assert_eq!(bucket.reserve(), None);
+	assert!(bucket.reserve().is_some());
+}
+
+#[test]
+fn cancelled_acquire_returns_its_token() {
+	let runtime = Runtime::new().unwrap();
+	let _guard = runtime.enter();
+	let bucket = bucket(1, Duration::from_secs(10));
+
+	assert_eq!(bucket.reserve(), None);
+
+	// Polling once reserves the next token, which is then cancelled by dropping the future
+	assert!(bucket.acquire().now_or_never().is_none());
+
+	assert_near(bucket.reserve(), Duration::from_secs(10));
+}
+
+#[test]
+fn evicts_idle_hosts() {
+	let runtime = Runtime::new().unwrap();
+	let limiter = Limiter::new(LimitsConfig {
+		rate_limit_per_host: Some(RateLimit { requests: 1, period: Duration::from_millis(50) }),
+		..Default::default()
+	});
+
+	drop(runtime.block_on(limiter.acquire("a:80")));
+	std::thread::sleep(Duration::from_millis(100));
+	drop(runtime.block_on(limiter.acquire("b:80")));
+
+	assert!(!limiter.hosts.contains_key("a:80"));
+	assert!(limiter.hosts.contains_key("b:80"));
+}
+
+#[test]
+fn keeps_hosts_with_active_requests() {
+	let runtime = Runtime::new().unwrap();
+	let limiter = Limiter::new(LimitsConfig {
+		max_concurrent_per_host: Some(1),
+		..Default::default()
+	});
+
+	let permit = runtime.block_on(limiter.acquire("a:80"));
+	drop(runtime.block_on(limiter.acquire("b:80")));
+	assert!(limiter.hosts.contains_key("a:80"));
+
+	drop(permit);
+	drop(runtime.block_on(limiter.acquire("c:80")));
+	assert!(!limiter.hosts.contains_key("a:80"));
+}
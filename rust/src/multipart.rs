@@ -0,0 +1,252 @@
+use crate::requests::RequestBodyChunk;
+use bytes::{Bytes, BytesMut};
+use futures_core::stream::BoxStream;
+use futures_util::StreamExt;
+use rand::Rng;
+use std::collections::VecDeque;
+use std::fmt::Write;
+use std::path::PathBuf;
+use tokio::fs::File;
+use tokio::io::AsyncReadExt;
+
+type BoxError = Box<dyn std::error::Error + Send + Sync>;
+
+/// The size of the chunks that file parts are read in.
+const FILE_CHUNK_SIZE: usize = 64 * 1024;
+
+/// The characters that WebKit and Blink pick boundaries from.
+/// This intentionally contains `A` and `B` twice, so that a random byte can be masked to pick one.
+const WEBKIT_BOUNDARY_CHARS: &[u8; 64] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789AB";
+
+/// How the client being impersonated formats `multipart/form-data` bodies.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Style {
+	/// Chrome, Edge, and Safari: `----WebKitFormBoundary` followed by 16 random characters,
+	/// with `application/octet-stream` as the default content type of files.
+	WebKit,
+	/// OkHttp: a random UUID, with a `Content-Length` for each part.
+	OkHttp,
+	/// rquest itself, when no preset is used: four random 64-bit hex numbers.
+	Rquest,
+}
+
+impl Style {
+	fn of(preset: Option<&str>) -> Self {
+		match preset {
+			Some(preset) if preset.starts_with("okhttp") => Style::OkHttp,
+			Some(_) => Style::WebKit,
+			None => Style::Rquest,
+		}
+	}
+
+	fn boundary(self) -> String {
+		let mut rng = rand::thread_rng();
+		match self {
+			Style::WebKit => {
+				let suffix: String = (0..16)
+					.map(|_| WEBKIT_BOUNDARY_CHARS[(rng.gen::<u8>() & 63) as usize] as char)
+					.collect();
+				format!("----WebKitFormBoundary{suffix}")
+			}
+			Style::OkHttp => {
+				let mut bytes: [u8; 16] = rng.gen();
+				bytes[6] = (bytes[6] & 0x0f) | 0x40; // Version 4
+				bytes[8] = (bytes[8] & 0x3f) | 0x80; // RFC 4122 variant
+				let hex: String = bytes.iter().map(|byte| format!("{byte:02x}")).collect();
+				format!("{}-{}-{}-{}-{}", &hex[0..8], &hex[8..12], &hex[12..16], &hex[16..20], &hex[20..32])
+			}
+			Style::Rquest => format!("{:016x}-{:016x}-{:016x}-{:016x}", rng.gen::<u64>(), rng.gen::<u64>(), rng.gen::<u64>(), rng.gen::<u64>()),
+		}
+	}
+}
+
+/// A part of a `multipart/form-data` body, mirroring the JVM-side `NativeFormPart`.
+#[derive(Debug)]
+pub struct Part {
+	pub name: String,
+	/// The file name in the `Content-Disposition` header, which is only sent for file parts.
+	pub file_name: Option<String>,
+	pub content_type: Option<String>,
+	pub source: PartSource,
+}
+
+#[derive(Debug)]
+pub enum PartSource {
+	/// A form field whose value is sent as-is.
+	Text(String),
+	/// A file that is read natively while the body is sent.
+	File(PathBuf),
+	/// Contents streamed from the JVM through the request body channel, which end with an empty chunk.
+	Jvm { length: Option<u64> },
+}
+
+/// A `multipart/form-data` body that is encoded natively in the style of the impersonated client.
+pub struct FormData {
+	boundary: String,
+	/// The encoded headers of each part alongside its contents.
+	parts: VecDeque<(Bytes, PartSource)>,
+	content_length: Option<u64>,
+}
+
+impl FormData {
+	/// Encodes the headers of all the parts, and determines the length of the body if possible.
+	/// This fails if the size of a file part cannot be read.
+	pub fn new(preset: Option<&str>, parts: Vec<Part>) -> Result<Self, BoxError> {
+		let style = Style::of(preset);
+		let boundary = style.boundary();
+		let mut content_length = Some(closing_delimiter(&boundary).len() as u64);
+		let mut encoded = VecDeque::with_capacity(parts.len());
+
+		for part in parts {
+			let length = match &part.source {
+				PartSource::Text(value) => Some(value.len() as u64),
+				PartSource::File(path) => Some(std::fs::metadata(path)
+					.map_err(|err| format!("Failed to read file {}: {err}", path.display()))?
+					.len()),
+				PartSource::Jvm { length } => *length,
+			};
+
+			let head = encode_head(style, &boundary, &part, length);
+			content_length = content_length.zip(length)
+				.map(|(total, length)| total + head.len() as u64 + length + 2);
+			encoded.push_back((Bytes::from(head), part.source));
+		}
+
+		Ok(Self { boundary, parts: encoded, content_length })
+	}
+
+	/// The value of the `Content-Type` header of this body.
+	pub fn content_type(&self) -> String {
+		format!("multipart/form-data; boundary={}", self.boundary)
+	}
+
+	/// The exact length of this body, if the size of every part is known.
+	pub fn content_length(&self) -> Option<u64> {
+		self.content_length
+	}
+
+	/// Whether any parts are streamed from the JVM, which requires a request body channel.
+	pub fn has_jvm_parts(&self) -> bool {
+		self.parts.iter().any(|(_, source)| matches!(source, PartSource::Jvm { .. }))
+	}
+
+	/// Converts this into a stream of the encoded body.
+	/// [jvm] is the stream of the request body channel, which must be passed when [has_jvm_parts] is true.
+	pub fn into_stream(self, jvm: Option<BoxStream<'static, RequestBodyChunk>>) -> BoxStream<'static, RequestBodyChunk> {
+		let encoder = Encoder { form: self, jvm, reading: None, done: false };
+
+		futures_util::stream::unfold(encoder, |mut encoder| async move {
+			encoder.next().await.map(|chunk| (chunk, encoder))
+		}).boxed()
+	}
+}
+
+/// The contents of the part that is currently being sent.
+enum Reading {
+	File(File),
+	Jvm,
+}
+
+/// Produces the chunks of a [FormData] body, reading the contents of each part as it is reached.
+struct Encoder {
+	form: FormData,
+	jvm: Option<BoxStream<'static, RequestBodyChunk>>,
+	reading: Option<Reading>,
+	done: bool,
+}
+
+impl Encoder {
+	async fn next(&mut self) -> Option<RequestBodyChunk> {
+		if self.done { return None; }
+
+		let result = match self.reading.as_mut() {
+			Some(Reading::File(file)) => {
+				let mut buf = BytesMut::with_capacity(FILE_CHUNK_SIZE);
+				match file.read_buf(&mut buf).await {
+					Ok(0) => Ok(self.end_part()),
+					Ok(_) => Ok(buf.freeze()),
+					Err(err) => Err(format!("Failed to read file part: {err}").into()),
+				}
+			}
+			Some(Reading::Jvm) => match self.jvm.as_mut().expect("BUG: missing request body channel").next().await {
+				Some(Ok(chunk)) if chunk.is_empty() => Ok(self.end_part()),
+				Some(result) => result,
+				None => Err("Request body ended before all of its parts were sent".into()),
+			},
+			None => self.next_part().await,
+		};
+
+		if result.is_err() {
+			self.done = true;
+		}
+		Some(result)
+	}
+
+	/// Finishes the contents of the current part, returning the line break that precedes the next delimiter.
+	fn end_part(&mut self) -> Bytes {
+		self.reading = None;
+		Bytes::from_static(b"\r\n")
+	}
+
+	/// Starts sending the next part, returning its headers, or otherwise the closing delimiter of the body.
+	async fn next_part(&mut self) -> RequestBodyChunk {
+		let Some((head, source)) = self.form.parts.pop_front() else {
+			self.done = true;
+			return Ok(Bytes::from(closing_delimiter(&self.form.boundary)));
+		};
+
+		match source {
+			PartSource::Text(value) => {
+				let mut chunk = BytesMut::from(&*head);
+				chunk.extend_from_slice(value.as_bytes());
+				chunk.extend_from_slice(b"\r\n");
+				return Ok(chunk.freeze());
+			}
+			PartSource::File(path) => {
+				let file = File::open(&path).await
+					.map_err(|err| format!("Failed to open file {}: {err}", path.display()))?;
+				self.reading = Some(Reading::File(file));
+			}
+			PartSource::Jvm { .. } => self.reading = Some(Reading::Jvm),
+		}
+
+		Ok(head)
+	}
+}
+
+/// Encodes the delimiter and headers that precede the contents of a part.
+fn encode_head(style: Style, boundary: &str, part: &Part, length: Option<u64>) -> String {
+	let mut head = format!("--{boundary}\r\nContent-Disposition: form-data; name=\"{}\"", escape(&part.name));
+	if let Some(file_name) = &part.file_name {
+		write!(head, "; filename=\"{}\"", escape(file_name)).unwrap();
+	}
+	head.push_str("\r\n");
+
+	let content_type = match (&part.content_type, &part.source) {
+		(Some(content_type), _) => Some(&**content_type),
+		(None, PartSource::Text(_)) => None,
+		(None, _) if style == Style::WebKit => Some("application/octet-stream"),
+		(None, _) => None,
+	};
+	if let Some(content_type) = content_type {
+		write!(head, "Content-Type: {content_type}\r\n").unwrap();
+	}
+
+	if let (Style::OkHttp, Some(length)) = (style, length) {
+		write!(head, "Content-Length: {length}\r\n").unwrap();
+	}
+
+	head.push_str("\r\n");
+	head
+}
+
+fn closing_delimiter(boundary: &str) -> String {
+	format!("--{boundary}--\r\n")
+}
+
+/// Escapes a name in a `Content-Disposition` header like browsers and OkHttp do.
+fn escape(value: &str) -> String {
+	value.replace('"', "%22")
+		.replace('\r', "%0D")
+		.replace('\n', "%0A")
+}
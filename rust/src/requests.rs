@@ -1,10 +1,19 @@
+use crate::limits::LimitPermit;
 use bytes::Bytes;
 use dashmap::DashMap;
 use futures_core::stream::BoxStream;
+use futures_util::StreamExt;
+use rquest::header::HeaderMap;
+use rquest::{StatusCode, Version};
 use std::sync::atomic::{AtomicU32, Ordering};
 use std::sync::{Arc, LazyLock, Mutex};
+use std::time::Duration;
+use tokio::sync::mpsc;
 use tokio::task::AbortHandle;
 
+/// A chunk of a streaming request body, or an error that occurred on the JVM side while producing it.
+pub type RequestBodyChunk = Result<Bytes, Box<dyn std::error::Error + Send + Sync>>;
+
 /// Used for sequentially increasing IDs.
 static NEXT_REQUEST_ID: AtomicU32 = AtomicU32::new(1);
 
@@ -28,10 +37,88 @@ pub enum RequestTask {
 		/// Once the response has been retrieved (excluding data), [abort] is set to [None] and [body] is populated.
 		abort: Option<AbortHandle>,
 
-		/// The response data stream that is populated once request has succeeded.
+		/// The response body that is populated once request has succeeded.
 		/// This is used to stream chunks of the body across multiple calls.
-		body: Option<Arc<Mutex<BoxStream<'static, Result<Bytes, rquest::Error>>>>>,
+		body: Option<Arc<Mutex<ResponseBody>>>,
+
+		/// The sending half of a streaming request body, used to pass chunks written by the JVM to the request.
+		/// Once the body has been fully written, this is set to [None] in order to end the body stream.
+		request_body: Option<mpsc::Sender<RequestBodyChunk>>,
+
+		/// Counts this request towards the client's concurrency limits until it is removed.
+		/// This is populated alongside [body], as it is held by the executing task until then.
+		permit: Option<LimitPermit>,
+	},
+
+	/// A request sent only to establish a connection, whose response is discarded natively.
+	Preconnect {
+		/// A handle to the spawned task sending the request, which is populated once it has been spawned.
+		abort: Option<AbortHandle>,
 	},
 
 	__NonExhaustive, // TODO: websockets
 }
+
+/// A response that is about to be passed to the JVM, either received from the network or produced by the cache.
+///
+/// Informational responses (ie. `103 Early Hints`) never reach this, as the hyper 0.14 fork used by both rquest 0.23
+/// and [crate::unix] discards every 1xx response other than `101 Switching Protocols` while reading the response head,
+/// without any hook to observe them.
+pub struct ResponseParts {
+	pub version: Version,
+	pub status: StatusCode,
+	pub headers: HeaderMap,
+	pub body: BoxStream<'static, Result<Bytes, rquest::Error>>,
+
+	/// Receives the trailers once the body has ended, or [None] if they cannot be received.
+	/// rquest 0.23 drops trailers of both chunked HTTP/1.1 and HTTP/2 responses, as its [rquest::Response] has no accessor
+	/// for them and its body decoder never polls them, so only responses over Unix domain sockets have this.
+	pub trailers: Option<Trailers>,
+}
+
+impl From<rquest::Response> for ResponseParts {
+	fn from(response: rquest::Response) -> Self {
+		Self {
+			version: response.version(),
+			status: response.status(),
+			headers: response.headers().clone(),
+			body: response.bytes_stream().boxed(),
+			trailers: None,
+		}
+	}
+}
+
+/// The trailers of a response, which are only available once its body has ended.
+#[derive(Clone, Default)]
+pub struct Trailers(Arc<Mutex<Option<HeaderMap>>>);
+
+impl Trailers {
+	pub fn set(&self, trailers: HeaderMap) {
+		*self.0.lock().expect("trailers lock poisoned") = Some(trailers);
+	}
+
+	pub fn get(&self) -> Option<HeaderMap> {
+		self.0.lock().expect("trailers lock poisoned").clone()
+	}
+}
+
+/// The body of a response that is being read by the JVM in parts.
+pub struct ResponseBody {
+	/// The remaining chunks of the body that have not been received yet.
+	pub stream: BoxStream<'static, Result<Bytes, rquest::Error>>,
+
+	/// The remainder of the last received chunk that did not fit into the JVM's buffer.
+	pub pending: Bytes,
+
+	/// The maximum time to wait for each chunk to be received.
+	pub read_timeout: Option<Duration>,
+
+	/// The trailers received after the body, if they can be received.
+	pub trailers: Option<Trailers>,
+}
+
+impl ResponseBody {
+	pub fn new(response: ResponseParts, read_timeout: Option<Duration>) -> Self {
+		Self { stream: response.body, pending: Bytes::new(), read_timeout, trailers: response.trailers }
+	}
+}
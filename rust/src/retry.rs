@@ -0,0 +1,77 @@
+use rand::Rng;
+use rquest::header::RETRY_AFTER;
+use rquest::{Method, Response};
+use std::time::{Duration, SystemTime};
+
+/// When and how often requests are retried natively.
+#[derive(Debug, Clone)]
+pub struct RetryPolicy {
+	/// The maximum amount of attempts made for a request, including the first one.
+	pub max_attempts: u32,
+	/// Retry requests that failed to connect, which is safe for any method since nothing was sent.
+	pub retry_on_connect_failure: bool,
+	/// Retry requests with idempotent methods that received a response with one of these status codes.
+	pub retry_status_codes: Vec<u16>,
+	/// The delay before the first retry, which is doubled for each following retry.
+	pub base_delay: Duration,
+	/// The maximum delay before a retry. A `Retry-After` longer than this stops retrying.
+	pub max_delay: Duration,
+	/// Whether to wait for as long as a response's `Retry-After` header specifies instead of backing off.
+	pub respect_retry_after: bool,
+}
+
+impl RetryPolicy {
+	/// Whether a request that failed with an error should be retried.
+	pub fn should_retry_error(&self, error: &rquest::Error) -> bool {
+		self.retry_on_connect_failure && error.is_connect()
+	}
+
+	/// Gets the delay before retrying a request that received a response,
+	/// or [None] if the response should be returned instead.
+	/// [retry]: The 1-based number of the retry that would be made.
+	pub fn response_retry_delay(&self, method: &Method, response: &Response, retry: u32) -> Option<Duration> {
+		if !is_idempotent(method) || !self.retry_status_codes.contains(&response.status().as_u16()) {
+			return None;
+		}
+
+		let retry_after = response.headers().get(RETRY_AFTER)
+			.and_then(|value| value.to_str().ok())
+			.and_then(parse_retry_after)
+			.filter(|_| self.respect_retry_after);
+
+		match retry_after {
+			Some(delay) if delay > self.max_delay => None,
+			Some(delay) => Some(delay),
+			None => Some(self.backoff(retry)),
+		}
+	}
+
+	/// Gets the delay before a retry using exponential backoff with full jitter.
+	/// [retry]: The 1-based number of the retry that would be made.
+	pub fn backoff(&self, retry: u32) -> Duration {
+		let exponent = retry.saturating_sub(1).min(31);
+		let ceiling = self.base_delay
+			.saturating_mul(1 << exponent)
+			.min(self.max_delay);
+
+		ceiling.mul_f64(rand::thread_rng().gen_range(0.0..=1.0))
+	}
+}
+
+/// Methods that can be safely sent multiple times (RFC 9110 §9.2.2).
+fn is_idempotent(method: &Method) -> bool {
+	matches!(*method, Method::GET | Method::HEAD | Method::OPTIONS | Method::PUT | Method::DELETE | Method::TRACE)
+}
+
+/// Parses a `Retry-After` header value, which is either a delay in seconds or an HTTP date.
+fn parse_retry_after(value: &str) -> Option<Duration> {
+	if let Ok(seconds) = value.trim().parse::<u64>() {
+		return Some(Duration::from_secs(seconds));
+	}
+
+	let date = httpdate::parse_http_date(value).ok()?;
+	Some(date.duration_since(SystemTime::now()).unwrap_or(Duration::ZERO))
+}
+
+#[cfg(test)]
+mod tests;
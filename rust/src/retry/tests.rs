@@ -0,0 +1,114 @@
+use super::*;
+
+fn policy() -> RetryPolicy {
+	RetryPolicy {
+		max_attempts: 3,
+		retry_on_connect_failure: true,
+		retry_status_codes: vec![429, 503],
+		base_delay: Duration::from_millis(100),
+		max_delay: Duration::from_secs(1),
+		respect_retry_after: true,
+	}
+}
+
+fn response(status: u16, retry_after: Option<&str>) -> Response {
+	let mut builder = hyper::Response::builder().status(status);
+	if let Some(value) = retry_after {
+		builder = builder.header(RETRY_AFTER, value);
+	}
+	Response::from(builder.body(String::new()).unwrap())
+}
+
+#[test]
+fn backoff_doubles_up_to_max_delay() {
+	let policy = policy();
+	let ceilings = [100, 200, 400, 800, 1000, 1000];
+
+	for (retry, ceiling) in (1..).zip(ceilings) {
+		for _ in 0..100 {
+			let delay = policy.backoff(retry);
+			assert!(delay <= Duration::from_millis(ceiling), "retry {retry} waited {delay:?}");
+		}
+	}
+}
+
+#[test]
+fn backoff_does_not_overflow() {
+	assert!(policy().backoff(u32::MAX) <= Duration::from_secs(1));
+}
+
+#[test]
+fn backoff_jitter_spans_the_whole_range() {
+	let policy = policy();
+	let delays: Vec<_> = (0..500).map(|_| policy.backoff(4)).collect();
+
+	// The chance of all 500 samples landing in one half of the range is negligible
+	assert!(delays.iter().any(|delay| *delay < Duration::from_millis(400)));
+	assert!(delays.iter().any(|delay| *delay > Duration::from_millis(400)));
+}
+
+#[test]
+fn parses_retry_after_seconds() {
+	assert_eq!(parse_retry_after("120"), Some(Duration::from_secs(120)));
+	assert_eq!(parse_retry_after(" 5 "), Some(Duration::from_secs(5)));
+	assert_eq!(parse_retry_after("0"), Some(Duration::ZERO));
+}
+
+#[test]
+fn parses_retry_after_http_date() {
+	let date = httpdate::fmt_http_date(SystemTime::now() + Duration::from_secs(60));
+	let delay = parse_retry_after(&date).unwrap();
+
+	// HTTP dates only have a precision of seconds
+	assert!(delay > Duration::from_secs(58) && delay <= Duration::from_secs(60), "{delay:?}");
+}
+
+#[test]
+fn parses_past_retry_after_date_as_zero() {
+	assert_eq!(parse_retry_after("Sun, 06 Nov 1994 08:49:37 GMT"), Some(Duration::ZERO));
+}
+
+#[test]
+fn rejects_invalid_retry_after() {
+	assert_eq!(parse_retry_after("soon"), None);
+	assert_eq!(parse_retry_after("-1"), None);
+}
+
+#[test]
+fn only_idempotent_methods_are_retried() {
+	for method in [Method::GET, Method::HEAD, Method::OPTIONS, Method::PUT, Method::DELETE, Method::TRACE] {
+		assert!(is_idempotent(&method), "{method}");
+	}
+	for method in [Method::POST, Method::PATCH, Method::CONNECT] {
+		assert!(!is_idempotent(&method), "{method}");
+	}
+
+	assert_eq!(policy().response_retry_delay(&Method::POST, &response(503, None), 1), None);
+}
+
+#[test]
+fn does_not_retry_unlisted_status() {
+	assert_eq!(policy().response_retry_delay(&Method::GET, &response(500, None), 1), None);
+}
+
+#[test]
+fn waits_for_retry_after() {
+	let delay = policy().response_retry_delay(&Method::GET, &response(429, Some("1")), 1);
+
+	assert_eq!(delay, Some(Duration::from_secs(1)));
+}
+
+#[test]
+fn stops_retrying_when_retry_after_exceeds_max_delay() {
+	let delay = policy().response_retry_delay(&Method::GET, &response(503, Some("2")), 1);
+
+	assert_eq!(delay, None);
+}
+
+#[test]
+fn backs_off_when_ignoring_retry_after() {
+	let policy = RetryPolicy { respect_retry_after: false, ..policy() };
+	let delay = policy.response_retry_delay(&Method::GET, &response(503, Some("2")), 1).unwrap();
+
+	assert!(delay <= Duration::from_millis(100), "{delay:?}");
+}
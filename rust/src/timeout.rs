@@ -0,0 +1,85 @@
+use crate::requests::RequestBodyChunk;
+use futures_core::stream::BoxStream;
+use futures_util::future::{select, Either};
+use futures_util::StreamExt;
+use rquest::header::{HeaderValue, CONTENT_LENGTH};
+use rquest::{Body, Request};
+use std::future::Future;
+use std::pin::pin;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use tokio::time::Instant;
+
+/// The size of the chunks that byte request bodies are split into, so that sending them counts as activity.
+const CHUNK_SIZE: usize = 64 * 1024;
+
+/// A socket timeout that only expires once no part of the request body has been sent for its duration, like Ktor's.
+///
+/// Chunks are counted once hyper takes them from the body, which is after the previous chunk has been written.
+/// Once the whole body has been sent, the timeout applies to waiting for the response headers.
+#[derive(Clone)]
+pub struct SocketTimeout {
+	pub duration: Duration,
+	last_activity: Arc<Mutex<Instant>>,
+}
+
+/// Returned by [SocketTimeout::run] when the timeout expired.
+#[derive(Debug)]
+pub struct Elapsed;
+
+impl SocketTimeout {
+	pub fn new(duration: Duration) -> Self {
+		Self { duration, last_activity: Arc::new(Mutex::new(Instant::now())) }
+	}
+
+	fn touch(&self) {
+		*self.last_activity.lock().unwrap() = Instant::now();
+	}
+
+	/// Counts each chunk taken from a streaming request body as activity.
+	pub fn track(&self, body: BoxStream<'static, RequestBodyChunk>) -> BoxStream<'static, RequestBodyChunk> {
+		let timeout = self.clone();
+		body.inspect(move |_| timeout.touch()).boxed()
+	}
+
+	/// Takes a byte body out of a request as a tracked stream of chunks,
+	/// since a single [Bytes](bytes::Bytes) would be written without any activity being seen.
+	pub fn take_bytes_body(&self, request: &mut Request) -> Option<BoxStream<'static, RequestBodyChunk>> {
+		let bytes = bytes::Bytes::copy_from_slice(request.body()?.as_bytes()?);
+		*request.body_mut() = None;
+
+		// Otherwise the stream would be sent with chunked encoding
+		let length = bytes.len();
+		request.headers_mut().entry(CONTENT_LENGTH).or_insert_with(|| HeaderValue::from(length));
+
+		let chunks = (0..length).step_by(CHUNK_SIZE)
+			.map(move |start| -> RequestBodyChunk { Ok(bytes.slice(start..length.min(start + CHUNK_SIZE))) });
+		Some(self.track(futures_util::stream::iter(chunks).boxed()))
+	}
+
+	/// Replaces a byte body of a request with a tracked stream of chunks.
+	pub fn track_request(&self, mut request: Request) -> Request {
+		if let Some(stream) = self.take_bytes_body(&mut request) {
+			*request.body_mut() = Some(Body::wrap_stream(stream));
+		}
+		request
+	}
+
+	/// Runs [future] until it completes, or until there has been no activity for the duration of the timeout.
+	pub async fn run<F: Future>(&self, future: F) -> Result<F::Output, Elapsed> {
+		self.touch();
+		let mut future = pin!(future);
+
+		loop {
+			let deadline = *self.last_activity.lock().unwrap() + self.duration;
+			match select(future.as_mut(), pin!(tokio::time::sleep_until(deadline))).await {
+				Either::Left((output, _)) => return Ok(output),
+				Either::Right(_) => {
+					if self.last_activity.lock().unwrap().elapsed() >= self.duration {
+						return Err(Elapsed);
+					}
+				}
+			}
+		}
+	}
+}
@@ -0,0 +1,131 @@
+use crate::requests::{RequestBodyChunk, ResponseParts, Trailers};
+use futures_core::stream::BoxStream;
+use futures_util::StreamExt;
+use hyper::body::HttpBody;
+use hyper::client::conn;
+use log::debug;
+use rquest::header::{HeaderValue, HOST};
+use rquest::{Body, Request, Response, Version};
+use std::path::Path;
+
+type BoxError = Box<dyn std::error::Error + Send + Sync>;
+
+/// Sends a request over a Unix domain socket, since rquest's connector only supports TCP.
+///
+/// This is meant for local daemons, so requests are sent as plain HTTP/1.1 over a new connection each,
+/// without the preset's default headers, TLS, or response decompression.
+/// On Linux and Android, a socket path starting with `@` refers to a name in the abstract namespace.
+///
+/// Streaming request bodies are passed separately as [body], since they cannot be taken back out of a [Request].
+/// Unlike with rquest, the trailers of chunked responses are received once the body has ended.
+pub async fn execute(
+	socket: &Path,
+	request: Request,
+	body: Option<BoxStream<'static, RequestBodyChunk>>,
+) -> Result<ResponseParts, BoxError> {
+	if request.url().scheme() != "http" {
+		return Err(format!("Unsupported scheme {:?} for a Unix domain socket, only http is supported", request.url().scheme()).into());
+	}
+
+	let body = match body {
+		Some(stream) => hyper::Body::wrap_stream(stream),
+		None => match request.body().and_then(Body::as_bytes) {
+			Some(bytes) => hyper::Body::from(bytes.to_vec()),
+			None => hyper::Body::empty(),
+		},
+	};
+
+	// Only the path is sent as the target, while the host of the url is still sent in the Host header
+	let url = request.url();
+	let target = match url.query() {
+		None => url.path().to_owned(),
+		Some(query) => format!("{}?{query}", url.path()),
+	};
+	let mut http_request = hyper::Request::builder()
+		.method(request.method().clone())
+		.uri(target)
+		.version(Version::HTTP_11)
+		.body(body)?;
+	*http_request.headers_mut() = request.headers().clone();
+	if !http_request.headers().contains_key(HOST) {
+		let host = match (url.host_str(), url.port()) {
+			(Some(host), Some(port)) => format!("{host}:{port}"),
+			(Some(host), None) => host.to_owned(),
+			(None, _) => "localhost".to_owned(),
+		};
+		http_request.headers_mut().insert(HOST, HeaderValue::from_str(&*host)?);
+	}
+
+	let send = async {
+		let stream = connect(socket).await
+			.map_err(|err| format!("Failed to connect to Unix domain socket {}: {err}", socket.display()))?;
+		let (mut sender, connection) = conn::handshake(stream).await?;
+
+		// The connection is driven separately until the response body has been fully read
+		tokio::spawn(async move {
+			if let Err(err) = connection.await {
+				debug!("Unix domain socket connection failed: {err}");
+			}
+		});
+
+		Ok::<_, BoxError>(sender.send_request(http_request).await?)
+	};
+	let response = match request.timeout() {
+		None => send.await?,
+		Some(timeout) => tokio::time::timeout(*timeout, send).await
+			.map_err(|_| format!("Request timeout of {}ms has expired", timeout.as_millis()))??,
+	};
+
+	let trailers = Trailers::default();
+	let (parts, body) = response.into_parts();
+	let body = with_trailers(body, trailers.clone());
+
+	let mut response = ResponseParts::from(Response::from(hyper::Response::from_parts(parts, Body::wrap_stream(body))));
+	response.trailers = Some(trailers);
+	Ok(response)
+}
+
+/// Streams the data of a body, and then stores its trailers once it has ended.
+fn with_trailers(body: hyper::Body, trailers: Trailers) -> BoxStream<'static, Result<bytes::Bytes, hyper::Error>> {
+	futures_util::stream::unfold(Some(body), move |body| {
+		let trailers = trailers.clone();
+		async move {
+			let mut body = body?;
+			match body.data().await {
+				Some(chunk) => Some((chunk, Some(body))),
+				None => match body.trailers().await {
+					Ok(Some(headers)) => {
+						trailers.set(headers);
+						None
+					}
+					Ok(None) => None,
+					Err(err) => Some((Err(err), None)),
+				},
+			}
+		}
+	}).boxed()
+}
+
+#[cfg(unix)]
+async fn connect(socket: &Path) -> std::io::Result<tokio::net::UnixStream> {
+	#[cfg(any(target_os = "linux", target_os = "android"))]
+	if let Some(name) = socket.to_str().and_then(|path| path.strip_prefix('@')) {
+		#[cfg(target_os = "android")]
+		use std::os::android::net::SocketAddrExt;
+		#[cfg(target_os = "linux")]
+		use std::os::linux::net::SocketAddrExt;
+
+		// Tokio cannot connect to abstract addresses, but connecting to a local socket does not block for long
+		let address = std::os::unix::net::SocketAddr::from_abstract_name(name)?;
+		let stream = std::os::unix::net::UnixStream::connect_addr(&address)?;
+		stream.set_nonblocking(true)?;
+		return tokio::net::UnixStream::from_std(stream);
+	}
+
+	tokio::net::UnixStream::connect(socket).await
+}
+
+#[cfg(not(unix))]
+async fn connect(_socket: &Path) -> std::io::Result<tokio::io::DuplexStream> {
+	Err(std::io::Error::new(std::io::ErrorKind::Unsupported, "Unix domain sockets are not supported on this platform"))
+}
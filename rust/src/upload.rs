@@ -0,0 +1,83 @@
+use crate::requests::RequestBodyChunk;
+use bytes::BytesMut;
+use futures_core::stream::BoxStream;
+use futures_util::StreamExt;
+use std::fmt::Debug;
+use std::io::{Seek, SeekFrom};
+use std::path::PathBuf;
+use std::sync::Arc;
+use tokio::fs::File;
+use tokio::io::AsyncReadExt;
+
+type BoxError = Box<dyn std::error::Error + Send + Sync>;
+
+/// The size of the chunks that files are read in.
+const FILE_CHUNK_SIZE: usize = 64 * 1024;
+
+/// Receives the progress of a [FileBody] being sent.
+pub trait UploadProgressListener: Debug + Send + Sync {
+	fn on_progress(&self, bytes_sent: u64, total_bytes: u64);
+}
+
+/// A request body that is read natively from (a range of) a file, mirroring the JVM-side `NativeFileContent`.
+#[derive(Debug)]
+pub struct FileBody {
+	pub path: PathBuf,
+	/// The position in the file to start sending from.
+	pub offset: u64,
+	/// The amount of bytes to send, otherwise the rest of the file after [offset] is sent.
+	pub length: Option<u64>,
+	pub progress: Option<Arc<dyn UploadProgressListener>>,
+}
+
+impl FileBody {
+	/// Opens the file and checks that the range fits in it, returning the exact length of the body alongside its stream.
+	/// The file is read as the stream is polled, which happens at the pace the body is sent.
+	pub fn open(self) -> Result<(u64, BoxStream<'static, RequestBodyChunk>), BoxError> {
+		let mut file = std::fs::File::open(&self.path)
+			.map_err(|err| format!("Failed to open file {}: {err}", self.path.display()))?;
+		let file_size = file.metadata()?.len();
+
+		if self.offset > file_size {
+			return Err(format!("Offset {} is past the end of file {} ({file_size} bytes)", self.offset, self.path.display()).into());
+		}
+		let length = match self.length {
+			None => file_size - self.offset,
+			Some(length) if self.offset.checked_add(length).is_some_and(|end| end <= file_size) => length,
+			Some(length) => return Err(format!(
+				"Range of {length} bytes at offset {} is past the end of file {} ({file_size} bytes)",
+				self.offset,
+				self.path.display(),
+			).into()),
+		};
+		file.seek(SeekFrom::Start(self.offset))?;
+
+		// The stream ends after an error, since the file is left at an unknown position
+		let reader = File::from_std(file).take(length);
+		let progress = self.progress;
+		let stream = futures_util::stream::unfold(Some((reader, 0u64)), move |state| {
+			let progress = progress.clone();
+			async move {
+				let (mut reader, sent) = state?;
+				let mut buf = BytesMut::with_capacity(FILE_CHUNK_SIZE);
+				match reader.read_buf(&mut buf).await {
+					Ok(0) if sent < length => {
+						let err = format!("File ended after {sent} of {length} bytes, it may have been modified while being sent");
+						Some((Err(err.into()), None))
+					}
+					Ok(0) => None,
+					Ok(count) => {
+						let sent = sent + count as u64;
+						if let Some(progress) = progress {
+							progress.on_progress(sent, length);
+						}
+						Some((Ok(buf.freeze()), Some((reader, sent))))
+					}
+					Err(err) => Some((Err(format!("Failed to read file: {err}").into()), None)),
+				}
+			}
+		});
+
+		Ok((length, stream.boxed()))
+	}
+}
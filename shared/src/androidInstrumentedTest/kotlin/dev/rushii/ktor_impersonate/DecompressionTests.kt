@@ -0,0 +1,65 @@
+package dev.rushii.ktor_impersonate
+
+import androidx.test.ext.junit.runners.AndroidJUnit4
+import io.ktor.client.HttpClient
+import io.ktor.client.request.get
+import io.ktor.client.statement.HttpResponse
+import io.ktor.client.statement.readRawBytes
+import io.ktor.http.HttpHeaders
+import kotlinx.coroutines.runBlocking
+import org.junit.Test
+import org.junit.runner.RunWith
+import java.io.ByteArrayOutputStream
+import java.util.zip.GZIPOutputStream
+import kotlin.test.assertContentEquals
+import kotlin.test.assertEquals
+import kotlin.test.assertNull
+
+@RunWith(AndroidJUnit4::class)
+class DecompressionTests {
+	private val gzipped = ByteArrayOutputStream().also { out ->
+		GZIPOutputStream(out).use { it.write("compressed".toByteArray()) }
+	}.toByteArray()
+
+	private fun gzipServer() = LocalServer {
+		LocalServer.Response(headers = mapOf("Content-Encoding" to "gzip"), body = gzipped)
+	}
+
+	private fun fetch(config: ImpersonateConfig.() -> Unit): Pair<HttpResponse, ByteArray> {
+		val server = gzipServer()
+		val client = HttpClient(Impersonate) { engine(config) }
+
+		val result = runBlocking {
+			val response = client.get("http://127.0.0.1:${server.port}/")
+			response to response.readRawBytes()
+		}
+
+		client.close()
+		server.close()
+		return result
+	}
+
+	@Test
+	fun decompressesByDefault() {
+		val (response, body) = fetch {}
+
+		assertEquals("compressed", body.decodeToString())
+		assertNull(response.headers[HttpHeaders.ContentEncoding])
+	}
+
+	@Test
+	fun keepsRawBodyWhenDecompressionDisabled() {
+		val (response, body) = fetch { decompression = false }
+
+		assertContentEquals(gzipped, body)
+		assertEquals("gzip", response.headers[HttpHeaders.ContentEncoding])
+	}
+
+	@Test
+	fun keepsRawBodyWhenAlgorithmDisabled() {
+		val (response, body) = fetch { gzip = false }
+
+		assertContentEquals(gzipped, body)
+		assertEquals("gzip", response.headers[HttpHeaders.ContentEncoding])
+	}
+}
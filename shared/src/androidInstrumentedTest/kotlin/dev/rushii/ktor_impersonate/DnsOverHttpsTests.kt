@@ -0,0 +1,103 @@
+package dev.rushii.ktor_impersonate
+
+import androidx.test.ext.junit.runners.AndroidJUnit4
+import io.ktor.client.HttpClient
+import io.ktor.client.request.get
+import io.ktor.client.statement.bodyAsText
+import kotlinx.coroutines.runBlocking
+import org.junit.Test
+import org.junit.runner.RunWith
+import java.nio.ByteBuffer
+import java.util.concurrent.atomic.AtomicInteger
+import kotlin.test.assertEquals
+import kotlin.test.assertFails
+
+/**
+ * Resolves made-up hosts through a local stand-in for a DNS-over-HTTPS resolver,
+ * which answers every A query with `127.0.0.1`.
+ */
+@RunWith(AndroidJUnit4::class)
+class DnsOverHttpsTests {
+	@Test
+	fun resolvesThroughDoh() {
+		val queries = AtomicInteger()
+		val target = LocalServer { LocalServer.Response(body = "ok".toByteArray()) }
+		val doh = LocalServer { queries.incrementAndGet(); LocalServer.Response(body = dnsAnswer(it.body)) }
+
+		val client = HttpClient(Impersonate) {
+			engine { dnsOverHttpsUrl = "http://127.0.0.1:${doh.port}/dns-query" }
+		}
+
+		runBlocking {
+			// The target closes every connection, so each request resolves the host again
+			repeat(3) {
+				assertEquals("ok", client.get("http://doh.test:${target.port}/").bodyAsText())
+			}
+		}
+
+		// One A and one AAAA query, after which the answer is cached
+		assertEquals(2, queries.get())
+
+		client.close()
+		target.close()
+		doh.close()
+	}
+
+	@Test
+	fun fallsBackToSystemResolver() {
+		val target = LocalServer { LocalServer.Response(body = "ok".toByteArray()) }
+		val doh = LocalServer { LocalServer.Response(status = 500) }
+
+		val strictClient = HttpClient(Impersonate) {
+			engine { dnsOverHttpsUrl = "http://127.0.0.1:${doh.port}/dns-query" }
+		}
+		val fallbackClient = HttpClient(Impersonate) {
+			engine {
+				dnsOverHttpsUrl = "http://127.0.0.1:${doh.port}/dns-query"
+				dnsOverHttpsFallback = true
+			}
+		}
+
+		runBlocking {
+			assertFails { strictClient.get("http://localhost:${target.port}/") }
+			assertEquals("ok", fallbackClient.get("http://localhost:${target.port}/").bodyAsText())
+		}
+
+		strictClient.close()
+		fallbackClient.close()
+		target.close()
+		doh.close()
+	}
+
+	private companion object {
+		/**
+		 * Builds a DNS response to a wire-format query, answering A queries with `127.0.0.1` and
+		 * everything else with no records.
+		 */
+		fun dnsAnswer(query: ByteArray): ByteArray {
+			// Skip the header and the question name to read its type
+			var questionEnd = 12
+			while (query[questionEnd].toInt() != 0) questionEnd += query[questionEnd] + 1
+			questionEnd += 5
+			val type = ((query[questionEnd - 4].toInt() and 0xFF) shl 8) or (query[questionEnd - 3].toInt() and 0xFF)
+			val isA = type == 1
+
+			return ByteBuffer.allocate(questionEnd + 16).apply {
+				putShort(0) // ID
+				putShort(0x8180.toShort()) // Response, recursion desired & available
+				putShort(1) // Questions
+				putShort(if (isA) 1 else 0) // Answers
+				putInt(0) // Authority & additional
+				put(query, 12, questionEnd - 12)
+				if (isA) {
+					putShort(0xC00C.toShort()) // Pointer to the question name
+					putShort(1) // A
+					putShort(1) // IN
+					putInt(60) // TTL
+					putShort(4)
+					put(byteArrayOf(127, 0, 0, 1))
+				}
+			}.let { it.array().copyOf(it.position()) }
+		}
+	}
+}
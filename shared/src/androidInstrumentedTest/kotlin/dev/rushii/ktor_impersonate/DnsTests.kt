@@ -0,0 +1,87 @@
+package dev.rushii.ktor_impersonate
+
+import androidx.test.ext.junit.runners.AndroidJUnit4
+import io.ktor.client.HttpClient
+import io.ktor.client.request.get
+import kotlinx.coroutines.runBlocking
+import org.junit.Test
+import org.junit.runner.RunWith
+import java.util.Collections
+import java.util.concurrent.atomic.AtomicReference
+import kotlin.test.assertEquals
+import kotlin.test.assertFails
+
+@RunWith(AndroidJUnit4::class)
+class DnsTests {
+	@Test
+	fun connectsToOverriddenAddress() {
+		val received = AtomicReference<LocalServer.Request>()
+		val server = LocalServer { received.set(it); LocalServer.Response() }
+		val client = HttpClient(Impersonate) {
+			engine { resolve("overridden.invalid", "127.0.0.1") }
+		}
+
+		runBlocking { client.get("http://overridden.invalid:${server.port}/") }
+
+		assertEquals("overridden.invalid:${server.port}", received.get().headers["host"])
+		assertEquals("127.0.0.1", received.get().remoteAddress)
+
+		client.close()
+		server.close()
+	}
+
+	@Test
+	fun resolvesThroughJvmResolver() {
+		val resolved = Collections.synchronizedList(mutableListOf<String>())
+		val server = LocalServer { LocalServer.Response() }
+		val client = HttpClient(Impersonate) {
+			engine {
+				dnsResolver = DnsResolver { host -> resolved += host; listOf("127.0.0.1") }
+			}
+		}
+
+		runBlocking { client.get("http://resolver.invalid:${server.port}/") }
+
+		assertEquals(listOf("resolver.invalid"), resolved.toList())
+
+		client.close()
+		server.close()
+	}
+
+	@Test
+	fun skipsAddressesOfExcludedFamily() {
+		val received = AtomicReference<LocalServer.Request>()
+		val server = LocalServer { received.set(it); LocalServer.Response() }
+		val client = HttpClient(Impersonate) {
+			engine {
+				ipPreference = IpPreference.Ipv4Only
+				resolve("dual.invalid", "::1", "127.0.0.1")
+			}
+		}
+
+		runBlocking { client.get("http://dual.invalid:${server.port}/") }
+
+		assertEquals("127.0.0.1", received.get().remoteAddress)
+
+		client.close()
+		server.close()
+	}
+
+	@Test
+	fun failsWithoutAddressOfPreferredFamily() {
+		val server = LocalServer { LocalServer.Response() }
+		val client = HttpClient(Impersonate) {
+			engine {
+				ipPreference = IpPreference.Ipv6Only
+				resolve("v4.invalid", "127.0.0.1")
+			}
+		}
+
+		assertFails {
+			runBlocking { client.get("http://v4.invalid:${server.port}/") }
+		}
+
+		client.close()
+		server.close()
+	}
+}
@@ -0,0 +1,135 @@
+package dev.rushii.ktor_impersonate
+
+import androidx.test.ext.junit.runners.AndroidJUnit4
+import io.ktor.client.HttpClient
+import io.ktor.http.HttpStatusCode
+import kotlinx.coroutines.runBlocking
+import org.junit.Test
+import org.junit.runner.RunWith
+import java.io.File
+import java.nio.file.Files
+import kotlin.test.assertEquals
+import kotlin.test.assertFalse
+import kotlin.test.assertNull
+
+@RunWith(AndroidJUnit4::class)
+class DownloadTests {
+	private val content = "0123456789".toByteArray()
+
+	private fun rangeServer() = LocalServer {
+		val range = it.headers["range"]
+		val start = range?.removePrefix("bytes=")?.removeSuffix("-")?.toInt()
+		if (start != null && it.headers["if-range"] == "\"v1\"") {
+			if (start >= content.size) {
+				LocalServer.Response(status = 416, headers = mapOf("Content-Range" to "bytes */${content.size}"))
+			} else {
+				LocalServer.Response(
+					status = 206,
+					headers = mapOf("ETag" to "\"v1\"", "Content-Range" to "bytes $start-${content.size - 1}/${content.size}"),
+					body = content.copyOfRange(start, content.size),
+				)
+			}
+		} else {
+			LocalServer.Response(headers = mapOf("ETag" to "\"v1\""), body = content)
+		}
+	}
+
+	@Test
+	fun downloadsToFile() {
+		val dir = Files.createTempDirectory("download").toFile()
+		val file = File(dir, "file.bin")
+		val server = rangeServer()
+		val client = HttpClient(Impersonate)
+
+		val result = runBlocking { client.downloadTo("http://127.0.0.1:${server.port}/", file.path) }
+
+		assertEquals(HttpStatusCode.OK, result.status)
+		assertEquals(10L, result.size)
+		assertEquals("0123456789", file.readText())
+		assertFalse(File(dir, "file.bin.part").exists())
+
+		client.close()
+		server.close()
+		dir.deleteRecursively()
+	}
+
+	@Test
+	fun resumesPartialDownload() {
+		val dir = Files.createTempDirectory("download").toFile()
+		val file = File(dir, "file.bin")
+		File(dir, "file.bin.part").writeText("0123")
+		File(dir, "file.bin.part.validator").writeText("\"v1\"")
+		val server = rangeServer()
+		val client = HttpClient(Impersonate)
+
+		val result = runBlocking { client.downloadTo("http://127.0.0.1:${server.port}/", file.path) }
+
+		assertEquals(HttpStatusCode.PartialContent, result.status)
+		assertEquals(10L, result.size)
+		assertEquals("0123456789", file.readText())
+
+		client.close()
+		server.close()
+		dir.deleteRecursively()
+	}
+
+	@Test
+	fun completesAlreadyDownloadedPartialFile() {
+		val dir = Files.createTempDirectory("download").toFile()
+		val file = File(dir, "file.bin")
+		File(dir, "file.bin.part").writeText("0123456789")
+		File(dir, "file.bin.part.validator").writeText("\"v1\"")
+		val server = rangeServer()
+		val client = HttpClient(Impersonate)
+
+		val result = runBlocking { client.downloadTo("http://127.0.0.1:${server.port}/", file.path) }
+
+		assertEquals(HttpStatusCode.PartialContent, result.status)
+		assertEquals(10L, result.size)
+		assertEquals("0123456789", file.readText())
+		assertFalse(File(dir, "file.bin.part").exists())
+		assertFalse(File(dir, "file.bin.part.validator").exists())
+
+		client.close()
+		server.close()
+		dir.deleteRecursively()
+	}
+
+	@Test
+	fun restartsWhenResourceChanged() {
+		val dir = Files.createTempDirectory("download").toFile()
+		val file = File(dir, "file.bin")
+		File(dir, "file.bin.part").writeText("abcd")
+		File(dir, "file.bin.part.validator").writeText("\"v0\"")
+		val server = rangeServer()
+		val client = HttpClient(Impersonate)
+
+		val result = runBlocking { client.downloadTo("http://127.0.0.1:${server.port}/", file.path) }
+
+		assertEquals(HttpStatusCode.OK, result.status)
+		assertEquals("0123456789", file.readText())
+
+		client.close()
+		server.close()
+		dir.deleteRecursively()
+	}
+
+	@Test
+	fun leavesFileUntouchedOnErrorStatus() {
+		val dir = Files.createTempDirectory("download").toFile()
+		val file = File(dir, "file.bin")
+		val server = LocalServer { LocalServer.Response(status = 404, body = "missing".toByteArray()) }
+		val client = HttpClient(Impersonate)
+
+		val result = runBlocking { client.downloadTo("http://127.0.0.1:${server.port}/", file.path) }
+
+		assertEquals(HttpStatusCode.NotFound, result.status)
+		assertEquals(0L, result.size)
+		assertFalse(file.exists())
+		assertNull(dir.listFiles()?.firstOrNull())
+
+		client.close()
+		server.close()
+		dir.deleteRecursively()
+	}
+}
@@ -6,6 +6,8 @@ import io.ktor.client.request.get
 import kotlinx.coroutines.runBlocking
 import org.junit.Test
 import org.junit.runner.RunWith
+import java.util.Collections
+import kotlin.test.assertEquals
 
 /**
  * These tests run on an Android device (or emulator).
@@ -48,4 +50,21 @@ class EngineTests {
 			client.get("https://example.com/")
 		}
 	}
+
+	@Test
+	fun preconnect() {
+		val methods = Collections.synchronizedList(mutableListOf<String>())
+		val server = LocalServer { methods += it.method; LocalServer.Response() }
+		val engine = ImpersonateEngine(ImpersonateConfig())
+
+		runBlocking {
+			engine.preconnect("http://127.0.0.1:${server.port}/", count = 2)
+		}
+
+		// Connections are established with HEAD requests
+		assertEquals(listOf("HEAD", "HEAD"), methods.toList())
+
+		engine.close()
+		server.close()
+	}
 }
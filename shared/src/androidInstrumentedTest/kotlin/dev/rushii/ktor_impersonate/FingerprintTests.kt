@@ -0,0 +1,54 @@
+package dev.rushii.ktor_impersonate
+
+import androidx.test.ext.junit.runners.AndroidJUnit4
+import io.ktor.client.HttpClient
+import io.ktor.client.request.get
+import io.ktor.client.statement.bodyAsText
+import kotlinx.coroutines.runBlocking
+import org.json.JSONObject
+import org.junit.Test
+import org.junit.runner.RunWith
+import kotlin.test.assertEquals
+import kotlin.test.assertNotNull
+import kotlin.test.assertTrue
+
+@RunWith(AndroidJUnit4::class)
+class FingerprintTests {
+	@Test
+	fun selfReport() {
+		val engine = ImpersonateEngine(ImpersonateConfig().apply { preset = ImpersonatePreset.Chrome100 })
+		val fingerprint = runBlocking { engine.fingerprint() }
+
+		assertTrue(fingerprint.ja4.startsWith("t13d"), fingerprint.ja4)
+		assertTrue(fingerprint.ja4.substring(8, 10) == "h2", fingerprint.ja4)
+		assertNotNull(fingerprint.akamai)
+		assertEquals(32, fingerprint.ja3Hash.length)
+
+		engine.close()
+	}
+
+	@OptIn(DangerousHTTPApi::class)
+	@Test
+	fun echoServerMatchesSelfReport() {
+		val config = ImpersonateConfig().apply {
+			preset = ImpersonatePreset.Chrome100
+			allowInvalidCertificates = true
+			resolve("echo.localhost", "127.0.0.1")
+		}
+		val engine = ImpersonateEngine(config)
+		val client = HttpClient(engine)
+		val server = FingerprintEchoServer()
+
+		runBlocking {
+			val body = JSONObject(client.get("https://echo.localhost:${server.port}/").bodyAsText())
+			val observed = server.observedFingerprints().single()
+
+			assertEquals(observed.ja4, body.getString("ja4"))
+			assertEquals(observed.akamai, body.getString("akamai"))
+			assertEquals(engine.fingerprint().ja4, observed.ja4)
+		}
+
+		client.close()
+		server.close()
+	}
+}
@@ -0,0 +1,66 @@
+package dev.rushii.ktor_impersonate
+
+import androidx.test.ext.junit.runners.AndroidJUnit4
+import io.ktor.client.HttpClient
+import io.ktor.client.request.get
+import io.ktor.client.request.header
+import io.ktor.client.statement.bodyAsText
+import kotlinx.coroutines.runBlocking
+import org.json.JSONObject
+import org.junit.Test
+import org.junit.runner.RunWith
+import java.io.File
+import java.util.concurrent.CopyOnWriteArrayList
+import kotlin.test.assertEquals
+
+@RunWith(AndroidJUnit4::class)
+class HarRecordingTests {
+	@Test
+	fun passesEntriesToListener() {
+		val entries = CopyOnWriteArrayList<String>()
+		val server = LocalServer { LocalServer.Response(headers = mapOf("X-Test" to "response"), body = "recorded".toByteArray()) }
+		val client = HttpClient(Impersonate) {
+			engine { harRecording = HarRecordingConfig(listener = entries::add, includeBodies = true) }
+		}
+
+		runBlocking {
+			client.get("http://127.0.0.1:${server.port}/path?query=value") { header("X-Test", "request") }.bodyAsText()
+		}
+
+		val entry = JSONObject(entries.single())
+		val request = entry.getJSONObject("request")
+		val response = entry.getJSONObject("response")
+		assertEquals("GET", request.getString("method"))
+		assertEquals("value", request.getJSONArray("queryString").getJSONObject(0).getString("value"))
+		assertEquals(true, request.getJSONArray("headers").toString().contains("\"request\""))
+		assertEquals(200, response.getInt("status"))
+		assertEquals("HTTP/1.1", response.getString("httpVersion"))
+		assertEquals("recorded", response.getJSONObject("content").getString("text"))
+		assertEquals("127.0.0.1", entry.getString("serverIPAddress"))
+
+		client.close()
+		server.close()
+	}
+
+	@Test
+	fun writesFile() {
+		val file = File.createTempFile("requests", ".har")
+		val server = LocalServer { LocalServer.Response(body = "ok".toByteArray()) }
+		val client = HttpClient(Impersonate) {
+			engine { harRecording = HarRecordingConfig(file = file.path) }
+		}
+
+		runBlocking {
+			repeat(2) { client.get("http://127.0.0.1:${server.port}/").bodyAsText() }
+		}
+
+		// Entries are written in the background until the client is closed
+		client.close()
+		server.close()
+
+		val log = JSONObject(file.readText()).getJSONObject("log")
+		assertEquals("1.2", log.getString("version"))
+		assertEquals(2, log.getJSONArray("entries").length())
+		file.delete()
+	}
+}
@@ -0,0 +1,125 @@
+package dev.rushii.ktor_impersonate
+
+import androidx.test.ext.junit.runners.AndroidJUnit4
+import io.ktor.client.HttpClient
+import io.ktor.client.request.get
+import io.ktor.client.request.header
+import kotlinx.coroutines.runBlocking
+import org.junit.Test
+import org.junit.runner.RunWith
+import java.util.concurrent.atomic.AtomicReference
+import kotlin.test.assertContains
+import kotlin.test.assertEquals
+import kotlin.test.assertFailsWith
+import kotlin.test.assertTrue
+
+@RunWith(AndroidJUnit4::class)
+class HeaderTests {
+	@Test
+	fun requestOrderOverridesClientOrder() {
+		val requests = mutableListOf<LocalServer.Request>()
+		val server = LocalServer { synchronized(requests) { requests += it }; LocalServer.Response() }
+		val client = HttpClient(Impersonate) {
+			engine { headerOrder = listOf("x-second", "x-first") }
+		}
+
+		runBlocking {
+			client.get("http://127.0.0.1:${server.port}/") {
+				header("x-first", "1")
+				header("x-third", "3")
+				header("x-second", "2")
+				header("x-fourth", "4")
+				headerOrder("x-fourth", "x-third")
+			}
+			client.get("http://127.0.0.1:${server.port}/") {
+				header("x-first", "1")
+				header("x-second", "2")
+			}
+		}
+
+		val names = requests.map { request -> request.rawHeaders.map { it.first.lowercase() }.filter { it.startsWith("x-") } }
+		assertEquals(listOf("x-fourth", "x-third", "x-first", "x-second"), names[0])
+		assertEquals(listOf("x-second", "x-first"), names[1])
+
+		client.close()
+		server.close()
+	}
+
+	@Test
+	fun requestOrderAppliesToPresetHeaders() {
+		val requests = mutableListOf<LocalServer.Request>()
+		val server = LocalServer { synchronized(requests) { requests += it }; LocalServer.Response() }
+		val client = HttpClient(Impersonate) {
+			engine { preset = ImpersonatePreset.Chrome129 }
+		}
+
+		runBlocking {
+			client.get("http://127.0.0.1:${server.port}/") {
+				header("x-custom", "1")
+				headerOrder("x-custom", "accept", "user-agent")
+			}
+		}
+
+		val names = requests.single().rawHeaders.map { it.first.lowercase() }
+		assertEquals(listOf("x-custom", "accept", "user-agent"), names.take(3))
+
+		client.close()
+		server.close()
+	}
+
+	@Test
+	fun sendsTitleCaseHeaderNames() {
+		val received = AtomicReference<LocalServer.Request>()
+		val server = LocalServer { received.set(it); LocalServer.Response() }
+		val client = HttpClient(Impersonate) {
+			engine { http1TitleCaseHeaders = true }
+		}
+
+		runBlocking {
+			client.get("http://127.0.0.1:${server.port}/") { header("x-custom-header", "1") }
+		}
+
+		val names = received.get().rawHeaders.map { it.first }
+		assertTrue("X-Custom-Header" in names, "Header names were not title-cased: $names")
+
+		client.close()
+		server.close()
+	}
+
+	@Test
+	fun roundTripsLatin1HeaderValues() {
+		val received = AtomicReference<LocalServer.Request>()
+		val server = LocalServer {
+			received.set(it)
+			LocalServer.Response(headers = mapOf("X-Echo" to it.headers.getValue("x-value")))
+		}
+		val client = HttpClient(Impersonate)
+
+		val response = runBlocking {
+			client.get("http://127.0.0.1:${server.port}/") { header("x-value", "caf\u00e9") }
+		}
+
+		// LocalServer decodes every byte as a single char, which is ISO-8859-1
+		assertEquals("caf\u00e9", received.get().headers["x-value"])
+		assertEquals("caf\u00e9", response.headers["X-Echo"])
+
+		client.close()
+		server.close()
+	}
+
+	@Test
+	fun namesInvalidHeaderInError() {
+		val server = LocalServer { LocalServer.Response() }
+		val client = HttpClient(Impersonate)
+
+		val error = assertFailsWith<IllegalArgumentException> {
+			runBlocking {
+				client.get("http://127.0.0.1:${server.port}/") { header("x-caf\u00e9", "1") }
+			}
+		}
+		assertContains(error.message.orEmpty(), "x-caf\u00e9")
+
+		client.close()
+		server.close()
+	}
+}
@@ -0,0 +1,49 @@
+package dev.rushii.ktor_impersonate
+
+import androidx.test.ext.junit.runners.AndroidJUnit4
+import io.ktor.client.HttpClient
+import io.ktor.client.request.get
+import kotlinx.coroutines.runBlocking
+import org.junit.Test
+import org.junit.runner.RunWith
+import java.util.Collections
+import kotlin.test.assertEquals
+
+// The whole 127.0.0.0/8 block is assigned to the loopback interface, so any of its addresses can be bound to
+@RunWith(AndroidJUnit4::class)
+class LocalAddressTests {
+	@Test
+	fun bindsClientLocalAddress() {
+		val remoteAddresses = Collections.synchronizedList(mutableListOf<String?>())
+		val server = LocalServer { remoteAddresses += it.remoteAddress; LocalServer.Response() }
+		val client = HttpClient(Impersonate) {
+			engine { localAddress = "127.0.0.2" }
+		}
+
+		runBlocking { client.get("http://127.0.0.1:${server.port}/") }
+
+		assertEquals(listOf<String?>("127.0.0.2"), remoteAddresses.toList())
+
+		client.close()
+		server.close()
+	}
+
+	@Test
+	fun requestLocalAddressOverridesClient() {
+		val remoteAddresses = Collections.synchronizedList(mutableListOf<String?>())
+		val server = LocalServer { remoteAddresses += it.remoteAddress; LocalServer.Response() }
+		val client = HttpClient(Impersonate) {
+			engine { localAddress = "127.0.0.2" }
+		}
+
+		runBlocking {
+			client.get("http://127.0.0.1:${server.port}/") { localAddress("127.0.0.3") }
+			client.get("http://127.0.0.1:${server.port}/")
+		}
+
+		assertEquals(listOf<String?>("127.0.0.3", "127.0.0.2"), remoteAddresses.toList())
+
+		client.close()
+		server.close()
+	}
+}
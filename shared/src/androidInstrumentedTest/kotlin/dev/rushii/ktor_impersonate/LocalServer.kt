@@ -0,0 +1,118 @@
+package dev.rushii.ktor_impersonate
+
+import android.net.LocalServerSocket
+import java.io.Closeable
+import java.io.DataInputStream
+import java.io.InputStream
+import java.io.OutputStream
+import java.net.ServerSocket
+import kotlin.concurrent.thread
+
+/**
+ * A minimal HTTP/1.1 server on localhost for tests, which handles a single request per connection.
+ * This listens on a TCP port, or on a Unix domain socket in the abstract namespace when [unixSocketName] is set.
+ */
+class LocalServer(
+	unixSocketName: String? = null,
+	private val handler: (Request) -> Response,
+) : Closeable {
+	/**
+	 * @param headers The headers with lowercase names, keeping the last value of repeated headers.
+	 * @param rawHeaders The header lines as received, in order and with their original case.
+	 * @param remoteAddress The IP address the request was sent from, or null over Unix domain sockets.
+	 */
+	class Request(
+		val method: String,
+		val path: String,
+		val headers: Map<String, String>,
+		val body: ByteArray,
+		val rawHeaders: List<Pair<String, String>>,
+		val remoteAddress: String?,
+	)
+
+	/** When [trailers] is set, the body is sent in a single chunk followed by the trailers. */
+	class Response(
+		val status: Int = 200,
+		val headers: Map<String, String> = emptyMap(),
+		val body: ByteArray = ByteArray(0),
+		val trailers: Map<String, String>? = null,
+	)
+
+	private val server = if (unixSocketName == null) ServerSocket(0) else null
+	private val unixServer = unixSocketName?.let(::LocalServerSocket)
+
+	val port: Int get() = server!!.localPort
+
+	init {
+		thread(isDaemon = true) {
+			while (true) {
+				if (server != null) {
+					val socket = runCatching { server.accept() }.getOrNull() ?: break
+					val remoteAddress = socket.inetAddress.hostAddress
+					thread(isDaemon = true) { socket.use { serve(it.getInputStream(), it.getOutputStream(), remoteAddress) } }
+				} else {
+					val socket = runCatching { unixServer!!.accept() }.getOrNull() ?: break
+					thread(isDaemon = true) { socket.use { serve(it.inputStream, it.outputStream, remoteAddress = null) } }
+				}
+			}
+		}
+	}
+
+	override fun close() {
+		server?.close()
+		unixServer?.close()
+	}
+
+	private fun serve(inputStream: InputStream, output: OutputStream, remoteAddress: String?) {
+		val input = DataInputStream(inputStream.buffered())
+		val (method, path) = input.readHttpLine().split(' ')
+
+		val rawHeaders = mutableListOf<Pair<String, String>>()
+		while (true) {
+			val line = input.readHttpLine()
+			if (line.isEmpty()) break
+			rawHeaders += line.substringBefore(':') to line.substringAfter(':').trim()
+		}
+		val headers = rawHeaders.associate { (name, value) -> name.lowercase() to value }
+
+		val body = ByteArray(headers["content-length"]?.toInt() ?: 0).also(input::readFully)
+		val response = handler(Request(method, path, headers, body, rawHeaders, remoteAddress))
+
+		output.write(buildString {
+			append("HTTP/1.1 ${response.status} X\r\n")
+			for ((name, value) in response.headers) append("$name: $value\r\n")
+			if (response.trailers == null) {
+				append("Content-Length: ${response.body.size}\r\n")
+			} else {
+				append("Transfer-Encoding: chunked\r\nTrailer: ${response.trailers.keys.joinToString()}\r\n")
+			}
+			append("Connection: close\r\n\r\n")
+		}.toByteArray(Charsets.ISO_8859_1))
+
+		if (response.trailers == null) {
+			output.write(response.body)
+		} else {
+			if (response.body.isNotEmpty()) {
+				output.write("${response.body.size.toString(16)}\r\n".toByteArray(Charsets.ISO_8859_1))
+				output.write(response.body)
+				output.write("\r\n".toByteArray(Charsets.ISO_8859_1))
+			}
+			output.write(buildString {
+				append("0\r\n")
+				for ((name, value) in response.trailers) append("$name: $value\r\n")
+				append("\r\n")
+			}.toByteArray(Charsets.ISO_8859_1))
+		}
+		output.flush()
+	}
+
+	private fun InputStream.readHttpLine(): String = buildString {
+		while (true) {
+			when (val char = read()) {
+				-1, '\n'.code -> break
+				'\r'.code -> {}
+				else -> append(char.toChar())
+			}
+		}
+	}
+}
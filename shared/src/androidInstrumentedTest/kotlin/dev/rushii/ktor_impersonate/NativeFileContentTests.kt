@@ -0,0 +1,57 @@
+package dev.rushii.ktor_impersonate
+
+import androidx.test.ext.junit.runners.AndroidJUnit4
+import io.ktor.client.HttpClient
+import io.ktor.client.request.post
+import io.ktor.client.request.setBody
+import io.ktor.client.statement.bodyAsText
+import kotlinx.coroutines.runBlocking
+import org.junit.Test
+import org.junit.runner.RunWith
+import java.io.File
+import java.util.concurrent.CopyOnWriteArrayList
+import kotlin.test.assertEquals
+import kotlin.test.assertFailsWith
+
+@RunWith(AndroidJUnit4::class)
+class NativeFileContentTests {
+	@Test
+	fun sendsRangeOfFile() {
+		val file = File.createTempFile("upload", ".bin").apply { writeText("0123456789") }
+		val progress = CopyOnWriteArrayList<Pair<Long, Long>>()
+		val server = LocalServer { LocalServer.Response(body = it.body) }
+		val client = HttpClient(Impersonate)
+
+		val body = runBlocking {
+			client.post("http://127.0.0.1:${server.port}/upload") {
+				setBody(NativeFileContent(file.path, offset = 2, length = 5) { sent, total -> progress += sent to total })
+			}.bodyAsText()
+		}
+
+		assertEquals("23456", body)
+		assertEquals(5L to 5L, progress.last())
+
+		client.close()
+		server.close()
+		file.delete()
+	}
+
+	@Test
+	fun rejectsRangePastEndOfFile() {
+		val file = File.createTempFile("upload", ".bin").apply { writeText("0123456789") }
+		val server = LocalServer { LocalServer.Response() }
+		val client = HttpClient(Impersonate)
+
+		assertFailsWith<IllegalArgumentException> {
+			runBlocking {
+				client.post("http://127.0.0.1:${server.port}/upload") {
+					setBody(NativeFileContent(file.path, offset = 8, length = 5))
+				}
+			}
+		}
+
+		client.close()
+		server.close()
+		file.delete()
+	}
+}
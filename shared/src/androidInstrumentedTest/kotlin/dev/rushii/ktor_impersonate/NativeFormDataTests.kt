@@ -0,0 +1,84 @@
+package dev.rushii.ktor_impersonate
+
+import androidx.test.ext.junit.runners.AndroidJUnit4
+import io.ktor.client.HttpClient
+import io.ktor.client.request.post
+import io.ktor.client.request.setBody
+import io.ktor.client.statement.bodyAsText
+import io.ktor.http.ContentType
+import io.ktor.utils.io.ByteReadChannel
+import kotlinx.coroutines.runBlocking
+import org.junit.Test
+import org.junit.runner.RunWith
+import java.io.File
+import kotlin.test.assertEquals
+import kotlin.test.assertTrue
+
+@RunWith(AndroidJUnit4::class)
+class NativeFormDataTests {
+	@Test
+	fun encodesLikeWebKit() {
+		val file = File.createTempFile("upload", ".txt").apply { writeText("file contents") }
+		var request: LocalServer.Request? = null
+		val server = LocalServer { request = it; LocalServer.Response() }
+		val client = HttpClient(Impersonate) {
+			engine { preset = ImpersonatePreset.Chrome129 }
+		}
+
+		runBlocking {
+			client.post("http://127.0.0.1:${server.port}/upload") {
+				setBody(nativeFormData {
+					append("field", "value")
+					appendFile("file", file.path, fileName = "upload.txt")
+					appendBytes("bytes", "in memory".toByteArray(), fileName = "bytes.bin", contentType = ContentType.Text.Plain)
+				})
+			}.bodyAsText()
+		}
+
+		val contentType = request!!.headers["content-type"]!!
+		val boundary = contentType.substringAfter("boundary=")
+		assertTrue(Regex("----WebKitFormBoundary[A-Za-z0-9]{16}").matches(boundary), boundary)
+		assertEquals(
+			"--$boundary\r\nContent-Disposition: form-data; name=\"field\"\r\n\r\nvalue\r\n" +
+				"--$boundary\r\nContent-Disposition: form-data; name=\"file\"; filename=\"upload.txt\"\r\n" +
+				"Content-Type: application/octet-stream\r\n\r\nfile contents\r\n" +
+				"--$boundary\r\nContent-Disposition: form-data; name=\"bytes\"; filename=\"bytes.bin\"\r\n" +
+				"Content-Type: text/plain\r\n\r\nin memory\r\n" +
+				"--$boundary--\r\n",
+			request!!.body.decodeToString(),
+		)
+
+		client.close()
+		server.close()
+		file.delete()
+	}
+
+	@Test
+	fun encodesLikeOkHttp() {
+		var request: LocalServer.Request? = null
+		val server = LocalServer { request = it; LocalServer.Response() }
+		val client = HttpClient(Impersonate) {
+			engine { preset = ImpersonatePreset.OkHttp5 }
+		}
+
+		runBlocking {
+			client.post("http://127.0.0.1:${server.port}/upload") {
+				setBody(nativeFormData {
+					appendChannel("stream", fileName = "stream.bin", length = 6) { ByteReadChannel("stream") }
+				})
+			}.bodyAsText()
+		}
+
+		val boundary = request!!.headers["content-type"]!!.substringAfter("boundary=")
+		assertTrue(Regex("[0-9a-f]{8}-[0-9a-f]{4}-4[0-9a-f]{3}-[89ab][0-9a-f]{3}-[0-9a-f]{12}").matches(boundary), boundary)
+		assertEquals(
+			"--$boundary\r\nContent-Disposition: form-data; name=\"stream\"; filename=\"stream.bin\"\r\n" +
+				"Content-Length: 6\r\n\r\nstream\r\n" +
+				"--$boundary--\r\n",
+			request!!.body.decodeToString(),
+		)
+
+		client.close()
+		server.close()
+	}
+}
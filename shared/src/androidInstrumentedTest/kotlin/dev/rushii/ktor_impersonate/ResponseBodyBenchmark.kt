@@ -0,0 +1,145 @@
+package dev.rushii.ktor_impersonate
+
+import android.util.Log
+import androidx.test.ext.junit.runners.AndroidJUnit4
+import io.ktor.client.HttpClient
+import io.ktor.client.request.prepareGet
+import io.ktor.client.statement.bodyAsChannel
+import io.ktor.utils.io.discard
+import kotlinx.coroutines.runBlocking
+import org.junit.AfterClass
+import org.junit.BeforeClass
+import org.junit.Test
+import org.junit.runner.RunWith
+import java.net.HttpURLConnection
+import java.net.ServerSocket
+import java.net.URL
+import kotlin.concurrent.thread
+import kotlin.random.Random
+import kotlin.test.assertEquals
+import kotlin.time.Duration
+import kotlin.time.measureTime
+
+/**
+ * Measures the throughput of reading response bodies through the engine from a local server.
+ * Results are written to logcat under the [TAG] tag. Run this on both sides of a change to the body path to compare them.
+ *
+ * The `baseline` cases read the same responses with [HttpURLConnection] instead, as a reference for what the device
+ * and the local server can do without the engine. The gap between the two is the overhead of the engine's body path.
+ */
+@RunWith(AndroidJUnit4::class)
+class ResponseBodyBenchmark {
+	@Test
+	fun download1MiB() = benchmark(sizeBytes = 1 shl 20, iterations = 50)
+
+	@Test
+	fun download16MiB() = benchmark(sizeBytes = 16 shl 20, iterations = 10)
+
+	@Test
+	fun download64MiB() = benchmark(sizeBytes = 64 shl 20, iterations = 3)
+
+	@Test
+	fun baselineDownload1MiB() = baseline(sizeBytes = 1 shl 20, iterations = 50)
+
+	@Test
+	fun baselineDownload16MiB() = baseline(sizeBytes = 16 shl 20, iterations = 10)
+
+	@Test
+	fun baselineDownload64MiB() = baseline(sizeBytes = 64 shl 20, iterations = 3)
+
+	private fun benchmark(sizeBytes: Int, iterations: Int) {
+		val client = HttpClient(Impersonate)
+		val url = "http://127.0.0.1:${server.localPort}/$sizeBytes"
+
+		runBlocking {
+			// Warm up the connection pool
+			client.prepareGet(url).execute { it.bodyAsChannel().discard() }
+
+			var totalBytes = 0L
+			val duration = measureTime {
+				repeat(iterations) {
+					totalBytes += client.prepareGet(url).execute { it.bodyAsChannel().discard() }
+				}
+			}
+
+			assertEquals(sizeBytes.toLong() * iterations, totalBytes)
+			log("engine", sizeBytes, iterations, totalBytes, duration)
+		}
+
+		client.close()
+	}
+
+	private fun baseline(sizeBytes: Int, iterations: Int) {
+		val url = URL("http://127.0.0.1:${server.localPort}/$sizeBytes")
+		val buffer = ByteArray(8192)
+		fun download(): Long = (url.openConnection() as HttpURLConnection).inputStream.use { input ->
+			var count = 0L
+			while (true) {
+				val read = input.read(buffer)
+				if (read < 0) break
+				count += read
+			}
+			count
+		}
+
+		// Warm up the connection pool
+		download()
+
+		var totalBytes = 0L
+		val duration = measureTime {
+			repeat(iterations) { totalBytes += download() }
+		}
+
+		assertEquals(sizeBytes.toLong() * iterations, totalBytes)
+		log("baseline", sizeBytes, iterations, totalBytes, duration)
+	}
+
+	private fun log(name: String, sizeBytes: Int, iterations: Int, totalBytes: Long, duration: Duration) {
+		val mibPerSecond = (totalBytes / (1 shl 20).toDouble()) / (duration.inWholeMicroseconds / 1_000_000.0)
+		Log.i(TAG, "$name: ${sizeBytes shr 20} MiB x $iterations: $duration total, ${"%.1f".format(mibPerSecond)} MiB/s")
+	}
+
+	companion object {
+		private const val TAG = "ResponseBodyBenchmark"
+		private val payload = Random(0).nextBytes(64 shl 20)
+		private lateinit var server: ServerSocket
+
+		/**
+		 * Starts a minimal HTTP/1.1 server on localhost that responds to `GET /<size>` with `size` bytes.
+		 */
+		@BeforeClass
+		@JvmStatic
+		fun startServer() {
+			server = ServerSocket(0)
+
+			thread(isDaemon = true) {
+				while (!server.isClosed) {
+					val socket = runCatching { server.accept() }.getOrNull() ?: break
+
+					thread(isDaemon = true) {
+						socket.use {
+							val input = socket.getInputStream().bufferedReader()
+							val output = socket.getOutputStream()
+
+							while (true) {
+								val requestLine = input.readLine() ?: break
+								while (input.readLine()?.isNotEmpty() == true) Unit // Skip headers
+
+								val size = requestLine.split(' ')[1].removePrefix("/").toInt()
+								output.write("HTTP/1.1 200 OK\r\nContent-Length: $size\r\nContent-Type: application/octet-stream\r\n\r\n".toByteArray())
+								output.write(payload, 0, size)
+								output.flush()
+							}
+						}
+					}
+				}
+			}
+		}
+
+		@AfterClass
+		@JvmStatic
+		fun stopServer() {
+			server.close()
+		}
+	}
+}
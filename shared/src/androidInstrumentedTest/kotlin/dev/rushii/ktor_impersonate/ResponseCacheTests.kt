@@ -0,0 +1,104 @@
+package dev.rushii.ktor_impersonate
+
+import androidx.test.ext.junit.runners.AndroidJUnit4
+import io.ktor.client.HttpClient
+import io.ktor.client.request.get
+import io.ktor.client.request.header
+import io.ktor.client.statement.bodyAsText
+import io.ktor.http.HttpHeaders
+import kotlinx.coroutines.runBlocking
+import org.junit.Test
+import org.junit.runner.RunWith
+import java.util.concurrent.atomic.AtomicInteger
+import kotlin.test.assertEquals
+
+@RunWith(AndroidJUnit4::class)
+class ResponseCacheTests {
+	@Test
+	fun servesFreshResponses() {
+		val requests = AtomicInteger()
+		val server = LocalServer {
+			requests.incrementAndGet()
+			LocalServer.Response(headers = mapOf("Cache-Control" to "max-age=60"), body = "fresh".toByteArray())
+		}
+		val client = HttpClient(Impersonate) {
+			engine { responseCache = ResponseCacheConfig() }
+		}
+
+		runBlocking {
+			val first = client.get("http://localhost:${server.port}/")
+			assertEquals("fresh", first.bodyAsText())
+			assertEquals(CacheStatus.Miss, first.cacheStatus)
+
+			val second = client.get("http://localhost:${server.port}/")
+			assertEquals("fresh", second.bodyAsText())
+			assertEquals(CacheStatus.Hit, second.cacheStatus)
+			assertEquals(0, second.attempts)
+		}
+
+		assertEquals(1, requests.get())
+
+		client.close()
+		server.close()
+	}
+
+	@Test
+	fun revalidatesStaleResponses() {
+		val server = LocalServer {
+			if (it.headers["if-none-match"] == "\"v1\"") {
+				LocalServer.Response(status = 304, headers = mapOf("ETag" to "\"v1\""))
+			} else {
+				LocalServer.Response(headers = mapOf("Cache-Control" to "no-cache", "ETag" to "\"v1\""), body = "stored".toByteArray())
+			}
+		}
+		val client = HttpClient(Impersonate) {
+			engine { responseCache = ResponseCacheConfig() }
+		}
+
+		runBlocking {
+			assertEquals(CacheStatus.Miss, client.get("http://localhost:${server.port}/").cacheStatus)
+
+			val second = client.get("http://localhost:${server.port}/")
+			assertEquals("stored", second.bodyAsText())
+			assertEquals(CacheStatus.Revalidated, second.cacheStatus)
+		}
+
+		client.close()
+		server.close()
+	}
+
+	@Test
+	fun storesVariantsPerVaryHeader() {
+		val requests = AtomicInteger()
+		val server = LocalServer {
+			requests.incrementAndGet()
+			val headers = mapOf("Cache-Control" to "max-age=60", "Vary" to "Accept-Language")
+			LocalServer.Response(headers = headers, body = it.headers["accept-language"].orEmpty().toByteArray())
+		}
+		val client = HttpClient(Impersonate) {
+			engine { responseCache = ResponseCacheConfig() }
+		}
+
+		runBlocking {
+			suspend fun fetch(language: String) = client.get("http://localhost:${server.port}/") {
+				header(HttpHeaders.AcceptLanguage, language)
+			}
+
+			assertEquals(CacheStatus.Miss, fetch("en").cacheStatus)
+			assertEquals(CacheStatus.Miss, fetch("fr").cacheStatus)
+
+			val english = fetch("en")
+			assertEquals("en", english.bodyAsText())
+			assertEquals(CacheStatus.Hit, english.cacheStatus)
+
+			val french = fetch("fr")
+			assertEquals("fr", french.bodyAsText())
+			assertEquals(CacheStatus.Hit, french.cacheStatus)
+		}
+
+		assertEquals(2, requests.get())
+
+		client.close()
+		server.close()
+	}
+}
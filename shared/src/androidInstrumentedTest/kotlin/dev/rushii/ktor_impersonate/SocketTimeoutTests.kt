@@ -0,0 +1,67 @@
+package dev.rushii.ktor_impersonate
+
+import androidx.test.ext.junit.runners.AndroidJUnit4
+import io.ktor.client.HttpClient
+import io.ktor.client.network.sockets.SocketTimeoutException
+import io.ktor.client.plugins.HttpTimeout
+import io.ktor.client.request.get
+import io.ktor.client.request.post
+import io.ktor.client.request.setBody
+import io.ktor.client.statement.bodyAsText
+import io.ktor.http.content.OutgoingContent
+import io.ktor.utils.io.ByteWriteChannel
+import io.ktor.utils.io.writeFully
+import kotlinx.coroutines.delay
+import kotlinx.coroutines.runBlocking
+import org.junit.Test
+import org.junit.runner.RunWith
+import kotlin.test.assertEquals
+import kotlin.test.assertFailsWith
+
+@RunWith(AndroidJUnit4::class)
+class SocketTimeoutTests {
+	private fun client() = HttpClient(Impersonate) {
+		install(HttpTimeout) { socketTimeoutMillis = 500 }
+	}
+
+	@Test
+	fun slowUploadLongerThanTimeoutSucceeds() {
+		val server = LocalServer { LocalServer.Response(body = it.body.size.toString().toByteArray()) }
+		val client = client()
+
+		// Takes about 2s in total, while each chunk is sent well within the socket timeout
+		val body = runBlocking {
+			client.post("http://127.0.0.1:${server.port}/") {
+				setBody(object : OutgoingContent.WriteChannelContent() {
+					override val contentLength: Long = 10L * 1024
+
+					override suspend fun writeTo(channel: ByteWriteChannel) {
+						repeat(10) {
+							channel.writeFully(ByteArray(1024))
+							channel.flush()
+							delay(200)
+						}
+					}
+				})
+			}.bodyAsText()
+		}
+
+		assertEquals("10240", body)
+
+		client.close()
+		server.close()
+	}
+
+	@Test
+	fun unresponsiveServerTimesOut() {
+		val server = LocalServer { Thread.sleep(2000); LocalServer.Response() }
+		val client = client()
+
+		assertFailsWith<SocketTimeoutException> {
+			runBlocking { client.get("http://127.0.0.1:${server.port}/") }
+		}
+
+		client.close()
+		server.close()
+	}
+}
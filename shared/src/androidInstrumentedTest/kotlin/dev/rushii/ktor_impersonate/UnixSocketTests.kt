@@ -0,0 +1,69 @@
+package dev.rushii.ktor_impersonate
+
+import androidx.test.ext.junit.runners.AndroidJUnit4
+import io.ktor.client.HttpClient
+import io.ktor.client.request.get
+import io.ktor.client.request.post
+import io.ktor.client.request.setBody
+import io.ktor.client.statement.bodyAsText
+import kotlinx.coroutines.runBlocking
+import org.junit.Test
+import org.junit.runner.RunWith
+import kotlin.test.assertEquals
+
+@RunWith(AndroidJUnit4::class)
+class UnixSocketTests {
+	@Test
+	fun sendsRequestsOverSocket() {
+		val server = LocalServer(unixSocketName = "ktor-impersonate-test-client") { request ->
+			LocalServer.Response(body = "${request.method} ${request.path} ${request.headers["host"]}".toByteArray())
+		}
+		val client = HttpClient(Impersonate) {
+			engine { unixSocket = "@ktor-impersonate-test-client" }
+		}
+
+		val body = runBlocking { client.get("http://daemon/status?verbose=1").bodyAsText() }
+		assertEquals("GET /status?verbose=1 daemon", body)
+
+		client.close()
+		server.close()
+	}
+
+	@Test
+	fun overridesSocketPerRequest() {
+		val server = LocalServer(unixSocketName = "ktor-impersonate-test-request") { request ->
+			LocalServer.Response(body = request.body)
+		}
+		val client = HttpClient(Impersonate)
+
+		val body = runBlocking {
+			client.post("http://localhost/echo") {
+				unixSocket("@ktor-impersonate-test-request")
+				setBody("over a socket")
+			}.bodyAsText()
+		}
+		assertEquals("over a socket", body)
+
+		client.close()
+		server.close()
+	}
+
+	@Test
+	fun receivesTrailers() {
+		val server = LocalServer(unixSocketName = "ktor-impersonate-test-trailers") {
+			LocalServer.Response(body = "body".toByteArray(), trailers = mapOf("X-Checksum" to "abc"))
+		}
+		val client = HttpClient(Impersonate) {
+			engine { unixSocket = "@ktor-impersonate-test-trailers" }
+		}
+
+		runBlocking {
+			val response = client.get("http://localhost/")
+			assertEquals("body", response.bodyAsText())
+			assertEquals("abc", response.trailers?.get("X-Checksum"))
+		}
+
+		client.close()
+		server.close()
+	}
+}
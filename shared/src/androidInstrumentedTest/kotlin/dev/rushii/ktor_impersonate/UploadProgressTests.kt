@@ -0,0 +1,85 @@
+package dev.rushii.ktor_impersonate
+
+import androidx.test.ext.junit.runners.AndroidJUnit4
+import io.ktor.client.HttpClient
+import io.ktor.client.plugins.onUpload
+import io.ktor.client.request.post
+import io.ktor.client.request.setBody
+import io.ktor.http.content.OutgoingContent
+import io.ktor.utils.io.ByteReadChannel
+import kotlinx.coroutines.runBlocking
+import org.junit.Test
+import org.junit.runner.RunWith
+import java.net.InetSocketAddress
+import java.net.ServerSocket
+import java.util.concurrent.atomic.AtomicLong
+import kotlin.concurrent.thread
+import kotlin.test.assertEquals
+import kotlin.test.assertTrue
+
+@RunWith(AndroidJUnit4::class)
+class UploadProgressTests {
+	@Test
+	fun progressTracksBytesReceivedByServer() {
+		val total = 16L * 1024 * 1024
+		// Socket buffers on both ends and the native body buffer can hold data that has been reported but not received yet
+		val slack = 4L * 1024 * 1024
+
+		val received = AtomicLong()
+		val server = ServerSocket().apply {
+			receiveBufferSize = 64 * 1024
+			bind(InetSocketAddress("127.0.0.1", 0))
+		}
+		thread(isDaemon = true) {
+			server.accept().use { socket ->
+				val input = socket.getInputStream()
+
+				// Skip the request head
+				var lineEnds = 0
+				while (lineEnds < 4) {
+					when (input.read()) {
+						-1 -> return@thread
+						'\r'.code, '\n'.code -> lineEnds++
+						else -> lineEnds = 0
+					}
+				}
+
+				// Read the body slowly so that the upload is limited by the server
+				val buffer = ByteArray(64 * 1024)
+				while (received.get() < total) {
+					val count = input.read(buffer)
+					if (count < 0) break
+					received.addAndGet(count.toLong())
+					Thread.sleep(5)
+				}
+
+				socket.getOutputStream().write("HTTP/1.1 200 X\r\nContent-Length: 0\r\nConnection: close\r\n\r\n".toByteArray())
+				socket.getOutputStream().flush()
+			}
+		}
+
+		val maxAhead = AtomicLong()
+		val lastSent = AtomicLong()
+		val client = HttpClient(Impersonate)
+
+		runBlocking {
+			client.post("http://127.0.0.1:${server.localPort}/") {
+				setBody(object : OutgoingContent.ReadChannelContent() {
+					override val contentLength: Long = total
+					override fun readFrom(): ByteReadChannel = ByteReadChannel(ByteArray(total.toInt()))
+				})
+				onUpload { sent, _ ->
+					lastSent.set(sent)
+					maxAhead.accumulateAndGet(sent - received.get(), ::maxOf)
+				}
+			}
+		}
+
+		assertEquals(total, lastSent.get())
+		assertEquals(total, received.get())
+		assertTrue(maxAhead.get() <= slack, "Progress was ${maxAhead.get()} bytes ahead of the server")
+
+		client.close()
+		server.close()
+	}
+}
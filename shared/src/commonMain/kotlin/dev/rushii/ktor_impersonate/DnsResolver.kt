@@ -0,0 +1,34 @@
+package dev.rushii.ktor_impersonate
+
+/**
+ * Resolves host names into IP addresses, used in place of the system resolver.
+ * Set with [ImpersonateConfig.dnsResolver].
+ */
+public fun interface DnsResolver {
+	/**
+	 * Resolves a host name into a list of IP address literals (ie. `93.184.215.14` or `2606:2800:21f:cb07:6820:80da:af6b:8b2c`).
+	 * This is called on a native background thread, and is allowed to block.
+	 * Throwing an exception will fail the request that is being connected.
+	 */
+	public fun resolve(host: String): List<String>
+}
+
+/**
+ * Which IP address families are used when connecting to a host.
+ */
+public enum class IpPreference {
+	/**
+	 * Connect with both IPv6 and IPv4 addresses, racing connection attempts between them (RFC 8305).
+	 */
+	HappyEyeballs,
+
+	/**
+	 * Only connect with IPv4 addresses.
+	 */
+	Ipv4Only,
+
+	/**
+	 * Only connect with IPv6 addresses.
+	 */
+	Ipv6Only,
+}
@@ -0,0 +1,42 @@
+package dev.rushii.ktor_impersonate
+
+import io.ktor.client.HttpClient
+import io.ktor.http.Headers
+import io.ktor.http.HttpProtocolVersion
+import io.ktor.http.HttpStatusCode
+
+/**
+ * The response of a download made with [ImpersonateEngine.downloadTo], whose body has been written to a file.
+ */
+public class DownloadResult(
+	/**
+	 * The status of the response. When resuming, this is `206 Partial Content`, including when the partial file
+	 * was already complete and the server responded with `416 Range Not Satisfiable`.
+	 * If this is not successful, the response body was discarded and the file was left untouched.
+	 */
+	public val status: HttpStatusCode,
+	public val headers: Headers,
+	public val version: HttpProtocolVersion,
+	/** The size of the downloaded file, or 0 if [status] is not successful. */
+	public val size: Long,
+) {
+	override fun toString(): String = "DownloadResult(status=$status, version=$version, size=$size)"
+}
+
+/**
+ * Downloads the body of a `GET` request to [url] directly into the file at [path].
+ * See [ImpersonateEngine.downloadTo] for details.
+ *
+ * @throws UnsupportedOperationException If this client does not use the [Impersonate] engine.
+ */
+public suspend fun HttpClient.downloadTo(
+	url: String,
+	path: String,
+	headers: Headers = Headers.Empty,
+	resume: Boolean = true,
+): DownloadResult {
+	val engine = engine as? ImpersonateEngine
+		?: throw UnsupportedOperationException("downloadTo is only supported by the Impersonate engine")
+
+	return engine.downloadTo(url, path, headers, resume)
+}
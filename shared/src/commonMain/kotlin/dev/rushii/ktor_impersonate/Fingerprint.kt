@@ -0,0 +1,76 @@
+package dev.rushii.ktor_impersonate
+
+import dev.rushii.ktor_impersonate.internal.NativeEngine
+import dev.rushii.ktor_impersonate.internal.initializeNative
+
+/**
+ * The fingerprints of a client's TLS ClientHello and HTTP/2 connection setup, as observed on the wire.
+ * These are returned by [ImpersonateEngine.fingerprint] and [FingerprintEchoServer.observedFingerprints].
+ */
+public class Fingerprint(
+	/**
+	 * The JA3 string, ie. `771,4865-4866-...,0-23-...,29-23-24,0`.
+	 * This depends on the order of extensions, which is randomized on every connection by newer browsers.
+	 */
+	public val ja3: String,
+	/** The MD5 hash of [ja3]. */
+	public val ja3Hash: String,
+	/**
+	 * The JA4 fingerprint, ie. `t13d1516h2_8daaf6152771_02713d6af862`.
+	 * This sorts extensions, so it is stable across connections.
+	 */
+	public val ja4: String,
+	/**
+	 * Akamai's HTTP/2 fingerprint, ie. `1:65536;2:0;4:6291456;6:262144|15663105|0|m,a,s,p`.
+	 * This is null if HTTP/2 was not negotiated.
+	 */
+	public val akamai: String?,
+	/** The MD5 hash of [akamai]. */
+	public val akamaiHash: String?,
+) {
+	override fun toString(): String =
+		"Fingerprint(ja3=$ja3, ja3Hash=$ja3Hash, ja4=$ja4, akamai=$akamai, akamaiHash=$akamaiHash)"
+
+	internal companion object {
+		/**
+		 * Parses fingerprints flattened by the native side into `[ja3, ja3Hash, ja4, akamai, akamaiHash, ...]`.
+		 */
+		fun fromValues(values: Array<String>): List<Fingerprint> = values.asList().chunked(5) { (ja3, ja3Hash, ja4, akamai, akamaiHash) ->
+			Fingerprint(ja3, ja3Hash, ja4, akamai.ifEmpty { null }, akamaiHash.ifEmpty { null })
+		}
+	}
+}
+
+/**
+ * A local HTTPS server for tests, which records the fingerprint of every connection made to it
+ * and responds to each request with that fingerprint as JSON (`ja3`, `ja3_hash`, `ja4`, `ja4_r`, `akamai`, `akamai_hash`).
+ *
+ * The server listens on a random port of `127.0.0.1` and uses a self-signed certificate,
+ * so clients need [ImpersonateConfig.allowInvalidCertificates] enabled, which does not affect their fingerprint.
+ * Connect to it through a host name (ie. with [ImpersonateConfig.resolve]) to have the server name extension included.
+ * Each connection only serves a single request, after which it is closed.
+ */
+public class FingerprintEchoServer : AutoCloseable {
+	private var nativeServerPtr: Long = NativeEngine.startEchoServer()
+
+	/** The port this server is listening on. */
+	public val port: Int = NativeEngine.getEchoServerPort(nativeServerPtr)
+
+	/**
+	 * Gets the fingerprints of all the connections made to this server so far, in the order they were made.
+	 */
+	public fun observedFingerprints(): List<Fingerprint> =
+		Fingerprint.fromValues(NativeEngine.getEchoServerFingerprints(nativeServerPtr))
+
+	override fun close() {
+		val ptr = nativeServerPtr
+		nativeServerPtr = 0
+		NativeEngine.stopEchoServer(ptr)
+	}
+
+	private companion object {
+		init {
+			initializeNative()
+		}
+	}
+}
@@ -0,0 +1,55 @@
+package dev.rushii.ktor_impersonate
+
+/**
+ * Config for natively recording every request sent by a client into HAR 1.2 entries, ie. to compare them against a browser's.
+ * Each attempt made by the native retries is a separate entry, while responses served by the native response cache are not recorded.
+ *
+ * Only what is visible to the engine is recorded, which leaves out the headers added while a request is being sent
+ * (ie. the [ImpersonateConfig.preset]'s default headers, `Host`, `Cookie`, and `Content-Length`)
+ * and the DNS, connect, and TLS timings, which are reported as `-1`.
+ * These are not reachable, as rquest exposes neither the request as written to the connection nor its connector,
+ * so entries are not a byte-exact record of the traffic. Each entry notes this in the `comment` of its request and timings.
+ * Response bodies are decoded before being recorded, so their size on the wire is also unknown.
+ * Peer certificates are included as `_securityDetails.peerCertificate` (base64 DER), and errors as `_error`.
+ */
+public class HarRecordingConfig(
+	/**
+	 * A path to write a HAR file to, which is replaced when the client is created.
+	 * Entries are appended on a background thread, after which the file is a complete document again.
+	 * All recorded entries have been written once the client is closed.
+	 * This should not be shared between clients.
+	 */
+	public val file: String? = null,
+	/**
+	 * Receives each entry as soon as it has been recorded, which happens once its response body has been read or closed.
+	 */
+	public val listener: HarListener? = null,
+	/**
+	 * Includes request and response bodies in entries.
+	 * Streaming request bodies are never included, as they are not buffered.
+	 * Defaults to false.
+	 */
+	public val includeBodies: Boolean = false,
+	/**
+	 * The maximum size of a body to include, after which it is left out of the entry.
+	 * Defaults to 1 MiB.
+	 */
+	public val maxBodySize: Long = 1L shl 20,
+) {
+	init {
+		require(file != null || listener != null) { "Either a file or a listener is required to record HAR entries" }
+		require(maxBodySize >= 0) { "maxBodySize ($maxBodySize) cannot be negative" }
+	}
+}
+
+/**
+ * Receives HAR entries recorded with [ImpersonateConfig.harRecording].
+ */
+public fun interface HarListener {
+	/**
+	 * Handles a single entry of a HAR log's `log.entries` array, serialized as JSON.
+	 * This is called on a native background thread or the thread closing a response, and should not block.
+	 * Exceptions thrown by this are ignored.
+	 */
+	public fun onEntry(entry: String)
+}
@@ -0,0 +1,22 @@
+package dev.rushii.ktor_impersonate
+
+/**
+ * Which HTTP versions are used when making requests.
+ */
+public enum class HttpVersionPolicy {
+	/**
+	 * Use HTTP/2 when the server supports it through ALPN (HTTPS only), otherwise HTTP/1.1.
+	 */
+	Negotiate,
+
+	/**
+	 * Only use HTTP/1.1, even if the server supports HTTP/2.
+	 */
+	Http1Only,
+
+	/**
+	 * Use HTTP/2 without negotiating it first, including cleartext HTTP/2 (h2c) for plain `http://` URLs.
+	 * Requests fail if the server does not support HTTP/2.
+	 */
+	Http2PriorKnowledge,
+}
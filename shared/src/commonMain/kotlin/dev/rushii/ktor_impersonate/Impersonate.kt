@@ -2,7 +2,6 @@ package dev.rushii.ktor_impersonate
 
 import io.ktor.client.engine.HttpClientEngine
 import io.ktor.client.engine.HttpClientEngineFactory
-import io.ktor.client.plugins.HttpTimeout
 import io.ktor.client.plugins.HttpTimeoutConfig
 
 /**
@@ -21,7 +20,11 @@ import io.ktor.client.plugins.HttpTimeoutConfig
  * **Notes**:
  * - Any changes to the engine configuration will be ignored once the engine has been initialized.
  * - SSE is not supported
- * - [HttpTimeout]'s only working setting is [HttpTimeoutConfig.requestTimeoutMillis]
+ * - [HttpTimeoutConfig.connectTimeoutMillis] is applied by a separate native client (and connection pool) per distinct value
+ * - [HttpTimeoutConfig.socketTimeoutMillis] is an inactivity timeout: it is reset by each part of the request body sent,
+ *   then applies to waiting for the response headers, and then to each part of the response body
+ * - Streaming request bodies are only read as fast as the native side sends them,
+ *   so `onUpload` and `onDownload` progress listeners reflect the actual transfer progress.
  */
 public object Impersonate : HttpClientEngineFactory<ImpersonateConfig> {
 	override fun create(block: ImpersonateConfig.() -> Unit): HttpClientEngine {
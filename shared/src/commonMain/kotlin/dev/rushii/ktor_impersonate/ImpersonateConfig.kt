@@ -2,8 +2,11 @@ package dev.rushii.ktor_impersonate
 
 import io.ktor.client.engine.HttpClientEngineConfig
 import io.ktor.client.plugins.HttpTimeoutConfig
+import io.ktor.client.request.HttpRequestBuilder
 import kotlin.jvm.JvmName
 import kotlin.time.Duration
+import kotlin.time.Duration.Companion.milliseconds
+import kotlin.time.Duration.Companion.seconds
 
 /**
  * Opt-in marker for APIs that allow for the breaking of the chain of trust.
@@ -44,7 +47,7 @@ public class ImpersonateConfig : HttpClientEngineConfig() {
 	 * This should be smaller than [requestTimeout] significantly.
 	 * Default is no timeout.
 	 *
-	 * **Note:** [HttpTimeoutConfig.connectTimeoutMillis] does nothing.
+	 * **Note:** [HttpTimeoutConfig.connectTimeoutMillis] overrides this per-request.
 	 */
 	public var connectTimeout: Duration? = null
 
@@ -54,10 +57,31 @@ public class ImpersonateConfig : HttpClientEngineConfig() {
 	 * Default is 90 seconds.
 	 *
 	 * **Note:** This is different from the HTTP 2.0 keep alive mechanism.
-	 * **Note:** [HttpTimeoutConfig.socketTimeoutMillis] does nothing.
+	 * **Note:** This is unrelated to [HttpTimeoutConfig.socketTimeoutMillis].
 	 */
 	public var idleTimeout: Duration? = null
 
+	// =========== Connection options =========== //
+
+	/**
+	 * The maximum amount of idle connections kept alive in the pool for each host.
+	 * Setting this to 0 disables connection reuse.
+	 * Default is no limit.
+	 */
+	public var maxIdleConnectionsPerHost: Int? = null
+
+	/**
+	 * Enables TCP keepalive on connections, sending probes after being idle for this duration.
+	 * Default is disabled.
+	 */
+	public var tcpKeepAlive: Duration? = null
+
+	/**
+	 * Sets `TCP_NODELAY` on connections, which disables Nagle's algorithm.
+	 * Defaults to true.
+	 */
+	public var tcpNoDelay: Boolean? = null
+
 	// =========== TLS options =========== //
 
 	/**
@@ -79,6 +103,239 @@ public class ImpersonateConfig : HttpClientEngineConfig() {
 
 	// =========== HTTP options =========== //
 
+	/**
+	 * Which HTTP versions are used for requests.
+	 * Defaults to [HttpVersionPolicy.Negotiate].
+	 *
+	 * **Note:** [HttpRequestBuilder.httpVersion] overrides this per-request.
+	 */
+	public var httpVersion: HttpVersionPolicy = HttpVersionPolicy.Negotiate
+
+	/**
+	 * Header names in the order they should be sent, overriding the header order of the [preset].
+	 * Headers that are not listed are sent after the listed ones.
+	 *
+	 * **Note:** [HttpRequestBuilder.headerOrder] overrides this per-request.
+	 * Default is the preset's order, otherwise the order headers were supplied in.
+	 */
+	public var headerOrder: List<String>? = null
+
+	/**
+	 * Sends HTTP/1.1 header names in Title-Case (ie. `Content-Type`) instead of lowercase.
+	 * This has no effect on HTTP/2, as header names are always lowercase there.
+	 * Defaults to false.
+	 */
+	public var http1TitleCaseHeaders: Boolean? = null
+
+	/**
+	 * Automatically decompress response bodies natively based on their `Content-Encoding` header.
+	 * Decompressed responses have their `Content-Encoding` and `Content-Length` headers removed.
+	 * When disabled, response bodies are received as the raw bytes sent by the server.
+	 * Individual algorithms can be toggled with [gzip], [brotli], [deflate], and [zstd].
+	 * Defaults to true.
+	 *
+	 * **Note:** Presets advertise their own `Accept-Encoding`, so disabling an algorithm it advertises
+	 * may result in receiving bodies compressed with that algorithm.
+	 */
+	public var decompression: Boolean = true
+
+	/**
+	 * Enables gzip response decompression when [decompression] is enabled.
+	 * Requires the native library to be built with the `gzip` cargo feature.
+	 * Defaults to true.
+	 */
+	public var gzip: Boolean? = null
+
+	/**
+	 * Enables brotli response decompression when [decompression] is enabled.
+	 * Requires the native library to be built with the `brotli` cargo feature.
+	 * Defaults to true.
+	 */
+	public var brotli: Boolean? = null
+
+	/**
+	 * Enables deflate response decompression when [decompression] is enabled.
+	 * Requires the native library to be built with the `deflate` cargo feature.
+	 * Defaults to true.
+	 */
+	public var deflate: Boolean? = null
+
+	/**
+	 * Enables zstd response decompression when [decompression] is enabled.
+	 * Requires the native library to be built with the `zstd` cargo feature.
+	 * Defaults to true.
+	 */
+	public var zstd: Boolean? = null
+
+	// =========== DNS options =========== //
+
+	/**
+	 * A custom resolver used to look up the addresses of hosts, instead of the system resolver.
+	 * Hosts overridden with [resolve] do not use this.
+	 * This cannot be used together with [dnsOverHttpsUrl].
+	 * Default is the system resolver.
+	 */
+	public var dnsResolver: DnsResolver? = null
+
+	/**
+	 * The URL of a DNS-over-HTTPS resolver (RFC 8484) used to look up the addresses of hosts, ie. `https://cloudflare-dns.com/dns-query`.
+	 * Queries are sent natively by a separate client with this same config, so the host of this URL is itself
+	 * resolved with the system resolver, unless it is overridden with [resolve].
+	 * Answers are cached for as long as their TTLs allow.
+	 * This cannot be used together with [dnsResolver].
+	 * Default is the system resolver.
+	 */
+	public var dnsOverHttpsUrl: String? = null
+
+	/**
+	 * Whether to fall back to the system resolver when a DNS-over-HTTPS query fails, instead of failing the request.
+	 * Defaults to false, as falling back defeats the point on networks that tamper with DNS.
+	 */
+	public var dnsOverHttpsFallback: Boolean = false
+
+	/**
+	 * Which IP address families are used when connecting to hosts.
+	 * Defaults to [IpPreference.HappyEyeballs].
+	 */
+	public var ipPreference: IpPreference = IpPreference.HappyEyeballs
+
+	private val hostOverrides = mutableMapOf<String, List<String>>()
+
+	/**
+	 * Overrides the addresses for a host, skipping DNS resolution entirely (like curl's `--resolve`).
+	 * The port of the request URL is still used, and the host is still used for SNI and the `Host` header.
+	 * Calling this again for the same host replaces its addresses.
+	 *
+	 * @param host The host name to override, ie. `example.com`.
+	 * @param addresses The IP address literals to connect to instead.
+	 */
+	public fun resolve(host: String, vararg addresses: String) {
+		hostOverrides[host] = addresses.toList()
+	}
+
+	// =========== Network options =========== //
+
+	/**
+	 * The local IP address that connections are made from, ie. to pick the source address on a multi-homed host.
+	 * Default is chosen by the OS.
+	 *
+	 * **Note:** [HttpRequestBuilder.localAddress] overrides this per-request.
+	 */
+	public var localAddress: String? = null
+
+	/**
+	 * The name of the network interface that connections are bound to, ie. `wlan0` or `rmnet0`.
+	 * This is only supported on Android and Linux, and requires `CAP_NET_RAW`, which apps usually don't have on Android.
+	 * Use [localAddress] with the address of the interface instead when this fails.
+	 * Default is chosen by the OS.
+	 *
+	 * **Note:** [HttpRequestBuilder.networkInterface] overrides this per-request.
+	 */
+	public var networkInterface: String? = null
+
+	/**
+	 * The path of a Unix domain socket that requests are sent over instead of connecting to their host with TCP,
+	 * ie. to talk to a local daemon. The host of the request's URL is then only used for the `Host` header.
+	 * On Android and Linux, a path starting with `@` refers to a socket in the abstract namespace,
+	 * like the ones created by `android.net.LocalServerSocket`.
+	 *
+	 * Only `http` URLs are supported, and requests are sent over HTTP/1.1 with a new connection each.
+	 * The preset's default headers, [responseCache], [harRecording], native retries, rate limits, and response decompression
+	 * are not applied to these requests.
+	 * Default is null (disabled).
+	 *
+	 * **Note:** [HttpRequestBuilder.unixSocket] overrides this per-request.
+	 */
+	public var unixSocket: String? = null
+
+	// =========== Retry options =========== //
+
+	/**
+	 * The maximum amount of attempts made natively for each request, including the first one.
+	 * Retries are not made for streaming request bodies, as they cannot be sent again.
+	 * The amount of attempts made is available through [io.ktor.client.statement.HttpResponse.attempts].
+	 * Defaults to 1, which disables retrying.
+	 */
+	public var retryMaxAttempts: Int = 1
+
+	/**
+	 * Retry requests that failed to connect, which is safe for any method as nothing has been sent yet.
+	 * Defaults to true.
+	 */
+	public var retryOnConnectionFailure: Boolean = true
+
+	/**
+	 * Retry requests with idempotent methods (ie. `GET`, `PUT`, `DELETE`) that received a response with one of these status codes.
+	 * Defaults to 429, 502, 503, and 504.
+	 */
+	public var retryStatusCodes: Set<Int> = setOf(429, 502, 503, 504)
+
+	/**
+	 * The delay before the first retry, which is doubled for each following retry.
+	 * A random jitter is applied to each delay, between zero and the full delay.
+	 * Defaults to 500 milliseconds.
+	 */
+	public var retryBaseDelay: Duration = 500.milliseconds
+
+	/**
+	 * The maximum delay before a retry.
+	 * Responses with a `Retry-After` longer than this are returned instead of being retried.
+	 * Defaults to 30 seconds.
+	 */
+	public var retryMaxDelay: Duration = 30.seconds
+
+	/**
+	 * Wait for as long as a response's `Retry-After` header specifies before retrying, instead of backing off.
+	 * Defaults to true.
+	 */
+	public var respectRetryAfter: Boolean = true
+
+	// =========== Limit options =========== //
+
+	/**
+	 * Limits how fast requests are sent across all hosts.
+	 * Requests over the limit are queued natively, and the time spent queued is available through
+	 * [io.ktor.client.statement.HttpResponse.queueTime].
+	 * Default is no limit.
+	 */
+	public var rateLimit: RateLimit? = null
+
+	/**
+	 * Limits how fast requests are sent to each host (and port) separately.
+	 * Default is no limit.
+	 */
+	public var rateLimitPerHost: RateLimit? = null
+
+	/**
+	 * The maximum amount of requests that are active at once across all hosts, including reading their response bodies.
+	 * Requests over the limit are queued natively until an active request's response is closed.
+	 * Default is no limit.
+	 */
+	public var maxConcurrentRequests: Int? = null
+
+	/**
+	 * The maximum amount of requests that are active at once to each host (and port) separately.
+	 * Default is no limit.
+	 */
+	public var maxConcurrentRequestsPerHost: Int? = null
+
+	// =========== Cache options =========== //
+
+	/**
+	 * Enables caching responses natively, which avoids passing cached bodies through Ktor's `HttpCache` plugin.
+	 * How each response was produced is available through [io.ktor.client.statement.HttpResponse.cacheStatus].
+	 * Default is no caching.
+	 */
+	public var responseCache: ResponseCacheConfig? = null
+
+	// =========== Recording options =========== //
+
+	/**
+	 * Records every request sent by this client into HAR 1.2 entries, written to a file or passed to a listener.
+	 * Default is not recording.
+	 */
+	public var harRecording: HarRecordingConfig? = null
+
 	// =========== HTTPS options =========== //
 
 	/**
@@ -89,6 +346,26 @@ public class ImpersonateConfig : HttpClientEngineConfig() {
 
 	// =========== HTTP/2 options =========== //
 
+	/**
+	 * Sends HTTP/2 PING frames at this interval to keep connections alive.
+	 * Default is disabled, unless set by the [preset].
+	 */
+	public var http2KeepAliveInterval: Duration? = null
+
+	/**
+	 * Closes HTTP/2 connections when a keepalive PING is not acknowledged within this timeout.
+	 * This has no effect unless [http2KeepAliveInterval] is set.
+	 * Default is 20 seconds.
+	 */
+	public var http2KeepAliveTimeout: Duration? = null
+
+	/**
+	 * Sends keepalive PINGs even when there are no active streams on a connection.
+	 * This has no effect unless [http2KeepAliveInterval] is set.
+	 * Defaults to false.
+	 */
+	public var http2KeepAliveWhileIdle: Boolean? = null
+
 	// =========== Internal =========== //
 
 	// Internal methods used by native code
@@ -98,5 +375,24 @@ public class ImpersonateConfig : HttpClientEngineConfig() {
 	@Suppress("unused") private fun getRequestTimeoutMillis(): Long? = requestTimeout?.inWholeMilliseconds
 	@Suppress("unused") private fun getConnectTimeoutMillis(): Long? = connectTimeout?.inWholeMilliseconds
 	@Suppress("unused") private fun getIdleTimeout(): Long? = idleTimeout?.inWholeMilliseconds
+	@Suppress("unused") private fun getTcpKeepAliveMillis(): Long? = tcpKeepAlive?.inWholeMilliseconds
+	@Suppress("unused") private fun getHttp2KeepAliveIntervalMillis(): Long? = http2KeepAliveInterval?.inWholeMilliseconds
+	@Suppress("unused") private fun getHttp2KeepAliveTimeoutMillis(): Long? = http2KeepAliveTimeout?.inWholeMilliseconds
+	@Suppress("unused") private fun getIpPreferenceName(): String = ipPreference.name
+	@Suppress("unused") private fun getHttpVersionName(): String = httpVersion.name
+	@Suppress("unused") private fun getRetryStatusCodesArray(): IntArray = retryStatusCodes.toIntArray()
+	@Suppress("unused") private fun getRetryBaseDelayMillis(): Long = retryBaseDelay.inWholeMilliseconds
+	@Suppress("unused") private fun getRetryMaxDelayMillis(): Long = retryMaxDelay.inWholeMilliseconds
+	@Suppress("unused") private fun getRateLimitValues(): LongArray? = rateLimit?.toValues()
+	@Suppress("unused") private fun getRateLimitPerHostValues(): LongArray? = rateLimitPerHost?.toValues()
+	@Suppress("unused") private fun getResponseCacheSizes(): LongArray? = responseCache?.let { longArrayOf(it.maxMemorySize, it.maxDiskSize) }
+	@Suppress("unused") private fun getResponseCacheDirectory(): String? = responseCache?.directory
+	@Suppress("unused") private fun getHarFile(): String? = harRecording?.file
+	@Suppress("unused") private fun getHarListener(): HarListener? = harRecording?.listener
+	@Suppress("unused") private fun getHarMaxBodySize(): Long = harRecording?.takeIf { it.includeBodies }?.maxBodySize ?: 0
+	@Suppress("unused") private fun getHostOverridesArray(): Array<String> =
+		hostOverrides.flatMap { (host, addresses) -> addresses.flatMap { listOf(host, it) } }.toTypedArray()
 	// @formatter:on
+
+	private fun RateLimit.toValues(): LongArray = longArrayOf(requests.toLong(), period.inWholeMilliseconds)
 }
@@ -4,6 +4,7 @@ package dev.rushii.ktor_impersonate
 
 import dev.rushii.ktor_impersonate.internal.*
 import io.ktor.client.engine.*
+import io.ktor.client.plugins.*
 import io.ktor.client.plugins.websocket.*
 import io.ktor.client.request.*
 import io.ktor.http.*
@@ -12,6 +13,7 @@ import io.ktor.utils.io.*
 import kotlinx.coroutines.*
 import kotlinx.io.*
 import kotlin.coroutines.*
+import kotlin.time.Duration.Companion.milliseconds
 
 public class ImpersonateEngine(override val config: ImpersonateConfig) : HttpClientEngineBase("ktor-impersonate") {
 	// Pointer to the native rquest client.
@@ -19,7 +21,7 @@ public class ImpersonateEngine(override val config: ImpersonateConfig) : HttpCli
 
 	// Reqwest does not support SSE
 	override val supportedCapabilities: Set<HttpClientEngineCapability<*>>
-		get() = setOf(WebSocketCapability, WebSocketExtensionsCapability)
+		get() = setOf(HttpTimeoutCapability, WebSocketCapability, WebSocketExtensionsCapability)
 
 	@OptIn(InternalAPI::class)
 	override suspend fun execute(data: HttpRequestData): HttpResponseData {
@@ -27,18 +29,35 @@ public class ImpersonateEngine(override val config: ImpersonateConfig) : HttpCli
 		val requestTime = GMTDate()
 
 		return suspendCancellableCoroutine { continuation ->
-			var requestId: Int = 0
-
 			// Make callbacks to handle native request completion
 			val callbacks = object : NativeEngine.Callbacks() {
-				override fun onResponse(version: String, code: Int, headers: Headers) {
+				override fun onResponse(
+					requestId: Int,
+					versionMajor: Int,
+					versionMinor: Int,
+					code: Int,
+					headers: Headers,
+					attempts: Int,
+					queueTimeMillis: Long,
+					cacheStatus: Int,
+				) {
 					try {
+						data.attributes.put(AttemptsAttributeKey, attempts)
+						data.attributes.put(QueueTimeAttributeKey, queueTimeMillis.milliseconds)
+						when (cacheStatus) {
+							NativeEngine.CACHE_MISS -> data.attributes.put(CacheStatusAttributeKey, CacheStatus.Miss)
+							NativeEngine.CACHE_HIT -> data.attributes.put(CacheStatusAttributeKey, CacheStatus.Hit)
+							NativeEngine.CACHE_REVALIDATED -> data.attributes.put(CacheStatusAttributeKey, CacheStatus.Revalidated)
+						}
+						val trailers = ResponseTrailers()
+						data.attributes.put(TrailersAttributeKey, trailers)
+
 						val data = HttpResponseData(
 							statusCode = HttpStatusCode.fromValue(code),
 							requestTime = requestTime,
 							headers = headers,
-							version = HttpProtocolVersion.parse(version),
-							body = SourceByteReadChannel(ResponseSource(requestId).buffered()),
+							version = HttpProtocolVersion.fromValue("HTTP", versionMajor, versionMinor),
+							body = SourceByteReadChannel(ResponseSource(requestId, trailers).buffered()),
 							callContext = callContext,
 						)
 						continuation.resume(data)
@@ -47,21 +66,41 @@ public class ImpersonateEngine(override val config: ImpersonateConfig) : HttpCli
 					}
 				}
 
-				override fun onError(message: String) {
-					continuation.resumeWithException(RquestException(message))
+				override fun onError(kind: Int, message: String) {
+					val cause = RquestException(message)
+					val exception = when (kind) {
+						NativeEngine.ERROR_CONNECT_TIMEOUT -> ConnectTimeoutException(data, cause)
+						NativeEngine.ERROR_SOCKET_TIMEOUT -> SocketTimeoutException(data, cause)
+						NativeEngine.ERROR_REQUEST_TIMEOUT -> HttpRequestTimeoutException(data)
+						else -> cause
+					}
+					continuation.resumeWithException(exception)
 				}
 			}
 
 			// Start native request
-			requestId = NativeEngine.executeRequest(
+			val requestId = NativeEngine.executeRequest(
 				clientPtr = nativeClientPtr,
 				callbacks = callbacks,
 				url = data.url.toString(),
 				httpMethod = data.method.value,
-				headers = data.headers,
+				headers = data.mergedHeaders(),
+				body = data.body.immediateBytes(),
+				streamBody = data.body.isStreaming(),
+				formData = (data.body as? NativeFormDataContent)?.toValues(),
+				fileBody = data.body as? NativeFileContent,
+				requestConfig = RequestConfig.from(data),
 				isWebsocket = data.isUpgradeRequest(),
 			)
 
+			// Stream the request body to the native side as it gets sent
+			val body = data.body
+			if (body.isStreaming()) {
+				CoroutineScope(callContext).launch { writeRequestBody(requestId, body) }
+			} else if (body is NativeFormDataContent && body.parts.any { it is NativeFormPart.Channel }) {
+				CoroutineScope(callContext).launch { writeFormDataParts(requestId, body) }
+			}
+
 			// Abort native request if coroutine gets cancelled
 			continuation.invokeOnCancellation { NativeEngine.cancelRequest(requestId) }
 
@@ -70,6 +109,103 @@ public class ImpersonateEngine(override val config: ImpersonateConfig) : HttpCli
 		}
 	}
 
+	/**
+	 * Establishes connections to the host of [url] ahead of time and keeps them in the connection pool,
+	 * so that later requests to it skip the DNS lookup, TCP connect, and TLS handshake.
+	 *
+	 * rquest does not expose its connector, so each connection is established by sending a real `HEAD` request to [url]
+	 * with the preset's default headers, which the server sees and may log like any other request.
+	 * These requests bypass the client's plugins, rate and concurrency limits, retries, response cache and HAR recording.
+	 * Their responses are discarded natively.
+	 * Over HTTP/2, concurrent requests share a single connection, so only one connection is established regardless of [count].
+	 *
+	 * @param count The amount of connections to establish concurrently.
+	 * @throws RquestException If establishing any of the connections failed.
+	 */
+	public suspend fun preconnect(url: String, count: Int = 1) {
+		require(count > 0) { "count ($count) must be positive" }
+
+		coroutineScope {
+			repeat(count) { launch { preconnectOnce(url) } }
+		}
+	}
+
+	private suspend fun preconnectOnce(url: String): Unit = suspendCancellableCoroutine { continuation ->
+		val callbacks = object : NativeEngine.PreconnectCallbacks() {
+			override fun onConnected() {
+				continuation.resume(Unit)
+			}
+
+			override fun onError(kind: Int, message: String) {
+				continuation.resumeWithException(RquestException(message))
+			}
+		}
+
+		val requestId = NativeEngine.preconnect(nativeClientPtr, callbacks, url)
+
+		continuation.invokeOnCancellation { NativeEngine.cancelRequest(requestId) }
+		if (continuation.isCancelled) NativeEngine.cancelRequest(requestId)
+	}
+
+	/**
+	 * Downloads the body of a `GET` request to [url] directly into the file at [path], without passing it through the JVM.
+	 * This bypasses the client's plugins, so only the preset's default headers and [headers] are sent.
+	 *
+	 * The body is written to `<path>.part` and only moved to [path] once it has been fully received, replacing any existing file.
+	 * If the download fails or is cancelled, the partial file is kept. With [resume], a later download to the same [path]
+	 * only requests the rest of the body with a `Range` header, as long as the server sent an `ETag` or `Last-Modified`
+	 * header to verify that the resource has not changed since. Otherwise, the download restarts from the beginning.
+	 *
+	 * The body is always requested without a content encoding, since ranges apply to the encoded body.
+	 *
+	 * @throws RquestException If the request failed or the file could not be written.
+	 */
+	public suspend fun downloadTo(
+		url: String,
+		path: String,
+		headers: Headers = Headers.Empty,
+		resume: Boolean = true,
+	): DownloadResult = suspendCancellableCoroutine { continuation ->
+		val callbacks = object : NativeEngine.DownloadCallbacks() {
+			override fun onComplete(versionMajor: Int, versionMinor: Int, code: Int, headers: Headers, size: Long) {
+				val result = DownloadResult(
+					status = HttpStatusCode.fromValue(code),
+					headers = headers,
+					version = HttpProtocolVersion.fromValue("HTTP", versionMajor, versionMinor),
+					size = size,
+				)
+				continuation.resume(result)
+			}
+
+			override fun onError(kind: Int, message: String) {
+				continuation.resumeWithException(RquestException(message))
+			}
+		}
+
+		val requestId = NativeEngine.executeDownload(
+			clientPtr = nativeClientPtr,
+			callbacks = callbacks,
+			url = url,
+			headers = headers,
+			path = path,
+			resume = resume,
+		)
+
+		continuation.invokeOnCancellation { NativeEngine.cancelRequest(requestId) }
+		if (continuation.isCancelled) NativeEngine.cancelRequest(requestId)
+	}
+
+	/**
+	 * Determines the TLS and HTTP/2 fingerprints that this engine's connections have, as seen by servers.
+	 * This connects a client with the same config to a local [FingerprintEchoServer] and captures its handshake,
+	 * so no traffic leaves the device.
+	 *
+	 * @throws RuntimeException If the connection to the echo server failed.
+	 */
+	public suspend fun fingerprint(): Fingerprint = withContext(dispatcher) {
+		Fingerprint.fromValues(NativeEngine.getFingerprint(nativeClientPtr)).single()
+	}
+
 	override fun close() {
 		super.close()
 		val ptr = nativeClientPtr
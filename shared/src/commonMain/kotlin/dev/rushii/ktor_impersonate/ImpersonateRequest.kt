@@ -0,0 +1,98 @@
+package dev.rushii.ktor_impersonate
+
+import dev.rushii.ktor_impersonate.internal.ResponseTrailers
+import io.ktor.client.request.HttpRequestBuilder
+import io.ktor.client.statement.HttpResponse
+import io.ktor.http.Headers
+import io.ktor.util.AttributeKey
+import kotlin.time.Duration
+
+internal val HeaderOrderAttributeKey: AttributeKey<List<String>> = AttributeKey("ImpersonateHeaderOrder")
+internal val LocalAddressAttributeKey: AttributeKey<String> = AttributeKey("ImpersonateLocalAddress")
+internal val AttemptsAttributeKey: AttributeKey<Int> = AttributeKey("ImpersonateAttempts")
+internal val CacheStatusAttributeKey: AttributeKey<CacheStatus> = AttributeKey("ImpersonateCacheStatus")
+internal val QueueTimeAttributeKey: AttributeKey<Duration> = AttributeKey("ImpersonateQueueTime")
+internal val HttpVersionAttributeKey: AttributeKey<HttpVersionPolicy> = AttributeKey("ImpersonateHttpVersion")
+internal val NetworkInterfaceAttributeKey: AttributeKey<String> = AttributeKey("ImpersonateNetworkInterface")
+internal val UnixSocketAttributeKey: AttributeKey<String> = AttributeKey("ImpersonateUnixSocket")
+internal val TrailersAttributeKey: AttributeKey<ResponseTrailers> = AttributeKey("ImpersonateTrailers")
+
+/**
+ * Sets the order that this request's headers are sent in, overriding [ImpersonateConfig.headerOrder] and the preset's order.
+ * This also applies to the default headers added by the preset.
+ * Headers that are not listed are sent after the listed ones.
+ * Requests with a different header order than the client's do not share its connection pool.
+ * This is ignored by other engines.
+ */
+public fun HttpRequestBuilder.headerOrder(vararg names: String) {
+	attributes.put(HeaderOrderAttributeKey, names.toList())
+}
+
+/**
+ * Sets the local IP address that this request's connection is made from, overriding [ImpersonateConfig.localAddress].
+ * Requests with a different local address than the client's do not share its connection pool.
+ * This is ignored by other engines.
+ */
+public fun HttpRequestBuilder.localAddress(address: String) {
+	attributes.put(LocalAddressAttributeKey, address)
+}
+
+/**
+ * Sets the network interface that this request's connection is bound to, overriding [ImpersonateConfig.networkInterface].
+ * Requests with a different interface than the client's do not share its connection pool.
+ * This is ignored by other engines.
+ */
+public fun HttpRequestBuilder.networkInterface(name: String) {
+	attributes.put(NetworkInterfaceAttributeKey, name)
+}
+
+/**
+ * Sends this request over a Unix domain socket instead of TCP, overriding [ImpersonateConfig.unixSocket].
+ * See [ImpersonateConfig.unixSocket] for the limitations of these requests.
+ * This is ignored by other engines.
+ */
+public fun HttpRequestBuilder.unixSocket(path: String) {
+	attributes.put(UnixSocketAttributeKey, path)
+}
+
+/**
+ * Sets which HTTP versions are used for this request, overriding [ImpersonateConfig.httpVersion].
+ * Requests with a different policy than the client's do not share its connection pool.
+ * This is ignored by other engines.
+ */
+public fun HttpRequestBuilder.httpVersion(policy: HttpVersionPolicy) {
+	attributes.put(HttpVersionAttributeKey, policy)
+}
+
+/**
+ * The amount of attempts the native retry policy made for this request, including the first one.
+ * This is 0 when the response was served from the native cache without making a request.
+ * This is always 1 when [ImpersonateConfig.retryMaxAttempts] is not set, or when using other engines.
+ */
+public val HttpResponse.attempts: Int
+	get() = call.request.attributes.getOrNull(AttemptsAttributeKey) ?: 1
+
+/**
+ * How long this request was queued natively by the [ImpersonateConfig.rateLimit] and concurrency limits before being sent.
+ * This is included in the time between [HttpResponse.requestTime] and [HttpResponse.responseTime].
+ * This is always zero when no limits are set, or when using other engines.
+ */
+public val HttpResponse.queueTime: Duration
+	get() = call.request.attributes.getOrNull(QueueTimeAttributeKey) ?: Duration.ZERO
+
+/**
+ * How this response was produced by the native response cache,
+ * or null if [ImpersonateConfig.responseCache] is not set, the request bypassed the cache, or when using other engines.
+ */
+public val HttpResponse.cacheStatus: CacheStatus?
+	get() = call.request.attributes.getOrNull(CacheStatusAttributeKey)
+
+/**
+ * The trailer fields received after this response's body, which are only available once the body has been fully read.
+ *
+ * This is null until then, when the response had no trailers, or when using other engines.
+ * rquest 0.23 drops the trailers of both HTTP/1.1 and HTTP/2 responses, so they are only received
+ * for requests sent over a Unix domain socket with [ImpersonateConfig.unixSocket] or [unixSocket].
+ */
+public val HttpResponse.trailers: Headers?
+	get() = call.request.attributes.getOrNull(TrailersAttributeKey)?.headers
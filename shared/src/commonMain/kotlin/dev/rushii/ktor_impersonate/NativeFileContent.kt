@@ -0,0 +1,41 @@
+package dev.rushii.ktor_impersonate
+
+import io.ktor.http.ContentType
+import io.ktor.http.content.OutgoingContent
+
+/**
+ * A request body that is read natively from a file while it is sent, without copying its contents through the JVM.
+ * The `Content-Length` header is set natively to the size of the range being sent.
+ *
+ * Ktor's `onUpload` cannot observe this body, so use [progressListener] instead.
+ * This is only supported by the [Impersonate] engine.
+ *
+ * @param path The path of the file to send.
+ * @param offset The position in the file to start sending from.
+ * @param length The amount of bytes to send, otherwise the rest of the file after [offset] is sent.
+ * The request fails if the range does not fit in the file.
+ * @param progressListener Receives the amount of bytes sent so far as the file is read.
+ */
+public class NativeFileContent(
+	public val path: String,
+	public val offset: Long = 0,
+	public val length: Long? = null,
+	override val contentType: ContentType? = null,
+	public val progressListener: UploadProgressListener? = null,
+) : OutgoingContent.NoContent() {
+	init {
+		require(offset >= 0) { "offset ($offset) cannot be negative" }
+		require(length == null || length >= 0) { "length ($length) cannot be negative" }
+	}
+
+	// Read by the native side, since the nullable length would be boxed
+	@Suppress("unused") private fun getLengthValue(): Long = length ?: -1
+}
+
+/**
+ * Receives the progress of sending a [NativeFileContent].
+ * This is called from a native thread after each part of the file is read to be sent.
+ */
+public fun interface UploadProgressListener {
+	public fun onProgress(bytesSent: Long, totalBytes: Long)
+}
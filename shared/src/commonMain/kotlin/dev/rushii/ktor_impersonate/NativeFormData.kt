@@ -0,0 +1,109 @@
+package dev.rushii.ktor_impersonate
+
+import io.ktor.http.ContentType
+import io.ktor.http.content.OutgoingContent
+import io.ktor.utils.io.ByteReadChannel
+
+/**
+ * A `multipart/form-data` request body that is encoded natively, created with [nativeFormData].
+ *
+ * Unlike Ktor's `MultiPartFormDataContent`, the boundary and part headers are formatted like the [ImpersonateConfig.preset]
+ * would format them, ie. `----WebKitFormBoundary...` for Chrome, Edge, and Safari, or a random UUID for OkHttp.
+ * File parts are read directly from their path natively, without being copied through the JVM.
+ * The `Content-Type` header (and `Content-Length`, when the size of every part is known) is set natively.
+ *
+ * This is only supported by the [Impersonate] engine.
+ */
+public class NativeFormDataContent internal constructor(internal val parts: List<NativeFormPart>) : OutgoingContent.NoContent() {
+	/**
+	 * Flattens the parts as `[kind, name, fileName, contentType, value, ...]` to be read by the native side,
+	 * with empty strings for absent values.
+	 */
+	internal fun toValues(): Array<String> = parts.flatMap { part ->
+		when (part) {
+			is NativeFormPart.Text -> listOf("text", part.name, "", part.contentType?.toString() ?: "", part.value)
+			is NativeFormPart.File -> listOf("file", part.name, part.fileName, part.contentType?.toString() ?: "", part.path)
+			is NativeFormPart.Channel -> listOf("stream", part.name, part.fileName, part.contentType?.toString() ?: "", part.length?.toString() ?: "")
+		}
+	}.toTypedArray()
+}
+
+/**
+ * Builds a [NativeFormDataContent] to be used as a request body, ie. with `setBody(nativeFormData { ... })`.
+ */
+public fun nativeFormData(block: NativeFormDataBuilder.() -> Unit): NativeFormDataContent =
+	NativeFormDataContent(NativeFormDataBuilder().apply(block).parts)
+
+/**
+ * Builds the parts of a [NativeFormDataContent], which are sent in the order they were appended.
+ */
+public class NativeFormDataBuilder internal constructor() {
+	internal val parts = mutableListOf<NativeFormPart>()
+
+	/**
+	 * Appends a form field. No `Content-Type` is sent for this part unless [contentType] is set.
+	 */
+	public fun append(name: String, value: String, contentType: ContentType? = null) {
+		parts += NativeFormPart.Text(name, value, contentType)
+	}
+
+	/**
+	 * Appends a file that is read natively from [path] while the request is sent.
+	 * The file's size is determined when the request is started, and it must not change while being sent.
+	 * @param fileName The file name sent in the part's `Content-Disposition` header.
+	 * @param contentType The content type of the part, otherwise chosen like the preset would choose it.
+	 */
+	public fun appendFile(
+		name: String,
+		path: String,
+		fileName: String = path.substringAfterLast('/'),
+		contentType: ContentType? = null,
+	) {
+		parts += NativeFormPart.File(name, path, fileName, contentType)
+	}
+
+	/**
+	 * Appends a file with its contents already in memory.
+	 * @see appendFile
+	 */
+	public fun appendBytes(
+		name: String,
+		bytes: ByteArray,
+		fileName: String,
+		contentType: ContentType? = null,
+	) {
+		parts += NativeFormPart.Channel(name, fileName, contentType, bytes.size.toLong()) { ByteReadChannel(bytes) }
+	}
+
+	/**
+	 * Appends a file whose contents are streamed from the JVM while the request is sent.
+	 * @param length The exact size of the contents if known, which allows sending a `Content-Length` for the whole body.
+	 * @param provider Opens the contents of this part, which is called once before the part is sent.
+	 * @see appendFile
+	 */
+	public fun appendChannel(
+		name: String,
+		fileName: String,
+		contentType: ContentType? = null,
+		length: Long? = null,
+		provider: () -> ByteReadChannel,
+	) {
+		parts += NativeFormPart.Channel(name, fileName, contentType, length, provider)
+	}
+}
+
+internal sealed class NativeFormPart {
+	abstract val name: String
+
+	class Text(override val name: String, val value: String, val contentType: ContentType?) : NativeFormPart()
+
+	class File(override val name: String, val path: String, val fileName: String, val contentType: ContentType?) : NativeFormPart()
+
+	class Channel(
+		override val name: String,
+		val fileName: String,
+		val contentType: ContentType?,
+		val length: Long?,
+		val provider: () -> ByteReadChannel,
+	) : NativeFormPart()
+}
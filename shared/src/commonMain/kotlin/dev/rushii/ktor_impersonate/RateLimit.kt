@@ -0,0 +1,19 @@
+package dev.rushii.ktor_impersonate
+
+import kotlin.time.Duration
+
+/**
+ * A token bucket rate limit, allowing up to [requests] to be sent in a burst and refilling at a rate of [requests] per [period].
+ * Requests over the limit are queued natively until they are allowed to be sent.
+ */
+public class RateLimit(
+	public val requests: Int,
+	public val period: Duration,
+) {
+	init {
+		require(requests > 0) { "requests ($requests) must be positive" }
+		require(period.inWholeMilliseconds > 0) { "period ($period) must be at least 1ms" }
+	}
+
+	override fun toString(): String = "RateLimit(requests=$requests, period=$period)"
+}
@@ -0,0 +1,51 @@
+package dev.rushii.ktor_impersonate
+
+/**
+ * Config for caching responses natively, following the rules of a private HTTP cache (RFC 9111) like a browser's.
+ * `Cache-Control`, `Expires`, `ETag`/`If-None-Match`, `Last-Modified`/`If-Modified-Since`, and `Vary` are supported.
+ * Only `GET` requests are served from the cache, and requests that set their own conditional or `Range` headers bypass it.
+ * Responses with a `Vary` header are stored as separate variants of their URL, up to 8 per URL.
+ */
+public class ResponseCacheConfig(
+	/**
+	 * The maximum total size of response bodies kept in memory.
+	 * Defaults to 10 MiB.
+	 */
+	public val maxMemorySize: Long = 10L shl 20,
+	/**
+	 * A directory to persist responses in, so that they can be reused across restarts.
+	 * This should not be shared between clients.
+	 * Default is only caching in memory.
+	 */
+	public val directory: String? = null,
+	/**
+	 * The maximum total size of the files in [directory], after which the least recently stored responses are deleted.
+	 * Defaults to 50 MiB.
+	 */
+	public val maxDiskSize: Long = 50L shl 20,
+) {
+	init {
+		require(maxMemorySize >= 0) { "maxMemorySize ($maxMemorySize) cannot be negative" }
+		require(maxDiskSize >= 0) { "maxDiskSize ($maxDiskSize) cannot be negative" }
+	}
+}
+
+/**
+ * How a response was produced by the native response cache.
+ */
+public enum class CacheStatus {
+	/**
+	 * The response was received from the network, and stored if it was cacheable.
+	 */
+	Miss,
+
+	/**
+	 * The response was served from the cache without making a request.
+	 */
+	Hit,
+
+	/**
+	 * The stored response was served after the server confirmed that it is still valid with a `304 Not Modified`.
+	 */
+	Revalidated,
+}
@@ -1,6 +1,7 @@
 package dev.rushii.ktor_impersonate.internal
 
 import dev.rushii.ktor_impersonate.ImpersonateConfig
+import dev.rushii.ktor_impersonate.NativeFileContent
 import io.ktor.http.Headers
 import kotlin.jvm.JvmStatic
 
@@ -24,14 +25,111 @@ internal object NativeEngine {
 		url: String,
 		httpMethod: String,
 		headers: Headers,
+		body: ByteArray?,
+		streamBody: Boolean,
+		formData: Array<String>?,
+		fileBody: NativeFileContent?,
+		requestConfig: RequestConfig,
 		isWebsocket: Boolean,
 	): Int
 
+	/**
+	 * Sends the next chunk of a streaming request body, blocking until the native side is ready to accept it.
+	 * For [NativeFormDataContent][dev.rushii.ktor_impersonate.NativeFormDataContent] bodies,
+	 * an empty chunk marks the end of the current part provided by the JVM.
+	 * @return False if the request is no longer active.
+	 */
+	@JvmStatic
+	external fun writeRequestBody(requestId: Int, bytes: ByteArray, length: Int): Boolean
+
+	/**
+	 * Finishes a streaming request body, optionally failing the request with an error instead.
+	 */
+	@JvmStatic
+	external fun closeRequestBody(requestId: Int, error: String?)
+
+	/**
+	 * Starts downloading the body of a `GET` request into a file, which can be cancelled with [cancelRequest].
+	 */
+	@JvmStatic
+	external fun executeDownload(
+		clientPtr: Long,
+		callbacks: DownloadCallbacks,
+		url: String,
+		headers: Headers,
+		path: String,
+		resume: Boolean,
+	): Int
+
+	/**
+	 * Establishes a connection by sending a `HEAD` request whose response is discarded natively,
+	 * which can be cancelled with [cancelRequest].
+	 */
+	@JvmStatic
+	external fun preconnect(clientPtr: Long, callbacks: PreconnectCallbacks, url: String): Int
+
 	@JvmStatic
 	external fun cancelRequest(requestId: Int)
 
+	/**
+	 * Determines the fingerprint of a client by connecting it to a local echo server, blocking until done.
+	 * The fingerprint is flattened as `[ja3, ja3Hash, ja4, akamai, akamaiHash]`, with empty strings for absent values.
+	 */
+	@JvmStatic
+	external fun getFingerprint(clientPtr: Long): Array<String>
+
+	@JvmStatic
+	external fun startEchoServer(): Long
+
+	@JvmStatic
+	external fun stopEchoServer(serverPtr: Long)
+
+	@JvmStatic
+	external fun getEchoServerPort(serverPtr: Long): Int
+
+	/**
+	 * Gets the fingerprints observed by an echo server, flattened in the same way as [getFingerprint].
+	 */
+	@JvmStatic
+	external fun getEchoServerFingerprints(serverPtr: Long): Array<String>
+
+	// Kinds of errors passed to Callbacks.onError, which must be kept in sync with the native side
+	const val ERROR_OTHER = 0
+	const val ERROR_CONNECT_TIMEOUT = 1
+	const val ERROR_SOCKET_TIMEOUT = 2
+	const val ERROR_REQUEST_TIMEOUT = 3
+
+	// How responses passed to Callbacks.onResponse were produced by the cache, which must be kept in sync with the native side
+	const val CACHE_UNCACHED = 0
+	const val CACHE_MISS = 1
+	const val CACHE_HIT = 2
+	const val CACHE_REVALIDATED = 3
+
 	abstract class Callbacks {
-		abstract fun onResponse(version: String, code: Int, headers: Headers)
-		abstract fun onError(message: String)
+		/**
+		 * Called once the response headers have been received, possibly before [executeRequest] has returned,
+		 * so the body has to be read with the [requestId] passed here.
+		 */
+		abstract fun onResponse(
+			requestId: Int,
+			versionMajor: Int,
+			versionMinor: Int,
+			code: Int,
+			headers: Headers,
+			attempts: Int,
+			queueTimeMillis: Long,
+			cacheStatus: Int,
+		)
+		abstract fun onError(kind: Int, message: String)
+	}
+
+	abstract class DownloadCallbacks {
+		abstract fun onComplete(versionMajor: Int, versionMinor: Int, code: Int, headers: Headers, size: Long)
+		abstract fun onError(kind: Int, message: String)
+	}
+
+	abstract class PreconnectCallbacks {
+		abstract fun onConnected()
+		abstract fun onError(kind: Int, message: String)
 	}
 }
@@ -0,0 +1,110 @@
+package dev.rushii.ktor_impersonate.internal
+
+import dev.rushii.ktor_impersonate.NativeFormDataContent
+import dev.rushii.ktor_impersonate.NativeFormPart
+import io.ktor.client.request.HttpRequestData
+import io.ktor.http.Headers
+import io.ktor.http.HttpHeaders
+import io.ktor.http.content.OutgoingContent
+import io.ktor.utils.io.ByteReadChannel
+import io.ktor.utils.io.readAvailable
+import io.ktor.utils.io.writer
+import kotlinx.coroutines.Dispatchers
+import kotlinx.coroutines.IO
+import kotlinx.coroutines.coroutineScope
+import kotlinx.coroutines.withContext
+
+/**
+ * The size of the chunks that streaming request bodies are sent to the native side in.
+ */
+private const val BODY_CHUNK_SIZE = 16 * 1024
+
+/**
+ * Merges the headers of a request with the headers describing its body.
+ * Unlike Ktor's `mergeHeaders`, this does not add a default `User-Agent`, leaving that up to the preset.
+ */
+internal fun HttpRequestData.mergedHeaders(): Headers = Headers.build {
+	appendAll(headers)
+	appendAll(body.headers)
+
+	body.contentType?.let { set(HttpHeaders.ContentType, it.toString()) }
+	body.contentLength?.let { set(HttpHeaders.ContentLength, it.toString()) }
+}
+
+/**
+ * Gets the bytes of a request body if it is immediately available,
+ * otherwise null if there is no body or the body needs to be streamed with [writeRequestBody].
+ */
+internal fun OutgoingContent.immediateBytes(): ByteArray? = when (this) {
+	is OutgoingContent.ByteArrayContent -> bytes()
+	is OutgoingContent.ContentWrapper -> delegate().immediateBytes()
+	else -> null
+}
+
+/**
+ * Whether this request body needs to be streamed to the native side with [writeRequestBody].
+ */
+internal fun OutgoingContent.isStreaming(): Boolean = when (this) {
+	is OutgoingContent.ReadChannelContent,
+	is OutgoingContent.WriteChannelContent -> true
+	is OutgoingContent.ContentWrapper -> delegate().isStreaming()
+	else -> false
+}
+
+/**
+ * Streams a request body to an active native request in chunks.
+ * The native side only accepts a new chunk once the previous one has been sent,
+ * so the body is read (and any progress listeners are notified) at the pace it is uploaded.
+ */
+internal suspend fun writeRequestBody(requestId: Int, content: OutgoingContent): Unit = coroutineScope {
+	val channel = when (content) {
+		is OutgoingContent.ReadChannelContent -> content.readFrom()
+		is OutgoingContent.WriteChannelContent -> writer { content.writeTo(channel) }.channel
+		is OutgoingContent.ContentWrapper -> return@coroutineScope writeRequestBody(requestId, content.delegate())
+		else -> error("Request body of type ${content::class} cannot be streamed")
+	}
+
+	withContext(Dispatchers.IO) {
+		writeRequestBodyChannels(requestId, listOf { channel }, separateParts = false)
+	}
+}
+
+/**
+ * Streams the parts of a [NativeFormDataContent] that are provided by the JVM to an active native request,
+ * in the order that they are encoded natively.
+ */
+internal suspend fun writeFormDataParts(requestId: Int, content: NativeFormDataContent) {
+	val providers = content.parts.filterIsInstance<NativeFormPart.Channel>().map { it.provider }
+
+	withContext(Dispatchers.IO) {
+		writeRequestBodyChannels(requestId, providers, separateParts = true)
+	}
+}
+
+/**
+ * Sends the contents of each channel in order, and then finishes the request body.
+ * When [separateParts] is set, an empty chunk is sent after each channel to mark the end of its part.
+ */
+private suspend fun writeRequestBodyChannels(requestId: Int, providers: List<() -> ByteReadChannel>, separateParts: Boolean) {
+	val buffer = ByteArray(BODY_CHUNK_SIZE)
+
+	try {
+		for (provider in providers) {
+			val channel = provider()
+			while (true) {
+				val count = channel.readAvailable(buffer)
+				if (count < 0) break
+
+				// Request is no longer active
+				if (!NativeEngine.writeRequestBody(requestId, buffer, count)) return
+			}
+
+			if (separateParts && !NativeEngine.writeRequestBody(requestId, buffer, 0)) return
+		}
+	} catch (t: Throwable) {
+		NativeEngine.closeRequestBody(requestId, t.message ?: t.toString())
+		throw t
+	}
+
+	NativeEngine.closeRequestBody(requestId, null)
+}
@@ -0,0 +1,63 @@
+package dev.rushii.ktor_impersonate.internal
+
+import dev.rushii.ktor_impersonate.HeaderOrderAttributeKey
+import dev.rushii.ktor_impersonate.HttpVersionAttributeKey
+import dev.rushii.ktor_impersonate.LocalAddressAttributeKey
+import dev.rushii.ktor_impersonate.NetworkInterfaceAttributeKey
+import dev.rushii.ktor_impersonate.UnixSocketAttributeKey
+import io.ktor.client.plugins.HttpTimeoutCapability
+import io.ktor.client.plugins.HttpTimeoutConfig
+import io.ktor.client.request.HttpRequestData
+
+/**
+ * Per-request options that are read by the native side when executing a request.
+ */
+internal class RequestConfig(
+	/** Header names in the order they should be sent, or null to use the client's order. */
+	val headerOrder: Array<String>?,
+	/** [HttpTimeoutConfig.requestTimeoutMillis], overriding the client's request timeout. */
+	val requestTimeoutMillis: Long?,
+	/** [HttpTimeoutConfig.connectTimeoutMillis], overriding the client's connect timeout. */
+	val connectTimeoutMillis: Long?,
+	/** [HttpTimeoutConfig.socketTimeoutMillis], applied to periods without any part of the request or response being transferred. */
+	val socketTimeoutMillis: Long?,
+	/** The local IP address to connect from, overriding the client's. */
+	val localAddress: String?,
+	/** The network interface to bind to, overriding the client's. */
+	val networkInterface: String?,
+	/** The name of the [dev.rushii.ktor_impersonate.HttpVersionPolicy] to use, overriding the client's. */
+	val httpVersionName: String?,
+	/** The path of the Unix domain socket to send the request over, overriding the client's. */
+	val unixSocket: String?,
+) {
+	companion object {
+		/** No per-request overrides, using the client's config as-is. */
+		val Empty = RequestConfig(
+			headerOrder = null,
+			requestTimeoutMillis = null,
+			connectTimeoutMillis = null,
+			socketTimeoutMillis = null,
+			localAddress = null,
+			networkInterface = null,
+			httpVersionName = null,
+			unixSocket = null,
+		)
+
+		fun from(data: HttpRequestData): RequestConfig {
+			val timeout = data.getCapabilityOrNull(HttpTimeoutCapability)
+
+			return RequestConfig(
+				headerOrder = data.attributes.getOrNull(HeaderOrderAttributeKey)?.toTypedArray(),
+				requestTimeoutMillis = timeout?.requestTimeoutMillis?.finiteTimeout(),
+				connectTimeoutMillis = timeout?.connectTimeoutMillis?.finiteTimeout(),
+				socketTimeoutMillis = timeout?.socketTimeoutMillis?.finiteTimeout(),
+				localAddress = data.attributes.getOrNull(LocalAddressAttributeKey),
+				networkInterface = data.attributes.getOrNull(NetworkInterfaceAttributeKey),
+				httpVersionName = data.attributes.getOrNull(HttpVersionAttributeKey)?.name,
+				unixSocket = data.attributes.getOrNull(UnixSocketAttributeKey),
+			)
+		}
+
+		private fun Long.finiteTimeout(): Long? = takeUnless { it == HttpTimeoutConfig.INFINITE_TIMEOUT_MS }
+	}
+}
@@ -1,7 +1,11 @@
 package dev.rushii.ktor_impersonate.internal
 
+import io.ktor.client.network.sockets.SocketTimeoutException
+import io.ktor.http.Headers
 import kotlinx.io.Buffer
 import kotlinx.io.RawSource
+import kotlinx.io.UnsafeIoApi
+import kotlinx.io.unsafe.UnsafeBufferOperations
 
 /**
  * Collects the response body of a currently active request from the native side.
@@ -10,12 +14,52 @@ internal class ResponseSource(
 	/** Used by the native side */
 	@Suppress("unused")
 	private val requestId: Int,
+	/** Receives the trailers of the response once its body has ended, if any were received. */
+	private val trailers: ResponseTrailers? = null,
 ) : RawSource {
 	external fun init()
 	external override fun close()
-	external override fun readAtMostTo(sink: Buffer, byteCount: Long): Long
+
+	@Suppress("unused") // Called by the native side
+	private fun onTrailers(headers: Headers) {
+		trailers?.headers = headers
+	}
+
+	/**
+	 * Copies the next part of the response body into [array], blocking until at least one byte is available.
+	 * @return The amount of bytes copied, -1 if the body has ended, or [READ_TIMED_OUT].
+	 */
+	private external fun readInto(array: ByteArray, offset: Int, length: Int): Int
+
+	// The native side copies directly into the sink's tail segment,
+	// avoiding both allocating an intermediate array per chunk and calling back into the JVM to write it.
+	@OptIn(UnsafeIoApi::class)
+	override fun readAtMostTo(sink: Buffer, byteCount: Long): Long {
+		require(byteCount >= 0) { "byteCount ($byteCount) < 0" }
+		if (byteCount == 0L) return 0
+
+		var exhausted = false
+		val count = UnsafeBufferOperations.writeToTail(sink, 1) { bytes, startIndex, endIndex ->
+			val length = minOf(byteCount, (endIndex - startIndex).toLong()).toInt()
+			when (val count = readInto(bytes, startIndex, length)) {
+				READ_TIMED_OUT -> throw SocketTimeoutException("Socket timeout has expired while reading the response body")
+				-1 -> {
+					exhausted = true
+					0
+				}
+				else -> count
+			}
+		}
+
+		return if (exhausted) -1 else count.toLong()
+	}
 
 	init {
 		init()
 	}
+
+	private companion object {
+		/** Returned by [readInto] when no part of the body was received within the socket timeout. */
+		const val READ_TIMED_OUT = -2
+	}
 }
@@ -0,0 +1,12 @@
+package dev.rushii.ktor_impersonate.internal
+
+import io.ktor.http.Headers
+import kotlin.concurrent.Volatile
+
+/**
+ * Holds the trailers of a response, which are only set once its body has been fully read.
+ */
+internal class ResponseTrailers {
+	@Volatile
+	var headers: Headers? = null
+}